@@ -7,6 +7,7 @@ use crate::interpreter::context::Context;
 use crate::interpreter::error::InterpreterError;
 use crate::macros::get_enum;
 use crate::primitives::any::Any;
+use crate::span::Span;
 
 get_enum! {
     /// Defines what an expression can be
@@ -19,8 +20,8 @@ get_enum! {
         /// Raw identifier like "+" or a defined variable, basically anything not being a primitive
         /// and not being quoted
         Ident(&'a str),
-        /// Quoted items
-        RawQuoted(Box<Expr<'a>>),
+        /// Data read from a quoted form or a self quoting literal like `#(1 2)`
+        Datum(Box<Any<'a>>),
     }
 }
 
@@ -32,7 +33,7 @@ impl<'a> Expr<'a> {
             Self::Ident(ident) => {
                 Self::Ident(Box::leak(ident.to_string().into_boxed_str()))
             },
-            Self::RawQuoted(r) => Self::RawQuoted(Box::new(r.make_static()))
+            Self::Datum(d) => Self::Datum(Box::new(d.make_static()))
         };
 
         unsafe {
@@ -41,24 +42,70 @@ impl<'a> Expr<'a> {
     }
 }
 
+/// Delimiters a form was written with, `(a b)`, `[a b]` or `{a b}` mean the same but are kept
+/// to show forms the way they were written.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum Shape {
+    #[default]
+    Paren,
+    Bracket,
+    Brace
+}
+
+impl Shape {
+    /// Shape of the form `token` opens, vector and hash table literals are closed by a
+    /// parenthesis.
+    pub fn opened_by(token: &Token<'_>) -> Option<Self> {
+        match token {
+            Token::OpenParen | Token::OpenVector | Token::OpenHash => Some(Self::Paren),
+            Token::OpenBracket => Some(Self::Bracket),
+            Token::OpenBraces => Some(Self::Brace),
+            _ => None
+        }
+    }
+
+    pub fn open(self) -> char {
+        match self {
+            Self::Paren => '(',
+            Self::Bracket => '[',
+            Self::Brace => '{'
+        }
+    }
+
+    pub fn close(self) -> char {
+        match self {
+            Self::Paren => ')',
+            Self::Bracket => ']',
+            Self::Brace => '}'
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Tree<'a> {
     pub node: Option<Box<Expr<'a>>>,
     pub children: Vec<Expr<'a>>,
+    /// Where the form was read from, `None` for forms built by the interpreter
+    pub span: Option<Span>,
+    pub shape: Shape
 }
 
 impl<'a> Tree<'a> {
     pub fn new() -> Self {
         Self {
             node: None,
-            children: Default::default()
+            children: Default::default(),
+            span: None,
+            shape: Shape::Paren
         }
     }
 
     pub fn with_capacity(capacity: usize) -> Self {
         Self {
             node: None,
-            children: Vec::with_capacity(capacity)
+            children: Vec::with_capacity(capacity),
+            span: None,
+            shape: Shape::Paren
         }
     }
 
@@ -82,6 +129,8 @@ impl<'a> Tree<'a> {
         let mut new_tree = Tree::new();
 
         new_tree.node = self.node.map(|m| Box::new(m.make_static()));
+        new_tree.span = self.span;
+        new_tree.shape = self.shape;
 
         for item in self.children {
             new_tree.push(item.make_static());
@@ -90,22 +139,25 @@ impl<'a> Tree<'a> {
         new_tree
     }
 
-    pub fn as_vec(&self) -> Vec<Expr<'a>> {
-        let mut vec = Vec::with_capacity(self.children.len() + if self.node.is_some() {
-            1
-        } else {
-            0
-        });
+    pub fn into_vec(self) -> Vec<Expr<'a>> {
+        let mut vec = Vec::with_capacity(self.children.len() + 1);
 
-        if let Some(node) = &self.node {
-            vec.push(*node.clone());
+        if let Some(node) = self.node {
+            vec.push(*node);
         }
 
-        for item in &self.children {
-            vec.push(item.clone());
+        vec.extend(self.children);
+        vec
+    }
+
+    pub fn from_vec(items: Vec<Expr<'a>>) -> Self {
+        let mut tree = Tree::with_capacity(items.len());
+
+        for item in items {
+            tree.push_auto(item);
         }
 
-        vec
+        tree
     }
 }
 
@@ -114,18 +166,16 @@ impl InterpreterDisplay for Expr<'_> {
         match self {
             Self::Ident(ident) => write!(f, "{ident}"),
             Self::Primitive(p) => p.fmt(f, interpreter),
-            Self::RawQuoted(r) => {
-                write!(f, "'")?;
-                r.raw_fmt(f, interpreter)
-            },
+            Self::Datum(d) => d.fmt(f, interpreter),
             Self::Parenthesized(p) => p.fmt(f, interpreter),
         }
     }
 }
 
+/// Code is shown with the delimiters it was written with, unlike quoted data, which is a list.
 impl InterpreterDisplay for Tree<'_> {
     fn fmt(&self, f: &mut dyn fmt::Write, interpreter: &Interpreter<'_>) -> fmt::Result {
-        write!(f, "(")?;
+        write!(f, "{}", self.shape.open())?;
         if let Some(node) = &self.node {
             node.fmt(f, interpreter)?;
         }
@@ -135,7 +185,7 @@ impl InterpreterDisplay for Tree<'_> {
             item.fmt(f, interpreter)?;
         }
 
-        write!(f, ")")
+        write!(f, "{}", self.shape.close())
     }
 }
 
@@ -161,10 +211,7 @@ impl RawDisplay for Expr<'_> {
         match self {
             Self::Ident(i) => write!(f, "{i}"),
             Self::Parenthesized(t) => t.raw_fmt(f, interpreter),
-            Self::RawQuoted(t) => {
-                write!(f, "'")?;
-                t.raw_fmt(f, interpreter)
-            },
+            Self::Datum(d) => d.fmt(f, interpreter),
             Self::Primitive(p) => p.raw_fmt(f, interpreter),
         }
     }
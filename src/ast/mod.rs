@@ -1,8 +1,12 @@
+use std::fmt;
+
 use thiserror::Error;
 
-use crate::lexer::Token;
+use crate::lexer::{LocatedToken, Token};
+use crate::reader::{Reader, Unclosed};
+use crate::span::{Located, Span};
 
-use self::expr::{Expr, Tree};
+use self::expr::{Expr, Shape, Tree};
 
 pub mod expr;
 
@@ -10,111 +14,165 @@ pub mod expr;
 pub enum AstError {
     #[error("Missing closing delimiter")]
     MissingClosingDelimiter,
+    /// Form closed with a delimiter not matching the one it was opened with, at `opened`.
+    #[error("mismatched delimiter: expected {expected} but found {found} (opened at {opened})")]
+    MismatchedDelimiter {
+        expected: char,
+        found: char,
+        opened: Span
+    },
+    #[error("Unexpected `{0}`")]
+    UnexpectedClosingDelimiter(char),
     #[error("Invalid expression")]
     InvalidExpression,
-    #[error("Missing token")]
-    MissingToken
+    #[error("Expected an expression after the quote")]
+    DanglingQuote,
+    #[error("Expected a datum after `#&`")]
+    DanglingBox,
+    #[error("Expected a key and a value in a hash table entry")]
+    InvalidHashEntry
+}
+
+/// Every syntax error found in a source, in the order they appear.
+#[derive(Debug)]
+pub struct AstErrors(pub Vec<Located<AstError>>);
+
+impl fmt::Display for AstErrors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (idx, error) in self.0.iter().enumerate() {
+            if idx > 0 {
+                writeln!(f)?;
+            }
+
+            write!(f, "{error}")?;
+        }
+
+        Ok(())
+    }
 }
 
+impl std::error::Error for AstErrors {}
+
 #[derive(Debug)]
 pub struct Ast<'a> {
     pub inner: Box<[Expr<'a>]>,
+    /// Span of each top level expression
+    pub spans: Box<[Span]>
 }
 
 impl<'a> Ast<'a> {
     pub fn empty() -> Self {
         Self {
-            inner: Box::from([])
+            inner: Box::from([]),
+            spans: Box::from([])
         }
     }
+}
 
-    fn parse_quoted<I: Iterator<Item = Token<'a>>>(iter: &mut I) -> Result<Expr<'a>, AstError> {
-        let next = iter.next().ok_or(AstError::MissingToken)?;
+/// Parses the items of the form opened by `open` up to its closing delimiter.
+fn parse_tree<'a>(reader: &mut Reader<'a>, open: LocatedToken<'a>) -> Result<Tree<'a>, Unclosed> {
+    let mut tree = Tree::new();
+    tree.shape = Shape::opened_by(&open.token).unwrap();
 
-        let inner = match next {
-            Token::OpenBraces | Token::OpenBracket | Token::OpenParen 
-                => Expr::Parenthesized(Self::parse_tree(iter)?),
-            other => Self::parse_token(other).ok_or(AstError::InvalidExpression)?
-        };
+    loop {
+        let token = reader.next_in_form(&open.span)?;
 
-        Ok(Expr::RawQuoted(Box::new(inner)))
+        if reader.closes(&open, &token) {
+            tree.span = Some(open.span.to(&token.span));
+            return Ok(tree);
+        }
+
+        if let Some((expr, _)) = parse_item(reader, token)? {
+            tree.push_auto(expr);
+        }
     }
+}
 
-    fn parse_token(token: Token<'a>) -> Option<Expr<'a>> {
-        Some(match token {
-            Token::Ident(ident) => Expr::Ident(ident),
-            Token::Primitive(prim) => Expr::Primitive(prim),
-            _ => return None
+/// Parses the expression starting at `token`, which isn't a closing delimiter. Quoted forms and
+/// literals like `#(1 2)` are data, which the reader reads.
+fn parse_item<'a>(reader: &mut Reader<'a>, token: LocatedToken<'a>) -> Result<Option<(Expr<'a>, Span)>, Unclosed> {
+    let datum = match &token.token {
+        Token::OpenParen | Token::OpenBracket | Token::OpenBraces => {
+            let tree = parse_tree(reader, token)?;
+            let span = tree.span.clone().unwrap();
+            return Ok(Some((Expr::Parenthesized(tree), span)));
+        },
+        Token::SingleQuote => reader.read_prefixed(&token.span, AstError::DanglingQuote)?,
+        Token::OpenVector | Token::OpenHash | Token::BoxPrefix => reader.read_item(token)?,
+        _ => return Ok(match token.token {
+            Token::Ident(ident) => Some((Expr::Ident(ident), token.span)),
+            Token::Primitive(prim) => Some((Expr::Primitive(prim), token.span)),
+            _ => None
         })
-    }
+    };
 
-    fn parse_tree<I>(iter: &mut I) -> Result<Tree<'a>, AstError> 
-    where
-        I: Iterator<Item = Token<'a>>
-    {
-        let mut tree = Tree::new();
-
-        while let Some(token) = iter.next() {
-            let parsed = match token {
-                Token::OpenBraces | Token::OpenBracket | Token::OpenParen => {
-                    Some(Expr::Parenthesized(Self::parse_tree(iter)?))
-                },
-                Token::CloseBraces | Token::CloseBracket | Token::CloseParen => {
-                    return Ok(tree) // close expression
-                },
-                Token::SingleQuote => Some(Self::parse_quoted(iter)?),
-                other => Self::parse_token(other)  
-            };
-
-            if let Some(p) = parsed {
-                tree.push_auto(p);
-            }
-        }
+    Ok(datum.map(|(datum, span)| (Expr::Datum(Box::new(datum)), span)))
+}
 
-        Err(AstError::MissingClosingDelimiter)
-    }
+impl<'a> TryFrom<Vec<LocatedToken<'a>>> for Ast<'a> {
+    type Error = AstErrors;
+
+    fn try_from(value: Vec<LocatedToken<'a>>) -> Result<Self, Self::Error> {
+        let mut reader = Reader::new(value);
+        let (inner, spans) = reader.top_level(parse_item).into_iter().unzip::<_, _, Vec<_>, Vec<_>>();
+        reader.finish()?;
 
-    fn parse_expr<I>(iter: &mut I) -> Result<Option<Expr<'a>>, AstError> 
-    where
-        I: Iterator<Item = Token<'a>>
-    {
-        let Some(token) = iter.next() else { return Ok(None); };
-
-        Ok(match token {
-            Token::OpenBraces | Token::OpenBracket | Token::OpenParen
-                => Some(Expr::Parenthesized(Self::parse_tree(iter)?)),
-            Token::SingleQuote => Some(Expr::RawQuoted(Box::new({
-                if let Some(e) = Self::parse_expr(iter)? {
-                    e
-                } else {
-                    return Ok(None);
-                }
-            }))),
-            other => Self::parse_token(other)
+        Ok(Ast {
+            inner: inner.into_boxed_slice(),
+            spans: spans.into_boxed_slice()
         })
     }
+}
 
-    fn parse<I>(mut iter: I) -> Result<Vec<Expr<'a>>, AstError>
-    where
-        I: ExactSizeIterator<Item = Token<'a>>
-    {
-        // preallocate a fourth of the size of the iterator, this is an arbitrary measure
-        let mut out = Vec::with_capacity(iter.len() / 4);
+#[test]
+fn test_locations() {
+    use crate::interpreter::{eval_source, Interpreter};
+    use crate::lexer::Lexer;
 
-        while let Some(next) = Self::parse_expr(&mut iter)? {
-            out.push(next);
-        }
+    let parse = |source| Ast::try_from(Lexer::with_file(source, "main.rkt").parse().unwrap());
 
-        Ok(out)
-    }
-}
+    let ast = parse("(define x 1)\n  '(a\n b) x").unwrap();
+    let spans = ast.spans.iter().map(|s| (s.line, s.column, s.range.clone())).collect::<Vec<_>>();
+    assert_eq!(spans, vec![(1, 1, 0..12), (2, 3, 15..22), (3, 5, 23..24)]);
+    assert_eq!(ast.inner[0].get_parenthesized().unwrap().span.as_ref().unwrap().to_string(), "main.rkt:1:1");
 
-impl<'a> TryFrom<Vec<Token<'a>>> for Ast<'a> {
-    type Error = AstError;
+    let unclosed = parse("(list 1)\n(list (+ 1 2)").unwrap_err();
+    assert_eq!(unclosed.to_string(), "main.rkt:2:1: Missing closing delimiter");
 
-    fn try_from(value: Vec<Token<'a>>) -> Result<Self, Self::Error> {
-        Ok(Self {
-            inner: Self::parse(value.into_iter())?.into_boxed_slice()
-        })
-    }
+    let lexer = Lexer::with_file("(list\n  \"open)", "main.rkt").parse().unwrap_err();
+    assert_eq!(lexer.to_string(), "main.rkt:2:3: Unterminated string literal");
+
+    let runtime = Interpreter::new(parse("(define (f x)\n  (+ 1 (car x)))\n(f 5)").unwrap()).run().unwrap_err();
+    assert!(runtime.to_string().starts_with("main.rkt:2:8: "), "{runtime}");
+
+    // lowered forms keep the span of the form they come from
+    let lowered = eval_source("(cond\n  [#t (undefined-fn 1)])").unwrap_err();
+    assert_eq!(lowered.to_string(), "2:7: Undefined function: undefined-fn");
+    assert_eq!(eval_source("(+ 1 2)\n   nope").unwrap_err().to_string(), "2:4: Unknown identifier: nope");
+}
+
+#[test]
+fn test_error_recovery() {
+    use crate::lexer::Lexer;
+
+    let parse = |source| Ast::try_from(Lexer::new(source).parse().unwrap());
+
+    let source = "(define (f x)
+  (+ x 1)
+(define y [list 1 2)
+)
+(display 'x)
+(g ')
+'";
+
+    assert_eq!(parse(source).unwrap_err().to_string(), "\
+1:1: Missing closing delimiter
+3:20: mismatched delimiter: expected ] but found ) (opened at 3:11)
+6:4: Expected an expression after the quote
+7:1: Expected an expression after the quote");
+    assert_eq!(parse("(list 1))\n(list 2 .)\n.").unwrap_err().to_string(), "1:9: Unexpected `)`\n3:1: Invalid expression");
+
+    // forms are only split at the first column when they're never closed
+    assert_eq!(parse("(list 1\n(+ 1 2))\n''a").unwrap().inner.len(), 2);
+    assert_eq!(parse("(a\n(b\n(c").unwrap_err().0.len(), 3);
 }
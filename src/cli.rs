@@ -1,4 +1,6 @@
-use clap::{Parser, Subcommand};
+use std::io::IsTerminal;
+
+use clap::{Parser, Subcommand, ValueEnum};
 
 #[derive(Parser)]
 #[command(version = "0.1")]
@@ -7,13 +9,37 @@ use clap::{Parser, Subcommand};
 #[command(long_about = "A simple racket interpreter made to learn to write interpreters")]
 pub struct Cli {
     #[command(subcommand)]
-    pub command: Option<SubCommands>
+    pub command: Option<SubCommands>,
+    /// Whether errors are shown with colors
+    #[arg(long, value_enum, global = true, default_value_t = ColorChoice::Auto)]
+    pub color: ColorChoice
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum ColorChoice {
+    Auto,
+    Always,
+    Never
+}
+
+impl ColorChoice {
+    /// `auto` colors errors when stderr is a terminal and `NO_COLOR` isn't set.
+    pub fn enabled(self) -> bool {
+        match self {
+            Self::Auto => std::io::stderr().is_terminal() && std::env::var_os("NO_COLOR").is_none(),
+            Self::Always => true,
+            Self::Never => false
+        }
+    }
 }
 
 #[derive(Subcommand)]
 pub enum SubCommands {
     Repl,
     Run {
-        filename: String
+        filename: String,
+        /// Arguments returned by `current-command-line-arguments`
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>
     }
 }
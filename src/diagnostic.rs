@@ -0,0 +1,231 @@
+use std::fmt::Write;
+
+use crate::ast::AstError;
+use crate::interpreter::error::InterpreterError;
+use crate::interpreter::stack_trace::StackTrace;
+use crate::interpreter::Interpreter;
+use crate::lexer::LexerError;
+use crate::span::{Located, Span};
+
+const RED: &str = "\x1b[1;31m";
+const BLUE: &str = "\x1b[1;34m";
+const CYAN: &str = "\x1b[1;36m";
+const BOLD: &str = "\x1b[1m";
+const RESET: &str = "\x1b[0m";
+
+/// Error ready to be shown to the user, pointing into the source it was raised from.
+#[derive(Debug)]
+pub struct Diagnostic {
+    pub kind: &'static str,
+    pub message: String,
+    pub span: Option<Span>,
+    pub hint: Option<String>,
+    /// Other span the error refers to, with what it is
+    pub note: Option<(Span, &'static str)>,
+    /// Calls the error went through
+    pub stack_trace: Option<StackTrace>
+}
+
+impl Diagnostic {
+    pub fn lexer(error: &Located<LexerError>) -> Self {
+        Self {
+            kind: "read error",
+            message: error.error.to_string(),
+            span: Some(error.span.clone()),
+            hint: None,
+            note: None,
+            stack_trace: None
+        }
+    }
+
+    pub fn ast(error: &Located<AstError>) -> Self {
+        let note = match &error.error {
+            AstError::MismatchedDelimiter { opened, .. } => Some((opened.clone(), "opened here")),
+            _ => None
+        };
+
+        Self {
+            kind: "syntax error",
+            message: error.error.to_string(),
+            span: Some(error.span.clone()),
+            hint: None,
+            note,
+            stack_trace: None
+        }
+    }
+
+    /// Diagnostic for an error raised by `interpreter`, unbound identifiers get a hint with the
+    /// closest native, defined or local name.
+    pub fn runtime(error: &InterpreterError, interpreter: &Interpreter<'_>) -> Self {
+        let hint = match error.unlocated() {
+            InterpreterError::UndefinedFunction { name, locals } | InterpreterError::UnknownIdentifier { name, locals } => {
+                let mut names = interpreter.native_vars().names().collect::<Vec<_>>();
+                names.extend(interpreter.vars().table.keys().map(String::as_str));
+                names.extend(locals.iter().map(String::as_str));
+
+                closest(name, names).map(|name| format!("did you mean `{name}`?"))
+            },
+            _ => None
+        };
+
+        Self {
+            kind: error.kind(),
+            message: error.message(),
+            span: error.span().cloned(),
+            hint,
+            note: None,
+            stack_trace: error.stack_trace().cloned()
+        }
+    }
+
+    /// Renders the diagnostic along with the lines of `source` it points to, using ANSI colors
+    /// if `colored` is set.
+    pub fn render(&self, source: &str, colored: bool) -> String {
+        let paint = |style: &str, text: &str| if colored {
+            format!("{style}{text}{RESET}")
+        } else {
+            text.to_string()
+        };
+
+        let mut out = String::new();
+        writeln!(out, "{} {}", paint(RED, &format!("{}:", self.kind)), paint(BOLD, &self.message)).unwrap();
+
+        let digits = self.span.iter()
+            .chain(self.note.iter().map(|(span, _)| span))
+            .map(|span| span.line.to_string().len())
+            .max()
+            .unwrap_or(0);
+        let gutter = " ".repeat(digits);
+
+        // line of the source under the span, with a marker under the spanned text
+        let snippet = |out: &mut String, span: &Span, marker: String| {
+            let line = source.lines().nth(span.line as usize - 1).unwrap_or("");
+
+            // keep tabs so the marker lines up with the source
+            let indent = line.chars()
+                .take(span.column as usize - 1)
+                .map(|c| if c == '\t' { '\t' } else { ' ' })
+                .collect::<String>();
+
+            writeln!(out, "{} {line}", paint(BLUE, &format!("{:>digits$} |", span.line))).unwrap();
+            writeln!(out, "{gutter} {} {indent}{marker}", paint(BLUE, "|")).unwrap();
+        };
+
+        if let Some(span) = &self.span {
+            writeln!(out, "{gutter}{} {span}", paint(BLUE, "-->")).unwrap();
+            writeln!(out, "{gutter} {}", paint(BLUE, "|")).unwrap();
+            snippet(&mut out, span, paint(RED, &"^".repeat(underline_width(source, span))));
+        }
+
+        if let Some((span, label)) = &self.note {
+            snippet(&mut out, span, paint(BLUE, &format!("- {label}")));
+        }
+
+        if let Some(hint) = &self.hint {
+            writeln!(out, "{gutter} {} {} {hint}", paint(BLUE, "="), paint(CYAN, "hint:")).unwrap();
+        }
+
+        if let Some(trace) = &self.stack_trace {
+            writeln!(out, "{trace}").unwrap();
+        }
+
+        out
+    }
+}
+
+/// Characters to underline for `span`, forms spanning several lines are only underlined up to
+/// the end of the first one.
+fn underline_width(source: &str, span: &Span) -> usize {
+    source.get(span.range.clone())
+        .and_then(|text| text.lines().next())
+        .map(|text| text.chars().count())
+        .unwrap_or(0)
+        .max(1)
+}
+
+/// Candidate closest to `name`, if it's close enough to be what a typo meant.
+fn closest<'c>(name: &str, candidates: impl IntoIterator<Item = &'c str>) -> Option<&'c str> {
+    // a third of the name can differ, so names shorter than three characters get no hint
+    let limit = name.chars().count() / 3;
+
+    candidates.into_iter()
+        .map(|candidate| (edit_distance(name, candidate), candidate))
+        .filter(|(distance, _)| (1..=limit).contains(distance))
+        .min()
+        .map(|(_, candidate)| candidate)
+}
+
+/// Levenshtein distance between `a` and `b`, counting characters.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut row = (0..=b.len()).collect::<Vec<_>>();
+
+    for (i, left) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+
+        for (j, right) in b.iter().enumerate() {
+            let above = row[j + 1];
+            row[j + 1] = (above + 1).min(row[j] + 1).min(diagonal + usize::from(left != *right));
+            diagonal = above;
+        }
+    }
+
+    row[b.len()]
+}
+
+#[test]
+fn test_diagnostics() {
+    use crate::ast::Ast;
+    use crate::lexer::Lexer;
+
+    let source = "(define (greet name)\n\t(string-apend \"hi \" name))\n(greet \"you\")";
+    let ast = Ast::try_from(Lexer::with_file(source, "greet.rkt").parse().unwrap()).unwrap();
+    let interpreter = Interpreter::new(ast);
+    let error = interpreter.run().unwrap_err();
+
+    assert_eq!(Diagnostic::runtime(&error, &interpreter).render(source, false), "\
+unbound identifier: string-apend: undefined
+ --> greet.rkt:2:2
+  |
+2 | \t(string-apend \"hi \" name))
+  | \t^^^^^^^^^^^^^^^^^^^^^^^^^
+  = hint: did you mean `string-append`?
+  context...:
+   greet.rkt:3:1 greet
+");
+
+    let local = "(let ([width 2])\n  (* wdth wdth))";
+    let ast = Ast::try_from(Lexer::new(local).parse().unwrap()).unwrap();
+    let interpreter = Interpreter::new(ast);
+    let error = interpreter.run().unwrap_err();
+    let rendered = Diagnostic::runtime(&error, &interpreter).render(local, false);
+    assert!(rendered.starts_with("unbound identifier: wdth: undefined\n"));
+    assert!(rendered.contains("= hint: did you mean `width`?"));
+
+    let short = "(let ([y 1]) (+ x y))";
+    let ast = Ast::try_from(Lexer::new(short).parse().unwrap()).unwrap();
+    let interpreter = Interpreter::new(ast);
+    let error = interpreter.run().unwrap_err();
+    assert!(!Diagnostic::runtime(&error, &interpreter).render(short, false).contains("hint"));
+
+    let unclosed = Ast::try_from(Lexer::new("(list 1\n  (+ 2 3)").parse().unwrap()).unwrap_err();
+    let colored = Diagnostic::ast(&unclosed.0[0]).render("(list 1\n  (+ 2 3)", true);
+    assert!(colored.starts_with("\x1b[1;31msyntax error:\x1b[0m \x1b[1mMissing closing delimiter\x1b[0m\n"));
+    assert!(colored.contains("1:1"));
+
+    let mismatched = Ast::try_from(Lexer::new("(list 1\n  [+ 2 3))").parse().unwrap()).unwrap_err();
+    assert_eq!(Diagnostic::ast(&mismatched.0[0]).render("(list 1\n  [+ 2 3))", false), "\
+syntax error: mismatched delimiter: expected ] but found ) (opened at 2:3)
+ --> 2:9
+  |
+2 |   [+ 2 3))
+  |         ^
+2 |   [+ 2 3))
+  |   - opened here
+");
+
+    assert_eq!(closest("lenght", ["length", "list", "let"]), Some("length"));
+    assert_eq!(closest("x", ["list", "car", "*"]), None);
+    assert_eq!(edit_distance("kitten", "sitting"), 3);
+}
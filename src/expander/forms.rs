@@ -0,0 +1,336 @@
+use crate::ast::expr::{Expr, Tree};
+use crate::reader;
+
+use super::{begin, form, void, ExpandError, Expander};
+
+fn bad_syntax(form: &'static str, reason: &'static str) -> ExpandError {
+    ExpandError::BadSyntax {
+        form,
+        reason
+    }
+}
+
+fn is_ident(expr: &Expr<'_>, ident: &str) -> bool {
+    expr.get_ident().map(|i| *i == ident).unwrap_or(false)
+}
+
+/// Splits a clause like `[test body ...]` into its first item and the rest of them.
+fn clause<'a>(
+    form: &'static str,
+    clause: Expr<'a>
+) -> Result<(Expr<'a>, Vec<Expr<'a>>), ExpandError> {
+    let Expr::Parenthesized(tree) = clause else {
+        return Err(bad_syntax(form, "clause is not a parenthesized expression"));
+    };
+
+    let mut items = tree.into_vec().into_iter();
+    let first = items.next().ok_or(bad_syntax(form, "empty clause"))?;
+
+    Ok((first, items.collect()))
+}
+
+/// Parses a binding list like `([name value] ...)`.
+pub fn bindings<'a>(
+    form: &'static str,
+    list: Option<Expr<'a>>
+) -> Result<Vec<(&'a str, Expr<'a>)>, ExpandError> {
+    let Some(Expr::Parenthesized(list)) = list else {
+        return Err(bad_syntax(form, "expected a list of bindings"));
+    };
+
+    let mut out = Vec::with_capacity(list.children.len() + 1);
+
+    for binding in list.into_vec() {
+        let (name, mut value) = clause(form, binding)?;
+
+        let Expr::Ident(name) = name else {
+            return Err(bad_syntax(form, "binding name is not an identifier"));
+        };
+
+        if value.len() != 1 {
+            return Err(bad_syntax(form, "binding must have exactly one value"));
+        }
+
+        out.push((name, value.pop().unwrap()));
+    }
+
+    Ok(out)
+}
+
+/// Expands the values of a binding list like `([name value] ...)`, keeping the bindings the way
+/// they were written.
+pub fn expand_bindings<'a>(
+    expander: &mut Expander,
+    form: &'static str,
+    list: Option<Expr<'a>>
+) -> Result<Expr<'a>, ExpandError> {
+    let Some(Expr::Parenthesized(mut list)) = list else {
+        return Err(bad_syntax(form, "expected a list of bindings"));
+    };
+
+    for binding in list.node.iter_mut().map(Box::as_mut).chain(list.children.iter_mut()) {
+        let Expr::Parenthesized(binding) = binding else {
+            return Err(bad_syntax(form, "clause is not a parenthesized expression"));
+        };
+
+        let name = binding.node.as_deref().ok_or(bad_syntax(form, "empty clause"))?;
+
+        if !name.is_ident() {
+            return Err(bad_syntax(form, "binding name is not an identifier"));
+        }
+
+        let [value] = binding.children.as_mut_slice() else {
+            return Err(bad_syntax(form, "binding must have exactly one value"));
+        };
+
+        *value = expander.expand(std::mem::replace(value, super::void()))?;
+    }
+
+    Ok(Expr::Parenthesized(list))
+}
+
+pub fn binding<'a>(name: &'a str, value: Expr<'a>) -> Expr<'a> {
+    Expr::Parenthesized(Tree::from_vec(vec![Expr::Ident(name), value]))
+}
+
+fn binding_list<'a>(bindings: Vec<(&'a str, Expr<'a>)>) -> Expr<'a> {
+    Expr::Parenthesized(Tree::from_vec(
+        bindings.into_iter().map(|(name, value)| binding(name, value)).collect()
+    ))
+}
+
+/// Quotes a datum of a `case` clause, primitives are self-evaluating so they are left as is.
+fn quote_datum(datum: Expr<'_>) -> Expr<'_> {
+    match datum {
+        p @ Expr::Primitive(_) => p,
+        other => Expr::Datum(Box::new(reader::datum(other)))
+    }
+}
+
+/// `(quote datum)` => `'datum`
+pub fn quote(tree: Tree<'_>) -> Result<Expr<'_>, ExpandError> {
+    let mut children = tree.children;
+
+    if children.len() != 1 {
+        return Err(bad_syntax("quote", "expected a single datum"));
+    }
+
+    Ok(Expr::Datum(Box::new(reader::datum(children.pop().unwrap()))))
+}
+
+/// Lowers each clause into a nested `if`, starting from the last one:
+///
+/// - `[else body ...]` => `(begin body ...)`
+/// - `[test]` => `(or test rest)`
+/// - `[test => f]` => `(let ([tmp test]) (if tmp (f tmp) rest))`
+/// - `[test body ...]` => `(if test (begin body ...) rest)`
+pub fn cond<'a>(ex: &mut Expander, tree: Tree<'a>) -> Result<Expr<'a>, ExpandError> {
+    let clauses = tree.children;
+    let last = clauses.len().saturating_sub(1);
+    let mut lowered = void();
+
+    for (idx, item) in clauses.into_iter().enumerate().rev() {
+        let (test, mut body) = clause("cond", item)?;
+
+        lowered = if is_ident(&test, "else") {
+            if idx != last {
+                return Err(bad_syntax("cond", "else clause must be the last one"));
+            }
+
+            if body.is_empty() {
+                return Err(bad_syntax("cond", "missing expressions in else clause"));
+            }
+
+            begin(body)
+        } else if body.is_empty() {
+            form("or", vec![test, lowered])
+        } else if is_ident(&body[0], "=>") {
+            if body.len() != 2 {
+                return Err(bad_syntax("cond", "expected a single expression after =>"));
+            }
+
+            let receiver = body.pop().unwrap();
+            let tmp = ex.gensym("cond-value");
+
+            form("let", vec![
+                binding_list(vec![(tmp, test)]),
+                form("if", vec![
+                    Expr::Ident(tmp),
+                    Expr::Parenthesized(Tree::from_vec(vec![receiver, Expr::Ident(tmp)])),
+                    lowered
+                ])
+            ])
+        } else {
+            form("if", vec![test, begin(body), lowered])
+        };
+    }
+
+    Ok(lowered)
+}
+
+/// `(when test body ...)` => `(if test (begin body ...) (void))`
+pub fn when(tree: Tree<'_>) -> Result<Expr<'_>, ExpandError> {
+    let mut children = tree.children.into_iter();
+    let test = children.next().ok_or(bad_syntax("when", "missing test expression"))?;
+    let body = children.collect::<Vec<_>>();
+
+    if body.is_empty() {
+        return Err(bad_syntax("when", "missing body"));
+    }
+
+    Ok(form("if", vec![test, begin(body), void()]))
+}
+
+/// `(unless test body ...)` => `(if test (void) (begin body ...))`
+pub fn unless(tree: Tree<'_>) -> Result<Expr<'_>, ExpandError> {
+    let mut children = tree.children.into_iter();
+    let test = children.next().ok_or(bad_syntax("unless", "missing test expression"))?;
+    let body = children.collect::<Vec<_>>();
+
+    if body.is_empty() {
+        return Err(bad_syntax("unless", "missing body"));
+    }
+
+    Ok(form("if", vec![test, void(), begin(body)]))
+}
+
+/// `(let* ([a x] [b y]) body ...)` => `(let ([a x]) (let* ([b y]) body ...))`
+pub fn let_asterisk(tree: Tree<'_>) -> Result<Expr<'_>, ExpandError> {
+    let mut children = tree.children.into_iter();
+    let mut bindings = bindings("let*", children.next())?;
+    let body = children.collect::<Vec<_>>();
+
+    if body.is_empty() {
+        return Err(bad_syntax("let*", "missing body"));
+    }
+
+    if bindings.len() <= 1 {
+        let mut items = vec![binding_list(bindings)];
+        items.extend(body);
+        return Ok(form("let", items));
+    }
+
+    let rest = bindings.split_off(1);
+    let mut inner = vec![binding_list(rest)];
+    inner.extend(body);
+
+    Ok(form("let", vec![binding_list(bindings), form("let*", inner)]))
+}
+
+/// `(let name ([v e] ...) body ...)` => `(letrec ([name (lambda (v ...) body ...)]) (name e ...))`
+pub fn named_let(tree: Tree<'_>) -> Result<Expr<'_>, ExpandError> {
+    let mut children = tree.children.into_iter();
+    let Some(Expr::Ident(name)) = children.next() else {
+        return Err(bad_syntax("let", "expected an identifier as the loop name"));
+    };
+    let (formals, values): (Vec<_>, Vec<_>) = bindings("let", children.next())?
+        .into_iter()
+        .map(|(name, value)| (Expr::Ident(name), value))
+        .unzip();
+    let body = children.collect::<Vec<_>>();
+
+    if body.is_empty() {
+        return Err(bad_syntax("let", "missing body"));
+    }
+
+    let mut lambda = vec![Expr::Parenthesized(Tree::from_vec(formals))];
+    lambda.extend(body);
+
+    let mut call = vec![Expr::Ident(name)];
+    call.extend(values);
+
+    Ok(form("letrec", vec![
+        binding_list(vec![(name, form("lambda", lambda))]),
+        Expr::Parenthesized(Tree::from_vec(call))
+    ]))
+}
+
+/// Binds the key to a temporary and turns every clause into a `cond` one:
+///
+/// `(case key [(d ...) body ...] [else body ...])` =>
+/// `(let ([tmp key]) (cond [(or (eqv? tmp 'd) ...) body ...] [else body ...]))`
+pub fn case<'a>(ex: &mut Expander, tree: Tree<'a>) -> Result<Expr<'a>, ExpandError> {
+    let mut children = tree.children.into_iter();
+    let key = children.next().ok_or(bad_syntax("case", "missing key expression"))?;
+    let tmp = ex.gensym("case-key");
+
+    let mut clauses = Vec::with_capacity(children.len());
+
+    for item in children {
+        let (data, body) = clause("case", item)?;
+
+        let test = if is_ident(&data, "else") {
+            data
+        } else {
+            let Expr::Parenthesized(data) = data else {
+                return Err(bad_syntax("case", "expected a list of datums"));
+            };
+
+            form("or", data.into_vec()
+                .into_iter()
+                .map(|datum| form("eqv?", vec![Expr::Ident(tmp), quote_datum(datum)]))
+                .collect())
+        };
+
+        if body.is_empty() {
+            return Err(bad_syntax("case", "missing expressions in clause"));
+        }
+
+        let mut lowered = vec![test];
+        lowered.extend(body);
+        clauses.push(Expr::Parenthesized(Tree::from_vec(lowered)));
+    }
+
+    Ok(form("let", vec![
+        binding_list(vec![(tmp, key)]),
+        form("cond", clauses)
+    ]))
+}
+
+/// `(do ([var init step] ...) (test res ...) body ...)` =>
+/// `(let loop ([var init] ...) (if test (begin res ...) (begin body ... (loop step ...))))`
+pub fn r#do<'a>(ex: &mut Expander, tree: Tree<'a>) -> Result<Expr<'a>, ExpandError> {
+    let mut children = tree.children.into_iter();
+
+    let Some(Expr::Parenthesized(specs)) = children.next() else {
+        return Err(bad_syntax("do", "expected a list of variable specifications"));
+    };
+
+    let mut bindings = Vec::with_capacity(specs.children.len() + 1);
+    let mut steps = Vec::with_capacity(specs.children.len() + 1);
+
+    for spec in specs.into_vec() {
+        let (name, mut rest) = clause("do", spec)?;
+
+        let Expr::Ident(name) = name else {
+            return Err(bad_syntax("do", "variable is not an identifier"));
+        };
+
+        let step = match rest.len() {
+            1 => Expr::Ident(name),
+            2 => rest.pop().unwrap(),
+            _ => return Err(bad_syntax("do", "expected an initial value and an optional step"))
+        };
+
+        bindings.push((name, rest.pop().unwrap()));
+        steps.push(step);
+    }
+
+    let Some(exit) = children.next() else {
+        return Err(bad_syntax("do", "missing exit clause"));
+    };
+
+    let (test, result) = clause("do", exit)?;
+    let name = ex.gensym("do-loop");
+
+    let mut body = children.collect::<Vec<_>>();
+    let mut call = vec![Expr::Ident(name)];
+    call.extend(steps);
+    body.push(Expr::Parenthesized(Tree::from_vec(call)));
+
+    Ok(form("let", vec![
+        Expr::Ident(name),
+        binding_list(bindings),
+        form("if", vec![test, begin(result), begin(body)])
+    ]))
+}
@@ -0,0 +1,294 @@
+mod forms;
+
+use thiserror::Error;
+
+use crate::ast::expr::{Expr, Shape, Tree};
+use crate::ext::StrExt;
+use crate::primitives::reserved::ReservedWords;
+use crate::span::Span;
+
+#[derive(Debug, Error)]
+pub enum ExpandError {
+    #[error("Bad syntax on {form}: {reason}")]
+    BadSyntax {
+        form: &'static str,
+        reason: &'static str
+    }
+}
+
+/// Lowers derived forms (`cond`, `when`, `unless`, `let*`, named `let`, `case` and `do`) into the
+/// core language the evaluator understands: `define`, `lambda`, `if`, `begin`, `let`, `letrec`,
+/// `quote`, `and`, `or` and plain applications.
+pub struct Expander {
+    counter: usize
+}
+
+impl Expander {
+    pub fn new() -> Self {
+        Self {
+            counter: 0
+        }
+    }
+
+    pub fn expand<'a>(&mut self, expr: Expr<'a>) -> Result<Expr<'a>, ExpandError> {
+        match expr {
+            Expr::Parenthesized(tree) => {
+                let span = tree.span.clone();
+                Ok(keep_span(self.expand_tree(tree)?, span))
+            },
+            other => Ok(other)
+        }
+    }
+
+    fn expand_tree<'a>(&mut self, tree: Tree<'a>) -> Result<Expr<'a>, ExpandError> {
+        let head = tree.node.as_ref().and_then(|n| n.get_ident().copied());
+
+        let Some(head) = head else {
+            return self.expand_application(tree);
+        };
+
+        match ReservedWords::from_str(head) {
+            Some(ReservedWords::Quote) => forms::quote(tree),
+            Some(ReservedWords::Cond) => {
+                let lowered = forms::cond(self, tree)?;
+                self.expand(lowered)
+            },
+            Some(ReservedWords::When) => {
+                let lowered = forms::when(tree)?;
+                self.expand(lowered)
+            },
+            Some(ReservedWords::Unless) => {
+                let lowered = forms::unless(tree)?;
+                self.expand(lowered)
+            },
+            Some(ReservedWords::LetAsterisk) => {
+                let lowered = forms::let_asterisk(tree)?;
+                self.expand(lowered)
+            },
+            Some(ReservedWords::Case) => {
+                let lowered = forms::case(self, tree)?;
+                self.expand(lowered)
+            },
+            Some(ReservedWords::Do) => {
+                let lowered = forms::r#do(self, tree)?;
+                self.expand(lowered)
+            },
+            Some(ReservedWords::Let) if tree.children.first().map(Expr::is_ident).unwrap_or(false) => {
+                let lowered = forms::named_let(tree)?;
+                self.expand(lowered)
+            },
+            Some(ReservedWords::Let | ReservedWords::Letrec) => self.expand_binding_form(tree),
+            Some(ReservedWords::Lambda | ReservedWords::Define) => self.expand_skipping_first(tree),
+            Some(ReservedWords::Match) => self.expand_match(tree),
+            // forms whose arguments are not expressions, and debug forms that inspect their
+            // arguments before they are expanded
+            Some(ReservedWords::Struct) => Ok(Expr::Parenthesized(tree)),
+            _ if head == "d/expand" => Ok(Expr::Parenthesized(tree)),
+            _ => self.expand_application(tree)
+        }
+    }
+
+    fn expand_application<'a>(&mut self, tree: Tree<'a>) -> Result<Expr<'a>, ExpandError> {
+        let shape = tree.shape;
+        let items = tree.into_vec()
+            .into_iter()
+            .map(|i| self.expand(i))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut out = Tree::from_vec(items);
+        out.shape = shape;
+        Ok(Expr::Parenthesized(out))
+    }
+
+    /// Expands every item of the form except the first child, used for forms whose first child
+    /// is a list of formals, like `lambda` or the function shorthand of `define`.
+    fn expand_skipping_first<'a>(&mut self, tree: Tree<'a>) -> Result<Expr<'a>, ExpandError> {
+        let mut children = tree.children.into_iter();
+        let mut out = Tree::with_capacity(children.len());
+        out.node = tree.node;
+        out.shape = tree.shape;
+
+        if let Some(first) = children.next() {
+            out.push(if first.is_parenthesized() {
+                first
+            } else {
+                self.expand(first)?
+            });
+        }
+
+        for child in children {
+            out.push(self.expand(child)?);
+        }
+
+        Ok(Expr::Parenthesized(out))
+    }
+
+    /// Expands `let` and `letrec`, where only the right hand side of each binding and the body
+    /// contain expressions.
+    fn expand_binding_form<'a>(&mut self, tree: Tree<'a>) -> Result<Expr<'a>, ExpandError> {
+        let mut children = tree.children.into_iter();
+        let mut out = Tree::with_capacity(children.len());
+        out.node = tree.node;
+        out.shape = tree.shape;
+
+        out.push(forms::expand_bindings(self, "let", children.next())?);
+
+        for child in children {
+            out.push(self.expand(child)?);
+        }
+
+        Ok(Expr::Parenthesized(out))
+    }
+
+    /// Expands the matched expression and every clause except its pattern.
+    fn expand_match<'a>(&mut self, tree: Tree<'a>) -> Result<Expr<'a>, ExpandError> {
+        let mut children = tree.children.into_iter();
+        let mut out = Tree::with_capacity(children.len());
+        out.node = tree.node;
+        out.shape = tree.shape;
+
+        if let Some(value) = children.next() {
+            out.push(self.expand(value)?);
+        }
+
+        for clause in children {
+            let Expr::Parenthesized(clause) = clause else {
+                return Err(ExpandError::BadSyntax {
+                    form: "match",
+                    reason: "clause is not a parenthesized expression"
+                });
+            };
+
+            let shape = clause.shape;
+            let mut items = clause.into_vec().into_iter();
+            let mut expanded = Vec::with_capacity(items.len());
+            expanded.extend(items.next());
+
+            for item in items {
+                expanded.push(self.expand(item)?);
+            }
+
+            let mut clause = Tree::from_vec(expanded);
+            clause.shape = shape;
+            out.push(Expr::Parenthesized(clause));
+        }
+
+        Ok(Expr::Parenthesized(out))
+    }
+
+    /// Generates an identifier that cannot clash with the ones written by the user, since braces
+    /// are delimiters and the lexer never produces identifiers containing them.
+    fn gensym(&mut self, base: &str) -> &'static str {
+        self.counter += 1;
+        format!("{base}{{{}}}", self.counter).make_static()
+    }
+}
+
+/// Gives the form a tree was lowered into the span of the tree, so errors in the lowered form
+/// point to the source.
+fn keep_span(mut expr: Expr<'_>, span: Option<Span>) -> Expr<'_> {
+    if let Expr::Parenthesized(tree) = &mut expr {
+        tree.span = tree.span.take().or(span);
+    }
+
+    expr
+}
+
+pub fn form<'a>(head: &'a str, children: Vec<Expr<'a>>) -> Expr<'a> {
+    Expr::Parenthesized(Tree {
+        node: Some(Box::new(Expr::Ident(head))),
+        children,
+        span: None,
+        shape: Shape::Paren
+    })
+}
+
+pub fn void<'a>() -> Expr<'a> {
+    form("void", Vec::new())
+}
+
+/// Sequences the given expressions, avoiding a `begin` when there is a single one.
+pub fn begin<'a>(mut body: Vec<Expr<'a>>) -> Expr<'a> {
+    match body.len() {
+        0 => void(),
+        1 => body.pop().unwrap(),
+        _ => form("begin", body)
+    }
+}
+
+#[cfg(test)]
+fn expand_source(source: &str) -> String {
+    use crate::{ast::Ast, display::InterpreterDisplay, interpreter::Interpreter, lexer::Lexer};
+
+    let tokens = Lexer::new(source).parse().unwrap();
+    let ast = Ast::try_from(tokens).unwrap();
+    let interpreter = Interpreter::new(Ast::empty());
+    let mut expander = Expander::new();
+    let mut out = String::new();
+
+    for expr in ast.inner.iter() {
+        expander.expand(expr.clone()).unwrap().fmt(&mut out, &interpreter).unwrap();
+    }
+
+    out
+}
+
+#[test]
+fn test_expand_derived_forms() {
+    assert_eq!(
+        expand_source("(cond [(= x 1) 'one] [else (when y 2 3)])"),
+        "(if (= x 1) 'one (if y (begin 2 3) (void)))"
+    );
+    assert_eq!(
+        expand_source("(let* ([a 1] [b a]) b)"),
+        "(let ((a 1)) (let ((b a)) b))"
+    );
+    assert_eq!(
+        expand_source("(do ([i 0 (+ i 1)]) ((= i 3) i))"),
+        "(letrec ((do-loop{1} (lambda (i) (if (= i 3) i (do-loop{1} (+ i 1)))))) (do-loop{1} 0))"
+    );
+}
+
+#[test]
+fn test_generated_names_dont_clash() {
+    use crate::interpreter::eval_source;
+
+    assert_eq!(eval_source("(define case-key.1 'user) (case 5 [(5) case-key.1] [else 0])").unwrap(), "'user");
+    assert_eq!(eval_source("(define do-loop 'user) (do ([i 0 (+ i 1)]) ((= i 2) do-loop))").unwrap(), "'user");
+}
+
+#[test]
+fn test_loops_run_in_constant_stack() {
+    use crate::interpreter::eval_source;
+
+    assert_eq!(eval_source("(do ([i 0 (+ i 1)]) ((= i 100000) i))").unwrap(), "100000");
+    assert_eq!(eval_source("(let loop ([i 0]) (cond [(= i 10000) 'done] [else (loop (+ i 1))]))").unwrap(), "'done");
+    assert_eq!(eval_source("(define (even n) (or (= n 0) (odd (- n 1))))
+(define (odd n) (and (not (= n 0)) (even (- n 1))))
+(even 10001)").unwrap(), "#f");
+
+    // loops inside a procedure body, called in tail position of it
+    assert_eq!(eval_source("(define (sum-to n)
+  (let loop ([i 0] [acc 0]) (if (> i n) acc (loop (+ i 1) (+ acc i)))))
+(sum-to 10000)").unwrap(), "50005000");
+    assert_eq!(eval_source("(define (count n) (do ([i 0 (+ i 1)]) ((= i n) i))) (count 10000)").unwrap(), "10000");
+    assert_eq!(eval_source("(define (parity n)
+  (letrec ([ev (lambda (k) (if (= k 0) 'even (od (- k 1))))]
+           [od (lambda (k) (if (= k 0) 'odd (ev (- k 1))))])
+    (ev n)))
+(parity 10001)").unwrap(), "'odd");
+}
+
+#[test]
+fn test_expand_keeps_shape() {
+    use crate::interpreter::eval_source;
+
+    assert_eq!(
+        expand_source("(let ([a 1] [b (when c 2)]) {f [g a] b})"),
+        "(let ([a 1] [b (if c 2 (void))]) {f [g a] b})"
+    );
+    assert_eq!(expand_source("(define [f x] [list x])"), "(define [f x] [list x])");
+
+    // data is a list however it's written
+    assert_eq!(eval_source("(let ([a '[1 {2}]]) a)").unwrap(), "'(1 (2))");
+}
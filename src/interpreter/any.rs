@@ -1,4 +1,4 @@
-use crate::ast::expr::{Expr, Tree};
+use crate::ast::expr::{Expr, Shape, Tree};
 use crate::ext::StrExt;
 use crate::interpreter::eval_tree::EvalTree;
 use crate::macros::get_enum;
@@ -13,7 +13,6 @@ get_enum! {
         Composed(Box<Composed<'a>>),
         Expression(Box<EvalTree<'a>>),
         Ident(&'a str),
-        RawQuoted(Box<AnyEval<'a>>),
         Void(()),
     }
 }
@@ -24,7 +23,7 @@ impl<'a> AnyEval<'a> {
             Expr::Ident(i) => AnyEval::Ident(i),
             Expr::Primitive(p) => AnyEval::Primitive(p),
             Expr::Parenthesized(t) => AnyEval::Expression(Box::new(EvalTree::new_singleton(&t))),
-            Expr::RawQuoted(q) => AnyEval::RawQuoted(Box::new(Self::from_expr(*q)))
+            Expr::Datum(d) => Self::from_any(*d)
         }
     }
 
@@ -36,7 +35,6 @@ impl<'a> AnyEval<'a> {
             Composed(c) => Composed(Box::new(c.make_static())),
             Expression(e) => Expression(Box::new(e.make_static())),
             Ident(i) => Ident(i.make_static()),
-            RawQuoted(q) => RawQuoted(Box::new(q.make_static())),
             Void(_) => Void(())
         }
     }
@@ -55,12 +53,14 @@ impl<'a> AnyEval<'a> {
             AnyEval::Expression(e) => {
                 Expr::Parenthesized(Tree {
                     node: e.node.map(|i| i.to_expr()).map(Box::new),
-                    children: e.children.into_iter().map(|i| i.to_expr()).collect()
+                    children: e.children.into_iter().map(|i| i.to_expr()).collect(),
+                    span: e.span,
+                    shape: Shape::Paren
                 })
             },
             AnyEval::Ident(i) => Expr::Ident(i),
-            AnyEval::RawQuoted(rq) => Expr::RawQuoted(Box::new(rq.to_expr())),
             AnyEval::Primitive(p) => Expr::Primitive(p),
+            AnyEval::Composed(c) => Expr::Datum(Box::new(Any::Composed(c))),
             _ => unreachable!()
         }
     }
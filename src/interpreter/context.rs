@@ -1,19 +1,57 @@
 use std::{collections::HashMap, marker::PhantomData, ops::Deref};
 use parking_lot::{RwLock, RwLockReadGuard, RwLockWriteGuard};
 
-use crate::{ast::expr::{Expr, Tree}, cell::Cell, container::VarsContainer, native::NativeStorage, primitives::composed::{Composed, Function, FunctionBody}};
+use crate::{ast::expr::{Expr, Tree}, cell::Cell, container::VarsContainer, native::NativeStorage, primitives::composed::{Composed, Function, FunctionBody, LambdaFunction}};
+use crate::display::InterpreterDisplay;
 use crate::interpreter::any::AnyEval;
+use crate::interpreter::stack_trace::CallFrame;
 use crate::interpreter::error::InterpreterError;
 use crate::interpreter::Interpreter;
 use crate::native::error::DeclaredFunctionError;
 use crate::primitives::any::Any;
+use crate::primitives::continuation::Continuation;
+use crate::span::Span;
 
 use super::{eval_tree::EvalTree, vars::{OwnedStorage, VarsStorage}};
 
+/// Natives that evaluate their last form in tail position, returning its value as it is.
+const TAIL_FORMS: &[&str] = &["if", "begin", "let", "letrec", "and", "or"];
+
 pub struct Context<'interpreter, 'inner> {
     interpreter: &'interpreter Interpreter<'inner>,
     local_variables: Cell<VarsStorage<'inner>>,
     root: bool,
+    /// Whether the value of the form being evaluated is the one of the procedure body it's in,
+    /// so calls made by it can replace the call of that procedure.
+    tail: bool,
+}
+
+/// Call left by a procedure body for its caller to make, since it was in tail position.
+pub(super) struct TailCall<'a> {
+    callee: Any<'a>,
+    args: Vec<AnyEval<'a>>,
+    call_site: Option<Span>,
+    /// Local variables where the call was made, which the callee sees like it would if it was
+    /// called from there, such as the bindings of the `letrec` it was defined in.
+    locals: VarsStorage<'a>
+}
+
+/// Procedure declared in the source, which gets a frame on the call stack while its body runs.
+pub struct Procedure<'p, 'inner> {
+    name: Option<&'inner str>,
+    arity: Option<u8>,
+    body: &'p FunctionBody<'inner>,
+    captured: &'p [(String, Any<'inner>)]
+}
+
+impl<'p, 'inner> Procedure<'p, 'inner> {
+    pub fn function(f: &'p Function<'inner>) -> Self {
+        Self { name: Some(f.name), arity: f.arity, body: &f.body, captured: &[] }
+    }
+
+    pub fn lambda(l: &'p LambdaFunction<'inner>) -> Self {
+        Self { name: None, arity: l.arity, body: &l.body, captured: &l.captured }
+    }
 }
 
 impl<'interpreter, 'inner> Context<'interpreter, 'inner> {
@@ -22,6 +60,7 @@ impl<'interpreter, 'inner> Context<'interpreter, 'inner> {
             interpreter,
             local_variables: Cell::new(VarsStorage::new()),
             root: true,
+            tail: false,
         }
     }
 
@@ -63,6 +102,35 @@ impl<'interpreter, 'inner> Context<'interpreter, 'inner> {
             interpreter: self.interpreter,
             local_variables: self.local_variables.clone(),
             root: false,
+            tail: false,
+        }
+    }
+
+    /// Like [`Self::level_down`], for forms whose value is the one of the current form, like the
+    /// branches of `if`.
+    pub fn tail_level_down(&self) -> Self {
+        Self {
+            tail: self.tail,
+            ..self.level_down()
+        }
+    }
+
+    /// Runs `f` outside of tail position, for forms whose value is used by the current one.
+    pub fn non_tail<T>(&mut self, f: impl FnOnce(&mut Self) -> T) -> T {
+        let tail = std::mem::replace(&mut self.tail, false);
+        let result = f(self);
+        self.tail = tail;
+        result
+    }
+
+    /// Creates a context whose local variables are the given ones, like the ones captured by a
+    /// promise.
+    pub fn with_locals(&self, locals: VarsStorage<'inner>) -> Self {
+        Self {
+            interpreter: self.interpreter,
+            local_variables: Cell::new(locals),
+            root: false,
+            tail: false,
         }
     }
 
@@ -88,7 +156,7 @@ impl<'interpreter, 'inner> Context<'interpreter, 'inner> {
     pub fn eval(&mut self, expr: &AnyEval<'inner>) -> Result<Any<'inner>, InterpreterError> {
         match expr {
             AnyEval::Expression(e)
-                => self.eval_tree(e),
+                => self.eval_tree(e).map_err(|error| error.located(e.span.as_ref())),
             AnyEval::Ident(i) => self.get_ident(i),
             other => Ok(Any::from(other)),
         }
@@ -99,18 +167,117 @@ impl<'interpreter, 'inner> Context<'interpreter, 'inner> {
             .or_else(|| self.interpreter.vars().get(ident))
             .cloned()
             .or_else(|| self.get_native_disguised_var(ident))
-            .ok_or(InterpreterError::UnknownIdentifier(ident.to_string()))
+            .ok_or_else(|| InterpreterError::UnknownIdentifier {
+                name: ident.to_string(),
+                locals: self.local_names()
+            })
     }
 
     pub fn call_declared(
         &mut self,
         fun: &str,
+        args: &[AnyEval<'inner>],
+        call_site: Option<&Span>
+    ) -> Result<Any<'inner>, InterpreterError> {
+        let callee = self.get_var(fun)
+            .cloned()
+            .ok_or_else(|| self.undefined_function(fun))?;
+        let args = self.eval_args(args)?;
+
+        self.apply_at(&callee, &args, call_site)
+    }
+
+    /// Evaluates the arguments of a call to a declared procedure, which are passed by value.
+    pub fn eval_args(&mut self, args: &[AnyEval<'inner>]) -> Result<Vec<AnyEval<'inner>>, InterpreterError> {
+        args.iter()
+            .map(|arg| self.level_down().eval(arg).map(AnyEval::from_any))
+            .collect()
+    }
+
+    /// Calls a procedure value with already evaluated arguments.
+    pub fn apply(
+        &mut self,
+        callee: &Any<'inner>,
         args: &[AnyEval<'inner>]
     ) -> Result<Any<'inner>, InterpreterError> {
-        let vars = self.interpreter.vars();
-        let fun = vars.get(fun).unwrap().get_composed().unwrap().get_function().unwrap();
+        self.apply_at(callee, args, None)
+    }
+
+    /// Calls a procedure value from the form at `call_site`, which shows up in the backtrace of
+    /// errors raised by the call.
+    pub fn apply_at(
+        &mut self,
+        callee: &Any<'inner>,
+        args: &[AnyEval<'inner>],
+        call_site: Option<&Span>
+    ) -> Result<Any<'inner>, InterpreterError> {
+        if let Some(procedure) = self.procedure(callee) {
+            if !self.tail {
+                return self.call_procedure(procedure, args, call_site);
+            }
+
+            *self.interpreter.tail_call.borrow_mut() = Some(TailCall {
+                callee: callee.clone(),
+                args: args.to_vec(),
+                call_site: call_site.cloned(),
+                locals: (*self.local_variables).clone()
+            });
+
+            return Ok(Any::Void(()));
+        }
+
+        match callee.get_composed().map(Box::as_ref) {
+            Some(Composed::Function(f)) => {
+                let native = self.interpreter.storage.get(f.name).unwrap();
+                self.non_tail(|cx| native.call(cx, args))
+            },
+            Some(Composed::StructProcedure(p)) => p.call(args.iter().map(Any::from).collect()),
+            Some(Composed::Continuation(k)) => Err(k.escape(args.iter().map(Any::from).collect())),
+            Some(Composed::Parameter(p)) => self.non_tail(|cx| p.apply(cx, args)),
+            _ => {
+                let mut given = String::new();
+                callee.fmt(&mut given, self.interpreter).unwrap();
+                Err(InterpreterError::NotAProcedure(given))
+            }
+        }
+    }
+
+    /// The procedure declared in the source `callee` is, if it's one.
+    fn procedure<'p>(&self, callee: &'p Any<'inner>) -> Option<Procedure<'p, 'inner>> {
+        match callee.get_composed().map(Box::as_ref)? {
+            Composed::Function(f) if f.body.body.is_empty() && self.interpreter.is_native(f.name) => None,
+            Composed::Function(f) => Some(Procedure::function(f)),
+            Composed::Lambda(l) => Some(Procedure::lambda(l)),
+            _ => None
+        }
+    }
+
+    /// Calls a procedure declared in the source, along with the calls its body leaves in tail
+    /// position, one after the other so loops don't grow the native stack.
+    pub fn call_procedure(
+        &mut self,
+        procedure: Procedure<'_, 'inner>,
+        args: &[AnyEval<'inner>],
+        call_site: Option<&Span>
+    ) -> Result<Any<'inner>, InterpreterError> {
+        let mut result = self.enter(procedure, args, call_site);
+
+        while let Some(call) = self.interpreter.tail_call.take() {
+            let procedure = self.procedure(&call.callee).expect("Only procedures are called in tail position");
+            result = self.with_locals(call.locals).enter(procedure, &call.args, call.call_site.as_ref());
+        }
+
+        result
+    }
 
-        if let Some(arity) = fun.arity {
+    /// Evaluates the body of a procedure with a frame on the call stack.
+    fn enter(
+        &mut self,
+        procedure: Procedure<'_, 'inner>,
+        args: &[AnyEval<'inner>],
+        call_site: Option<&Span>
+    ) -> Result<Any<'inner>, InterpreterError> {
+        if let Some(arity) = procedure.arity {
             if args.len() != arity as _ {
                 return Err(InterpreterError::DeclaredFnError(DeclaredFunctionError::ArityMismatch {
                     got: args.len() as _,
@@ -118,26 +285,48 @@ impl<'interpreter, 'inner> Context<'interpreter, 'inner> {
                 }))
             }
         }
-        fun.body.call(&mut self.level_down(), args)
 
-        /*let mut iter = fun.body.body.iter().enumerate();
+        let calls = &self.interpreter.calls;
+        calls.borrow_mut().push(CallFrame { name: procedure.name, call_site: call_site.cloned() });
 
-        while let Some((idx, expr)) = iter.next() {
-            if idx == fun.body.body.len() - 1 {
-                return self.eval_any(expr)
-            } else {
-                self.eval_any(expr)?;
-            }
+        let mut scope = self.level_down();
+        scope.tail = true;
+
+        for (name, value) in procedure.captured {
+            scope.vars_mut().insert(name, value.clone());
         }
 
-        Ok(Any::Void(()))*/
+        let result = procedure.body.call(&mut scope, args)
+            .map_err(|e| e.traced(&calls.borrow()));
+
+        calls.borrow_mut().pop();
+        result
+    }
+
+    /// Evaluates `body` with a fresh escape continuation, returning the value the continuation
+    /// is applied to if it's used before `body` returns.
+    pub fn with_escape<F>(&mut self, body: F) -> Result<Any<'inner>, InterpreterError>
+    where
+        F: FnOnce(&mut Self, Any<'inner>) -> Result<Any<'inner>, InterpreterError>
+    {
+        let continuation = Continuation::new();
+        let result = body(self, Any::Composed(Box::new(Composed::Continuation(continuation.clone()))));
+        continuation.invalidate();
+
+        match result {
+            Err(InterpreterError::Escape { id, value }) if id == continuation.id() => Ok(value),
+            other => other
+        }
     }
 
     pub fn eval_tree(&mut self, tree: &EvalTree<'inner>) -> Result<Any<'inner>, InterpreterError> {
         let node = tree.node.as_ref().ok_or(InterpreterError::MissingTreeNode)?;
 
         let Some(fun) = node.get_ident() else {
-            return Ok(Any::from(node));
+            // application of any other expression, like ((lambda (x) x) 1)
+            let callee = self.level_down().eval(node)?;
+            let args = self.eval_args(&tree.children)?;
+            return self.apply_at(&callee, &args, tree.span.as_ref());
         };
 
         let children = tree.children.iter().map(|c| c.clone()/*self.eval(&c)*/)
@@ -145,11 +334,27 @@ impl<'interpreter, 'inner> Context<'interpreter, 'inner> {
             .collect::<Vec<_>>();
 
         if self.interpreter.is_native(fun) {
-            Ok(self.interpreter.storage.get(fun).unwrap().call(self, children.as_slice())?)
+            let native = self.interpreter.storage.get(fun).unwrap();
+
+            match TAIL_FORMS.contains(fun) {
+                true => native.call(self, children.as_slice()),
+                false => self.non_tail(|cx| native.call(cx, children.as_slice()))
+            }
         } else if self.is_declared_function(fun) {
-            Ok(self.call_declared(fun, children.as_slice())?)
+            Ok(self.call_declared(fun, children.as_slice(), tree.span.as_ref())?)
         } else {
-            Err(InterpreterError::UndefinedFunction(fun.to_string()))
+            Err(self.undefined_function(fun))
+        }
+    }
+
+    fn local_names(&self) -> Vec<String> {
+        self.local_variables.table.keys().cloned().collect()
+    }
+
+    fn undefined_function(&self, name: &str) -> InterpreterError {
+        InterpreterError::UndefinedFunction {
+            name: name.to_string(),
+            locals: self.local_names()
         }
     }
 
@@ -1,18 +1,34 @@
 use thiserror::Error;
+use crate::expander::ExpandError;
+use crate::interpreter::stack_trace::{CallFrame, StackTrace};
 use crate::native::error::{DeclaredFunctionError, NativeFnError};
+use crate::primitives::any::Any;
+use crate::span::{Located, Span};
 
 #[derive(Debug, Error)]
 pub enum InterpreterError {
-    #[error("Undefined function: {0}")]
-    UndefinedFunction(String),
+    /// Unbound name in the head of an application, along with the local variables in scope,
+    /// which are searched for hints.
+    #[error("Undefined function: {name}")]
+    UndefinedFunction {
+        name: String,
+        locals: Vec<String>
+    },
+    #[error("Application: not a procedure, given: {0}")]
+    NotAProcedure(String),
     #[error("Missing node on tree expression")]
     MissingTreeNode,
     #[error("Native fn error: {0}")]
     NativeError(#[from] NativeFnError),
+    #[error("Expansion error: {0}")]
+    Expansion(#[from] ExpandError),
     #[error("Declared function error: {0}")]
     DeclaredFnError(#[from] DeclaredFunctionError),
-    #[error("Unknown identifier: {0}")]
-    UnknownIdentifier(String),
+    #[error("Unknown identifier: {name}")]
+    UnknownIdentifier {
+        name: String,
+        locals: Vec<String>
+    },
     #[error("Out of bounds, len is {length} but index {got} was tried to access")]
     OutOfBounds {
         length: usize,
@@ -21,5 +37,108 @@ pub enum InterpreterError {
     #[error("{0}")]
     Runtime(String),
     #[error("Invalid expression")]
-    InvalidExpression
+    InvalidExpression,
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+    /// Value passed to `raise`, with the message shown if nothing handles it.
+    #[error("{message}")]
+    Raised {
+        value: Any<'static>,
+        message: String
+    },
+    /// Jump to the escape continuation with the given id, unwinding everything in between.
+    #[error("continuation application: escape continuation used outside of its extent")]
+    Escape {
+        id: usize,
+        value: Any<'static>
+    },
+    /// Error raised while evaluating the form at the span.
+    #[error("{0}")]
+    Located(Box<Located<InterpreterError>>),
+    /// Error raised inside procedure calls, along with the calls it went through.
+    #[error("{error}\n{trace}")]
+    Traced {
+        error: Box<InterpreterError>,
+        trace: StackTrace
+    }
+}
+
+impl InterpreterError {
+    /// Attaches the span of the form being evaluated, unless the error already has one from a
+    /// form nested in it. Continuation jumps are left as is since they aren't failures.
+    pub fn located(self, span: Option<&Span>) -> Self {
+        match (self, span) {
+            (Self::Traced { error, trace }, span) => Self::Traced {
+                error: Box::new(error.located(span)),
+                trace
+            },
+            (error @ (Self::Located(_) | Self::Escape { .. }), _) | (error, None) => error,
+            (error, Some(span)) => Self::Located(Box::new(Located { span: span.clone(), error }))
+        }
+    }
+
+    /// Attaches the stack trace of the calls being evaluated, unless the error already has one
+    /// from the moment it left the innermost call.
+    pub fn traced(self, stack: &[CallFrame<'_>]) -> Self {
+        match self {
+            error @ (Self::Traced { .. } | Self::Escape { .. }) => error,
+            error => Self::Traced {
+                error: Box::new(error),
+                trace: StackTrace::capture(stack)
+            }
+        }
+    }
+
+    pub fn span(&self) -> Option<&Span> {
+        match self {
+            Self::Located(located) => Some(&located.span),
+            Self::Traced { error, .. } => error.span(),
+            _ => None
+        }
+    }
+
+    pub fn stack_trace(&self) -> Option<&StackTrace> {
+        match self {
+            Self::Traced { trace, .. } => Some(trace),
+            _ => None
+        }
+    }
+
+    /// Short description of what went wrong, shown before the message in diagnostics.
+    pub fn kind(&self) -> &'static str {
+        match self.unlocated() {
+            Self::UndefinedFunction { .. } | Self::UnknownIdentifier { .. } => "unbound identifier",
+            Self::NotAProcedure(_)
+                | Self::NativeError(_)
+                | Self::DeclaredFnError(_)
+                | Self::OutOfBounds { .. } => "contract violation",
+            Self::MissingTreeNode | Self::InvalidExpression | Self::Expansion(_) => "bad syntax",
+            Self::Io(_) => "I/O error",
+            Self::Raised { .. } => "uncaught exception",
+            Self::Escape { .. } => "continuation",
+            Self::Runtime(_) | Self::Located(_) | Self::Traced { .. } => "error"
+        }
+    }
+
+    /// Text of the error without the prefix naming its kind, like the message of the exn struct
+    /// `with-handlers` sees for it.
+    pub fn message(&self) -> String {
+        match self.unlocated() {
+            Self::NativeError(error) => error.to_string(),
+            Self::Expansion(ExpandError::BadSyntax { form, reason }) => format!("{form}: {reason}"),
+            Self::DeclaredFnError(error) => error.to_string(),
+            Self::Io(error) => error.to_string(),
+            Self::UndefinedFunction { name, .. } | Self::UnknownIdentifier { name, .. } => format!("{name}: undefined"),
+            error => error.to_string()
+        }
+    }
+
+    /// The error without the span it was raised at and the calls it went through.
+    pub fn unlocated(&self) -> &Self {
+        match self {
+            Self::Located(located) => located.error.unlocated(),
+            Self::Traced { error, .. } => error.unlocated(),
+            other => other
+        }
+    }
 }
@@ -2,6 +2,7 @@ use std::{collections::HashMap, ops::Deref};
 
 use crate::{ast::expr::{Expr, Tree}, primitives::{any::Any, composed::{Function, LambdaFunction}}};
 use crate::interpreter::any::AnyEval;
+use crate::span::Span;
 
 use super::{context::Context, error::InterpreterError};
 
@@ -9,13 +10,16 @@ use super::{context::Context, error::InterpreterError};
 pub struct EvalTree<'a> {
     pub node: Option<AnyEval<'a>>,
     pub children: Vec<AnyEval<'a>>,
+    /// Span of the form the tree was built from
+    pub span: Option<Span>
 }
 
 impl<'a> EvalTree<'a> {
     pub fn new(tree: &Tree<'a>, vars: &HashMap<&'a str, Option<&'a Any<'a>>>) -> EvalTree<'a> {
         let mut this = EvalTree {
             node: None,
-            children: Vec::new()
+            children: Vec::new(),
+            span: tree.span.clone()
         };
 
         if let Some(node) = &tree.node {
@@ -32,14 +36,16 @@ impl<'a> EvalTree<'a> {
     pub fn new_singleton(source: &Tree<'a>) -> EvalTree<'a> {
         EvalTree {
             node: source.node.as_ref().map(|n| AnyEval::from_expr(*n.clone())),
-            children: source.children.iter().map(|c| AnyEval::from_expr(c.clone())).collect()
+            children: source.children.iter().map(|c| AnyEval::from_expr(c.clone())).collect(),
+            span: source.span.clone()
         }
     }
 
     pub fn make_static(self) -> EvalTree<'static> {
         EvalTree {
             node: self.node.map(|n| n.make_static()),
-            children: self.children.into_iter().map(|c| c.make_static()).collect()
+            children: self.children.into_iter().map(|c| c.make_static()).collect(),
+            span: self.span
         }
     }
 
@@ -75,13 +81,35 @@ impl<'a> EvalTree<'a> {
         out
     }
 
+    pub fn as_vec(&self) -> Vec<AnyEval<'a>> {
+        let mut out = Vec::with_capacity(self.children.len() + 1);
+
+        if let Some(node) = &self.node {
+            out.push(node.clone());
+        }
+
+        out.extend(self.children.iter().cloned());
+        out
+    }
+
+    pub fn from_vec(items: Vec<AnyEval<'a>>) -> Self {
+        let mut iter = items.into_iter();
+
+        Self {
+            node: iter.next(),
+            children: iter.collect(),
+            span: None
+        }
+    }
+
     pub fn shift_left(&self) -> Self {
         assert!(self.children.len() > 0);
 
         let mut iter = self.children.iter().cloned();
         Self {
             node: Some(iter.next().unwrap()),
-            children: iter.collect()
+            children: iter.collect(),
+            span: self.span.clone()
         }
     }
 
@@ -90,7 +118,8 @@ impl<'a> EvalTree<'a> {
 
         Ok(LambdaFunction {
             arity: fun.arity,
-            body: fun.body
+            body: fun.body,
+            captured: Vec::new()
         })
     }
 }
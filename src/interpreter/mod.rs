@@ -3,6 +3,9 @@ pub mod context;
 pub mod vars;
 pub mod error;
 pub mod any;
+pub mod stack_trace;
+
+use std::cell::RefCell;
 
 use crate::display::InterpreterDisplay;
 
@@ -10,11 +13,15 @@ use parking_lot::{RwLock, RwLockReadGuard, RwLockWriteGuard};
 use crate::ast::Ast;
 use crate::ast::expr::{Expr, Tree};
 use crate::cell::Cell;
-use crate::interpreter::context::Context;
+use crate::expander::Expander;
+use crate::interpreter::stack_trace::CallFrame;
+use crate::interpreter::context::{Context, TailCall};
 use crate::interpreter::error::InterpreterError;
 use crate::interpreter::vars::VarsStorage;
 use crate::native::NativeStorage;
-use crate::primitives::any::Any;
+use crate::native::parameter;
+#[cfg(test)]
+use crate::primitives::{any::Any, composed::Composed, port::Port};
 
 use self::vars::OwnedStorage;
 
@@ -22,23 +29,48 @@ pub struct Interpreter<'a> {
     ast: Ast<'a>,
     storage: NativeStorage,
     pub(super) vars: Cell<OwnedStorage>,
+    /// Procedure calls being evaluated, innermost last
+    calls: RefCell<Vec<CallFrame<'a>>>,
+    /// Call left by the procedure body being evaluated, made once that body returns
+    tail_call: RefCell<Option<TailCall<'a>>>
 }
 
 impl<'a> Interpreter<'a> {
     pub fn new(ast: Ast<'a>) -> Self {
-        Self {
-            ast,
-            storage: NativeStorage::new(),
-            vars: Cell::new(OwnedStorage::new())
-        }
+        Self::with_vars(ast, Cell::new(OwnedStorage::new()))
     }
 
     pub fn with_vars(ast: Ast<'a>, vars: Cell<OwnedStorage>) -> Self {
-        Self {
+        let interpreter = Self {
             ast,
             storage: NativeStorage::new(),
-            vars
-        }
+            vars,
+            calls: RefCell::new(Vec::new()),
+            tail_call: RefCell::new(None)
+        };
+
+        parameter::define_builtins(interpreter.vars_mut());
+        interpreter
+    }
+
+    /// Sets the port where the program and the values of its top level expressions are written.
+    #[cfg(test)]
+    pub fn with_output_port(self, port: Port) -> Self {
+        parameter::set_builtin(self.vars(), "current-output-port", Any::Composed(Box::new(Composed::Port(port))));
+        self
+    }
+
+    /// Sets the port where the program writes its errors.
+    #[cfg(test)]
+    pub fn with_error_port(self, port: Port) -> Self {
+        parameter::set_builtin(self.vars(), "current-error-port", Any::Composed(Box::new(Composed::Port(port))));
+        self
+    }
+
+    /// Sets the arguments returned by `current-command-line-arguments`.
+    pub fn with_arguments(self, arguments: &[String]) -> Self {
+        parameter::set_command_line_arguments(self.vars(), arguments);
+        self
     }
 
     pub fn context(&self) -> Context<'_, 'a> {
@@ -72,15 +104,71 @@ impl<'a> Interpreter<'a> {
     }
 
     pub fn run(&self) -> Result<(), InterpreterError> {
-        for expr in self.ast.inner.iter() {
+        let mut expander = Expander::new();
+
+        for (expr, span) in self.ast.inner.iter().zip(self.ast.spans.iter()) {
+            let value = expander.expand(expr.clone())
+                .map_err(InterpreterError::from)
+                .and_then(|expr| self.context().eval_expr(&expr))
+                .map_err(|e| e.located(Some(span)))?;
+
+            if value.is_void() {
+                continue;
+            }
+
             let mut writer = String::new();
-            self.context()
-                .eval_expr(expr)?
-                .fmt(&mut writer, self)
-                .unwrap();
-            println!("{writer}");
+            value.fmt(&mut writer, self).unwrap();
+            writer.push('\n');
+            parameter::current_port(&self.context(), "current-output-port")?.write_str(&writer)?;
         }
 
         Ok(())
     }
 }
+
+/// Runs the given source, returning how the value of its last expression is displayed.
+#[cfg(test)]
+pub fn eval_source(source: &str) -> Result<String, InterpreterError> {
+    use crate::lexer::Lexer;
+
+    let tokens = Lexer::new(source).parse().unwrap();
+    let ast = Ast::try_from(tokens).unwrap();
+    let interpreter = Interpreter::new(ast);
+    let mut expander = Expander::new();
+    let mut out = String::new();
+
+    for (expr, span) in interpreter.ast.inner.iter().zip(interpreter.ast.spans.iter()) {
+        let value = expander.expand(expr.clone())
+            .map_err(InterpreterError::from)
+            .and_then(|expr| interpreter.context().eval_expr(&expr))
+            .map_err(|e| e.located(Some(span)))?;
+        out.clear();
+        value.fmt(&mut out, &interpreter).unwrap();
+    }
+
+    Ok(out)
+}
+
+#[test]
+fn test_capture_output() {
+    use crate::lexer::Lexer;
+
+    let source = "(define (greet name) (printf \"Hello, ~a!~n\" name))
+(greet \"embedder\")
+(eprintf \"to stderr~n\")
+(list 'done)";
+
+    let tokens = Lexer::new(source).parse().unwrap();
+    let ast = Ast::try_from(tokens).unwrap();
+    let output = Port::output_string();
+    let errors = Port::output_string();
+
+    Interpreter::new(ast)
+        .with_output_port(output.clone())
+        .with_error_port(errors.clone())
+        .run()
+        .unwrap();
+
+    assert_eq!(output.contents().unwrap(), "Hello, embedder!\n'(done)\n");
+    assert_eq!(errors.contents().unwrap(), "to stderr\n");
+}
@@ -0,0 +1,125 @@
+use std::fmt;
+
+use crate::span::Span;
+
+/// Most frames shown in a stack trace, the rest are counted.
+const STACK_TRACE_LIMIT: usize = 16;
+
+/// Call to a procedure being evaluated, `name` is `None` for lambdas.
+#[derive(Debug, Clone)]
+pub struct CallFrame<'a> {
+    pub name: Option<&'a str>,
+    /// Span of the form the procedure was called from, `None` when called by a native
+    pub call_site: Option<Span>
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Frame {
+    pub name: Option<String>,
+    pub call_site: Option<Span>,
+    /// Times the frame was repeated in a row, like calls in a deep recursion
+    pub repeated: usize
+}
+
+/// Calls an error went through, innermost first.
+#[derive(Debug, Clone)]
+pub struct StackTrace {
+    pub frames: Vec<Frame>,
+    /// Frames left out past the limit
+    pub elided: usize
+}
+
+impl StackTrace {
+    /// Trace of the call stack `stack`, whose last frame is the innermost.
+    pub fn capture(stack: &[CallFrame<'_>]) -> Self {
+        let mut frames = Vec::<Frame>::new();
+        let mut elided = 0;
+
+        for call in stack.iter().rev() {
+            if let Some(last) = frames.last_mut().filter(|_| elided == 0) {
+                if last.name.as_deref() == call.name && last.call_site == call.call_site {
+                    last.repeated += 1;
+                    continue;
+                }
+            }
+
+            if frames.len() == STACK_TRACE_LIMIT {
+                elided += 1;
+            } else {
+                frames.push(Frame {
+                    name: call.name.map(str::to_string),
+                    call_site: call.call_site.clone(),
+                    repeated: 1
+                });
+            }
+        }
+
+        Self { frames, elided }
+    }
+}
+
+impl fmt::Display for StackTrace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "  context...:")?;
+
+        for frame in &self.frames {
+            write!(f, "\n   ")?;
+
+            if let Some(span) = &frame.call_site {
+                write!(f, "{span} ")?;
+            }
+
+            write!(f, "{}", frame.name.as_deref().unwrap_or("lambda"))?;
+
+            if frame.repeated > 1 {
+                write!(f, " [repeated {} times]", frame.repeated)?;
+            }
+        }
+
+        if self.elided > 0 {
+            write!(f, "\n   ... and {} more", self.elided)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[test]
+fn test_stack_traces() {
+    use crate::interpreter::eval_source;
+
+    let source = "(define (inner x) (+ x \"one\"))
+(define (outer x) (list (inner x)))
+(with-handlers ([exn:fail? (lambda (e) 'caught)]) (outer 0))
+(outer 1)";
+
+    assert_eq!(eval_source(source).unwrap_err().to_string().split_once('\n').unwrap().1, "  \
+context...:
+   2:25 inner
+   4:1 outer");
+
+    let recursion = "(define (down n) (if (= n 0) (error \"bottom\") (+ 1 (down (- n 1)))))\n(down 20)";
+    assert_eq!(eval_source(recursion).unwrap_err().to_string(), "1:30: bottom
+  context...:
+   1:52 down [repeated 20 times]
+   2:1 down");
+
+    // calls in tail position replace the frame of their caller
+    let tail = "(define (down n) (if (= n 0) (error \"bottom\") (down (- n 1))))\n(down 100)";
+    assert_eq!(eval_source(tail).unwrap_err().to_string(), "1:30: bottom
+  context...:
+   1:47 down");
+
+    // procedures called by natives get a frame too
+    let mapped = "(define (f x) (+ x \"one\"))\n(map f (list 1 2))";
+    assert_eq!(eval_source(mapped).unwrap_err().to_string().split_once('\n').unwrap().1, "  \
+context...:
+   f");
+
+    let mutual = "(define (ping n) (if (= n 0) (error \"done\") (list (pong (- n 1)))))
+(define (pong n) (list (ping n)))
+(ping 40)";
+    let trace = eval_source(mutual).unwrap_err().to_string();
+    assert_eq!(trace.lines().count(), 2 + STACK_TRACE_LIMIT + 1);
+    assert!(trace.ends_with("\n   ... and 65 more"), "{trace}");
+}
@@ -29,6 +29,14 @@ impl<'a> VarsStorage<'a> {
     pub fn insert(&mut self, key: impl ToString, value: Any<'a>) {
         self.table.insert(key.to_string(), Cell::new(value));
     }
+
+    pub fn make_static(self) -> VarsStorage<'static> {
+        VarsStorage {
+            table: self.table.into_iter()
+                .map(|(k, v)| (k, Cell::new((*v).clone().make_static())))
+                .collect()
+        }
+    }
 }
 
 pub struct OwnedStorage {
@@ -1,89 +1,107 @@
-use super::{token::Token, LocatedToken};
+use super::error::LexerError;
+
+/// Length of the `#| ... |#` comment starting `buf`, block comments can be nested.
+pub fn block_comment_len(buf: &str) -> Result<usize, LexerError> {
+    let mut depth = 0usize;
+    let mut idx = 0;
+
+    while idx < buf.len() {
+        match &buf.as_bytes()[idx..buf.len().min(idx + 2)] {
+            b"#|" => {
+                depth += 1;
+                idx += 2;
+            },
+            b"|#" => {
+                depth -= 1;
+                idx += 2;
+
+                if depth == 0 {
+                    return Ok(idx);
+                }
+            },
+            _ => idx += 1
+        }
+    }
 
+    Err(LexerError::UnterminatedComment)
+}
+
+/// Position in the source being scanned, lines and columns start at 1 and columns count
+/// characters.
 pub struct LexerCursor<'a> {
     buf: &'a str,
-    line: usize,
-    buf_position: usize
+    position: usize,
+    line: u32,
+    column: u32
 }
 
 impl<'a> LexerCursor<'a> {
     pub fn new(buf: &'a str) -> Self {
         Self {
             buf,
+            position: 0,
             line: 1,
-            buf_position: 0
+            column: 1
         }
     }
 
-    pub fn line(&self) -> usize {
-        self.line
+    pub fn position(&self) -> usize {
+        self.position
     }
 
-    fn update_line(&mut self) {
-        let mut line = 1;
-
-        let mut newlines = self.buf.as_bytes().iter()
-            .enumerate()
-            .filter(|(_, c)| **c == '\n' as u8)
-            .map(|(idx, _)| idx);
+    pub fn line(&self) -> u32 {
+        self.line
+    }
 
-        let Some(mut next_newline) = newlines.next().map(|l| l) else { return; };
+    pub fn column(&self) -> u32 {
+        self.column
+    }
 
-        if self.buf_position == next_newline && self.buf.len() > self.buf_position + 1 {
-            self.buf_position += 1; // if we on \n char, advance one
-        }
-        
-        while self.buf_position > next_newline {
-            line += 1;
-
-            if let Some(next) = newlines.next() {
-                next_newline = next;
-            } else {
-                break;
-            }
-        }
+    pub fn remaining(&self) -> &'a str {
+        &self.buf[self.position..]
+    }
 
-        self.line = line;
+    pub fn is_eof(&self) -> bool {
+        self.position == self.buf.len()
     }
 
-    fn ignore_newline(&mut self) -> &'a str {
-        if self.buf[self.buf_position..].len() < 1 {
-            return &self.buf[self.buf_position..];
-        }
+    pub fn peek(&self) -> Option<char> {
+        self.remaining().chars().next()
+    }
 
-        let mut item = &self.buf[self.buf_position..];
-        while item.len() > 1 && (&item[0..1] == "\n" || &item[0..1] == "\r") {
-            item = &item[1..];
-            self.buf_position += 1;
-        }
+    pub fn starts_with(&self, prefix: &str) -> bool {
+        self.remaining().starts_with(prefix)
+    }
 
-        let mut finish = item.len();
+    pub fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.position += c.len_utf8();
 
-        while finish > 0 && (&item[finish-1..finish] == "\n" || &item[finish-1..finish] == "\r") {
-            finish -= 1;
+        if c == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
         }
 
-        &item[..finish]
+        Some(c)
     }
 
-    pub fn remaining(&mut self) -> &'a str {
-        self.ignore_newline()
+    /// Moves past the next `bytes` bytes, which have to end on a character boundary.
+    pub fn advance(&mut self, bytes: usize) {
+        let end = self.position + bytes;
+
+        while self.position < end && self.bump().is_some() {}
     }
 
-    pub fn parse_with<F, E>(&mut self, fun: F) -> Result<LocatedToken<'a>, E>
-    where
-        F: FnOnce(&'a str) -> Result<Token<'a>, E>,
-    {
-        let res = fun(self.remaining());
+    /// Moves past the characters accepted by `accept`, returning them.
+    pub fn eat_while(&mut self, accept: impl Fn(char) -> bool) -> &'a str {
+        let start = self.position;
 
-        if let Ok(token) = res.as_ref() {
-            self.buf_position += token.token_len();
+        while self.peek().map(&accept).unwrap_or(false) {
+            self.bump();
         }
 
-        let ret = res.map(|t| LocatedToken { line: self.line as u32, token: t });
-
-        self.update_line();
-
-        ret
+        &self.buf[start..self.position]
     }
 }
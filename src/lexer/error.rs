@@ -5,7 +5,25 @@ pub enum LexerError {
     #[error("Option got None")]
     OptionNone,
     #[error("End of file")]
-    Eof
+    Eof,
+    #[error("Unterminated block comment")]
+    UnterminatedComment,
+    #[error("Expected a datum after `#;`")]
+    MissingCommentedDatum,
+    #[error("Unterminated string literal")]
+    UnterminatedString,
+    #[error("Invalid escape sequence `\\{0}` in string literal")]
+    InvalidEscape(char),
+    #[error("Byte string literal contains the non-byte character `{0}`")]
+    InvalidByte(char),
+    #[error("Unknown character name `#\\{0}`")]
+    UnknownCharacterName(String),
+    #[error("Invalid regular expression: {0}")]
+    InvalidRegex(String),
+    #[error("Bad syntax `{0}`")]
+    UnknownHashSyntax(String),
+    #[error("Unsupported hash table literal `{0}`, only `#hash(` tables comparing keys with `equal?` exist")]
+    UnsupportedHash(&'static str)
 }
 
 pub trait OptionExt<T> {
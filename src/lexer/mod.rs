@@ -2,78 +2,311 @@ mod cursor;
 mod error;
 mod token;
 
-use std::{iter::{Enumerate, Map}, path::Iter, str::{Chars, Lines}};
+use std::sync::Arc;
 
 pub use {token::Token, error::LexerError};
 
-use cursor::LexerCursor;
+use crate::primitives::{parse_bytes, parse_character, parse_regex, parse_string, DataType};
+use crate::span::{Located, Span};
+
+use cursor::{block_comment_len, LexerCursor};
 
 #[derive(Debug)]
 pub struct LocatedToken<'a> {
-    pub line: u32,
+    pub span: Span,
     pub token: Token<'a>
 }
 
+/// Characters ending an atom, like an identifier or a number.
+fn is_delimiter(c: char) -> bool {
+    c.is_whitespace() || matches!(c, '(' | ')' | '[' | ']' | '{' | '}' | '"' | ';' | '\'')
+}
+
+/// Single pass scanner turning source text into tokens.
 pub struct Lexer<'a> {
-    cursor: LexerCursor<'a>
+    cursor: LexerCursor<'a>,
+    file: Option<Arc<str>>
 }
 
-fn remove_last_tokens<'a>(item: &'a str) -> &'a str {
-    if item.len() <= 1 {
-        return item;
+impl<'a> Lexer<'a> {
+    pub fn new(buf: &'a str) -> Self{
+        Self {
+            cursor: LexerCursor::new(buf),
+            file: None
+        }
     }
 
-    let mut finish_at = item.len() - 1;
+    /// Lexer whose spans point into `file`.
+    pub fn with_file(buf: &'a str, file: &str) -> Self {
+        Self {
+            file: Some(Arc::from(file)),
+            ..Self::new(buf)
+        }
+    }
 
-    while finish_at > 0 && Token::try_single(&item[finish_at..finish_at+1]).is_some() {
-        finish_at -= 1;
+    pub fn parse_all(mut self) -> Result<Vec<LocatedToken<'a>>, Located<LexerError>> {
+        let mut out = Vec::new();
+
+        loop {
+            self.cursor.eat_while(char::is_whitespace);
+
+            if self.cursor.is_eof() {
+                break;
+            }
+
+            let mut span = Span {
+                file: self.file.clone(),
+                line: self.cursor.line(),
+                column: self.cursor.column(),
+                range: self.cursor.position()..self.cursor.position()
+            };
+
+            let token = match self.next_token() {
+                Ok(token) => token,
+                Err(error) => return Err(Located { span, error })
+            };
+
+            span.range.end = self.cursor.position();
+            out.push(LocatedToken { span, token });
+        }
+
+        Ok(out)
     }
 
-    &item[..=finish_at]
-}
+    pub fn parse(self) -> Result<Vec<LocatedToken<'a>>, Located<LexerError>> {
+        self.parse_all()
+    }
 
-fn remove_incoming_tokens<'a>(item: &'a str) -> &'a str {
-    let mut idx = 0;
+    /// Reads the token at the cursor, which isn't at whitespace.
+    fn next_token(&mut self) -> Result<Token<'a>, LexerError> {
+        let single = match self.cursor.peek().ok_or(LexerError::Eof)? {
+            '(' => Token::OpenParen,
+            ')' => Token::CloseParen,
+            '[' => Token::OpenBracket,
+            ']' => Token::CloseBracket,
+            '{' => Token::OpenBraces,
+            '}' => Token::CloseBraces,
+            '\'' => Token::SingleQuote,
+            ';' => {
+                self.cursor.eat_while(|c| c != '\n');
+                return Ok(Token::Comment);
+            },
+            // strings can hold delimiters and line breaks, and escapes make their source longer
+            // than their contents
+            '"' => {
+                let (s, len) = parse_string(&self.cursor.remaining()[1..])?;
+                self.cursor.advance(len + 1);
+                return Ok(Token::Primitive(DataType::String(s)));
+            },
+            '#' => return self.prefixed(),
+            _ => return Ok(self.atom())
+        };
 
-    while idx < item.len() && Token::try_single(&item[idx..idx+1]).is_some() {
-        idx += 1;
+        self.cursor.bump();
+        Ok(single)
     }
 
-    &item[idx..]
-}
+    /// Reads the `#` prefixed syntax at the cursor.
+    fn prefixed(&mut self) -> Result<Token<'a>, LexerError> {
+        let rest = self.cursor.remaining();
 
-fn remove_single_tokens<'a>(item: &'a str) -> &'a str {
-    remove_last_tokens(remove_incoming_tokens(item))
-}
+        if self.cursor.starts_with("#|") {
+            self.cursor.advance(block_comment_len(rest)?);
+            return Ok(Token::Comment);
+        }
 
-impl<'a> Lexer<'a> {
-    pub fn new(buf: &'a str) -> Self{
-        Self {
-            cursor: LexerCursor::new(buf)
+        if self.cursor.starts_with("#;") {
+            self.cursor.advance(2);
+            self.skip_datum()?;
+            return Ok(Token::Comment);
         }
-    }
 
-    pub fn parse_all(mut self) -> Result<Vec<LocatedToken<'a>>, LexerError> {
-        let mut out = Vec::new();
+        // character literals can be delimiters themselves, like `#\(`, and their source can be
+        // longer than the way they're printed, like `#\x41`
+        if let Some(literal) = rest.strip_prefix("#\\") {
+            let (c, len) = parse_character(literal)?;
+            self.cursor.advance(len + 2);
+            return Ok(Token::Primitive(DataType::Character(c)));
+        }
 
-        while self.cursor.remaining().len() > 0 {
-            out.push(self.cursor.parse_with::<_, _>(|buf| {
-                if buf.is_empty() {
-                    return Err(LexerError::Eof);
-                }
+        if let Some(literal) = rest.strip_prefix("#\"") {
+            let (bytes, len) = parse_bytes(literal)?;
+            self.cursor.advance(len + 2);
+            return Ok(Token::Primitive(DataType::Bytes(bytes)));
+        }
 
-                if let Some(single) = Token::try_single(&buf[0..1]) {
-                    return Ok(single);
-                }
+        if self.cursor.starts_with("#(") {
+            self.cursor.advance(2);
+            return Ok(Token::OpenVector);
+        }
 
-                Ok(Token::multiple(remove_single_tokens(buf)))
-            })?);
+        if let Some(literal) = ["#hasheq(", "#hasheqv("].into_iter().find(|p| self.cursor.starts_with(p)) {
+            return Err(LexerError::UnsupportedHash(literal));
         }
 
-        Ok(out)
+        if self.cursor.starts_with("#hash(") {
+            self.cursor.advance(6);
+            return Ok(Token::OpenHash);
+        }
+
+        if self.cursor.starts_with("#&") {
+            self.cursor.advance(2);
+            return Ok(Token::BoxPrefix);
+        }
+
+        if rest.starts_with("#rx\"") || rest.starts_with("#px\"") {
+            let (regex, len) = parse_regex(rest)?;
+            self.cursor.advance(len);
+            return Ok(Token::Primitive(DataType::Regex(regex)));
+        }
+
+        // keywords like `#:when` and names like `#%app` are identifiers, anything else has to
+        // be a literal like `#t` or `#x1F`
+        match self.atom() {
+            Token::Ident(text) if !text.starts_with("#:") && !text.starts_with("#%") => {
+                Err(LexerError::UnknownHashSyntax(text.to_string()))
+            },
+            token => Ok(token)
+        }
     }
 
-    pub fn parse(self) -> Result<Vec<LocatedToken<'a>>, LexerError> {
-        self.parse_all()
+    /// Reads everything up to the next delimiter, which is a number, a `#` prefixed literal like
+    /// `#t` or `#x1F`, a lone dot or an identifier.
+    fn atom(&mut self) -> Token<'a> {
+        let text = self.cursor.eat_while(|c| !is_delimiter(c));
+
+        if text == "." {
+            return Token::Dot;
+        }
+
+        match DataType::parse(text) {
+            Some(data) => Token::Primitive(data),
+            None => Token::Ident(text)
+        }
     }
+
+    /// Moves past the datum a `#;` comment skips, along with the whitespace and comments before
+    /// it.
+    fn skip_datum(&mut self) -> Result<(), LexerError> {
+        let mut depth = 0usize;
+
+        loop {
+            self.cursor.eat_while(char::is_whitespace);
+
+            if self.cursor.is_eof() {
+                return Err(LexerError::MissingCommentedDatum);
+            }
+
+            match self.next_token()? {
+                Token::OpenParen | Token::OpenBracket | Token::OpenBraces
+                    | Token::OpenVector | Token::OpenHash => depth += 1,
+                Token::CloseParen | Token::CloseBracket | Token::CloseBraces => {
+                    depth = depth.checked_sub(1).ok_or(LexerError::MissingCommentedDatum)?;
+                },
+                // quotes and boxes belong to the datum after them
+                Token::SingleQuote | Token::BoxPrefix | Token::Comment => continue,
+                _ => ()
+            }
+
+            if depth == 0 {
+                return Ok(());
+            }
+        }
+    }
+}
+
+#[test]
+fn test_string_literals() {
+    use crate::interpreter::eval_source;
+
+    let source = r#"(list "a b" "(x) ; y" "tab\there" "q\"uote\\" "λ\x41\101"
+  "two
+lines" "joined \
+   here" 'after)"#;
+
+    let lines = Lexer::new(source).parse().unwrap()
+        .into_iter()
+        .filter(|t| matches!(t.token, Token::Primitive(_) | Token::Ident(_)))
+        .map(|t| t.span.line)
+        .collect::<Vec<_>>();
+
+    assert_eq!(lines, vec![1, 1, 1, 1, 1, 1, 2, 3, 4]);
+    assert_eq!(
+        eval_source(source).unwrap(),
+        r#"'("a b" "(x) ; y" "tab\there" "q\"uote\\" "λAA" "two\nlines" "joined here" after)"#
+    );
+    assert!(Lexer::new(r#""open"#).parse().is_err());
+    assert!(Lexer::new(r#""bad \q""#).parse().is_err());
+}
+
+#[test]
+fn test_character_literals() {
+    let error = |source| Lexer::new(source).parse().unwrap_err().error.to_string();
+
+    assert!(matches!(Lexer::new(r"#\a1").parse().unwrap()[0].token, Token::Primitive(DataType::Character('a'))));
+    assert_eq!(error(r"#\ab"), r"Unknown character name `#\ab`");
+    assert_eq!(error(r"(list #\altmode)"), r"Unknown character name `#\altmode`");
+    assert_eq!(error(r"#\xyz"), r"Unknown character name `#\xyz`");
+}
+
+#[test]
+fn test_hash_syntax() {
+    use crate::interpreter::eval_source;
+
+    let error = |source| Lexer::new(source).parse().unwrap_err().error.to_string();
+
+    assert_eq!(error("'#foo"), "Bad syntax `#foo`");
+    assert_eq!(error("(list #hashtable((a . 1)))"), "Bad syntax `#hashtable`");
+    assert_eq!(error("#'x"), "Bad syntax `#`");
+    assert_eq!(
+        error("(list #hasheq((a . 1)))"),
+        "Unsupported hash table literal `#hasheq(`, only `#hash(` tables comparing keys with `equal?` exist"
+    );
+    assert!(Lexer::new("'#hasheqv()").parse().is_err());
+    assert_eq!(eval_source("(list #T #F #true #false)").unwrap(), "'(#t #f #t #f)");
+    assert_eq!(eval_source("(for/list ([i 3] #:when (> i 0)) i)").unwrap(), "'(1 2)");
+}
+
+#[test]
+fn test_comments() {
+    use crate::interpreter::eval_source;
+
+    let source = r#";; header comment (with "parens"
+#| block comment
+   #| nested |# (still commented)
+|#
+(define x 1) ; trailing comment
+(define (f a) #;(old body) (list a #;'skipped #| inline |# "kept ; not a comment"))
+#;
+(error "never evaluated")
+(f x)"#;
+
+    assert_eq!(eval_source(source).unwrap(), r#"'(1 "kept ; not a comment")"#);
+    assert!(Lexer::new("#| open").parse().is_err());
+    assert!(Lexer::new("(a #;)").parse().is_err());
+}
+
+#[test]
+fn test_spans() {
+    use crate::interpreter::eval_source;
+
+    let source = "(define\tx 10)\n  (list x\t1abc #true #xff)  (+ x 1)\n[f . \"λ\"]";
+    let tokens = Lexer::new(source).parse().unwrap();
+
+    let located = tokens.iter()
+        .map(|t| (t.span.line, t.span.column, &source[t.span.range.clone()]))
+        .collect::<Vec<_>>();
+
+    assert_eq!(located, vec![
+        (1, 1, "("), (1, 2, "define"), (1, 9, "x"), (1, 11, "10"), (1, 13, ")"),
+        (2, 3, "("), (2, 4, "list"), (2, 9, "x"), (2, 11, "1abc"), (2, 16, "#true"), (2, 22, "#xff"), (2, 26, ")"),
+        (2, 29, "("), (2, 30, "+"), (2, 32, "x"), (2, 34, "1"), (2, 35, ")"),
+        (3, 1, "["), (3, 2, "f"), (3, 4, "."), (3, 6, "\"λ\""), (3, 9, "]")
+    ]);
+    assert!(matches!(tokens[8].token, Token::Ident("1abc")));
+    assert_eq!(eval_source("(define x 2)\n(list x 3)\n(+ x 1)  (* x 10)\n").unwrap(), "20");
+
+    // the scanner never looks back, so big inputs take linear time
+    let big = "(list 1 \"two\" #\\3 'four)\n".repeat(100_000);
+    assert_eq!(Lexer::new(&big).parse().unwrap().len(), 800_000);
 }
@@ -14,28 +14,6 @@ macro_rules! c {
     };
 }
 
-macro_rules! mfn {
-    ($it: ident, $fun: ident, match {
-        $($ex: expr => { $($tree: tt)* }),* $(,)? $(else => { $($else_tree: tt)* })?
-    }) => {
-        $(
-            if $it.$fun($ex) {
-                return { $($tree)* }
-            }
-        )*
-
-        $(
-            return { $($else_tree)* };
-        )?
-    };
-}
-
-macro_rules! swm {
-    ($it: ident, $($tree:tt)*) => {
-        crate::macros::mfn!($it, starts_with, $($tree)*);
-    };
-}
-
 macro_rules! hashmap {
     ($($k: literal => $v: expr),*) => {{
         let mut hm = std::collections::HashMap::new();
@@ -166,11 +144,17 @@ macro_rules! require_arity {
                 expected: $arity
             }.into())
         }
+    };
+    (at_most $arity: expr, $args: expr) => {
+        if $args.len() > $arity {
+            return Err(crate::native::error::NativeFnError::ArityMismatch {
+                got: $args.len() as _,
+                expected: $arity
+            }.into())
+        }
     }
 }
 
-pub(crate) use mfn;
-pub(crate) use swm;
 pub(crate) use sw;
 pub(crate) use c;
 pub(crate) use enum_from_str;
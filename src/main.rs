@@ -6,9 +6,10 @@ use clap::Parser;
 use interpreter::vars::{OwnedStorage, VarsStorage};
 use primitives::DataType;
 
-use crate::{cli::{Cli, SubCommands}, interpreter::Interpreter, lexer::Lexer};
+use crate::{cli::{Cli, SubCommands}, diagnostic::Diagnostic, interpreter::Interpreter, lexer::Lexer};
 
 mod ast;
+mod expander;
 mod interpreter;
 mod lexer;
 mod native;
@@ -16,31 +17,57 @@ mod primitives;
 mod cell;
 mod cli;
 mod container;
+mod diagnostic;
 mod display;
 mod macros;
 mod ext;
+mod reader;
+mod span;
 
 fn main() -> Result<(), Box<dyn Error>> {
     let cli = Cli::parse();
+    let colored = cli.color.enabled();
 
     match cli.command {
-        Some(SubCommands::Run { filename }) => run(&filename),
-        Some(SubCommands::Repl) | None => repl()
+        Some(SubCommands::Run { filename, args }) => run(&filename, &args, colored),
+        Some(SubCommands::Repl) | None => repl(colored)
     }
 }
 
-fn run(file: &str) -> Result<(), Box<dyn Error>> {
-    let file = std::fs::read_to_string(file)?;
-    let tokens = Lexer::new(&file).parse()?;
-    let ast = Ast::try_from(tokens
-        .into_iter()
-        .map(|t| t.token)
-        .collect::<Vec<_>>()
-    )?;
-    Interpreter::new(ast).run().map_err(From::from)
+/// Shows the diagnostics and exits with a failure status.
+fn fail(diagnostics: Vec<Diagnostic>, source: &str, colored: bool) -> ! {
+    let _ = std::io::stdout().flush();
+
+    for diagnostic in diagnostics {
+        eprint!("{}", diagnostic.render(source, colored));
+    }
+
+    std::process::exit(1)
+}
+
+fn run(file: &str, args: &[String], colored: bool) -> Result<(), Box<dyn Error>> {
+    let source = std::fs::read_to_string(file)?;
+
+    let tokens = match Lexer::with_file(&source, file).parse() {
+        Ok(tokens) => tokens,
+        Err(e) => fail(vec![Diagnostic::lexer(&e)], &source, colored)
+    };
+
+    let ast = match Ast::try_from(tokens) {
+        Ok(ast) => ast,
+        Err(e) => fail(e.0.iter().map(Diagnostic::ast).collect(), &source, colored)
+    };
+
+    let interpreter = Interpreter::new(ast).with_arguments(args);
+
+    if let Err(e) = interpreter.run() {
+        fail(vec![Diagnostic::runtime(&e, &interpreter)], &source, colored);
+    }
+
+    Ok(())
 }
 
-fn repl() -> Result<(), Box<dyn Error>> {
+fn repl(colored: bool) -> Result<(), Box<dyn Error>> {
     let mut buf = String::new();
     let mut vars = Cell::new(OwnedStorage::new());
     let mut stdout = std::io::stdout();
@@ -52,22 +79,19 @@ fn repl() -> Result<(), Box<dyn Error>> {
         let tokens = match Lexer::new(&buf).parse() {
             Ok(t) => t,
             Err(e) => {
-                eprintln!("Failed to parse tokens: {e}");
+                eprint!("{}", Diagnostic::lexer(&e).render(&buf, colored));
                 buf.clear();
                 continue;
             }
         };
 
-        let ast_res = Ast::try_from(tokens
-            .into_iter()
-            .map(|t| t.token)
-            .collect::<Vec<_>>()
-        );
-
-        let ast = match ast_res {
+        let ast = match Ast::try_from(tokens) {
             Ok(a) => a,
             Err(e) => {
-                eprintln!("Error parsing abstract syntax tree, error: {e}");
+                for error in &e.0 {
+                    eprint!("{}", Diagnostic::ast(error).render(&buf, colored));
+                }
+
                 buf.clear();
                 print!("> ");
                 continue;
@@ -76,7 +100,7 @@ fn repl() -> Result<(), Box<dyn Error>> {
 
         let i = Interpreter::with_vars(ast, vars);
         if let Err(e) = i.run() {
-            eprintln!("Runtime error: {e}");
+            eprint!("{}", Diagnostic::runtime(&e, &i).render(&buf, colored));
         }
         vars = i.vars;
         println!("");
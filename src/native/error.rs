@@ -26,7 +26,9 @@ pub enum NativeFnError {
     #[error("Feature not yet implemented: {0}")]
     NotYetImplemented(&'static str),
     #[error("Invalid type: {0}")]
-    InvalidType(String)
+    InvalidType(String),
+    #[error("/: division by zero")]
+    DivisionByZero
 }
 
 #[derive(Debug, Error)]
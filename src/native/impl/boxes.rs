@@ -0,0 +1,39 @@
+use crate::interpreter::any::AnyEval;
+use crate::interpreter::context::Context;
+use crate::interpreter::error::InterpreterError;
+use crate::macros::require_arity;
+use crate::native::error::NativeFnError;
+use crate::primitives::any::Any;
+use crate::primitives::composed::Composed;
+use crate::primitives::DataType;
+
+pub fn r#box<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(exact 1, args);
+
+    let value = cx.level_down().eval(&args[0])?;
+    Ok(Any::Composed(Box::new(Composed::Boxed(Box::new(value)))))
+}
+
+pub fn unbox<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(exact 1, args);
+
+    match cx.level_down().eval(&args[0])? {
+        Any::Composed(c) if c.is_boxed() => {
+            let Composed::Boxed(value) = *c else { unreachable!() };
+            Ok(*value)
+        },
+        other => Err(NativeFnError::UnexpectedType {
+            function: "unbox",
+            argument_position: 1,
+            got: other.variant_name(),
+            expected: "box"
+        }.into())
+    }
+}
+
+pub fn is_box<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(exact 1, args);
+
+    let value = cx.level_down().eval(&args[0])?;
+    Ok(Any::Primitive(DataType::Boolean(value.get_composed().map(|c| c.is_boxed()).unwrap_or(false))))
+}
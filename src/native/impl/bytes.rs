@@ -0,0 +1,248 @@
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::interpreter::any::AnyEval;
+use crate::interpreter::context::Context;
+use crate::interpreter::error::InterpreterError;
+use crate::macros::require_arity;
+use crate::native::error::NativeFnError;
+use crate::native::r#impl::character::require_character;
+use crate::native::r#impl::list::require_list;
+use crate::native::r#impl::string::require_string;
+use crate::native::r#impl::util::non_negative_int;
+use crate::primitives::any::Any;
+use crate::primitives::composed::{Composed, List};
+use crate::primitives::DataType;
+
+pub fn bytes_contents<'b>(value: &'b Any<'_>) -> Option<Cow<'b, [u8]>> {
+    match value {
+        Any::Primitive(DataType::Bytes(b)) => Some(Cow::Borrowed(b.as_ref())),
+        Any::Composed(c) => c.get_mutablebytes().map(|b| Cow::Owned(b.borrow().clone())),
+        _ => None
+    }
+}
+
+pub fn require_bytes<'a>(
+    cx: &mut Context<'_, 'a>,
+    arg: &AnyEval<'a>,
+    fn_name: &'static str,
+    position: u8
+) -> Result<Vec<u8>, InterpreterError> {
+    let value = cx.eval(arg)?;
+
+    bytes_contents(&value)
+        .map(Cow::into_owned)
+        .ok_or(NativeFnError::UnexpectedType {
+            function: fn_name,
+            argument_position: position,
+            got: value.variant_name(),
+            expected: "bytes"
+        }.into())
+}
+
+fn require_byte<'a>(
+    cx: &mut Context<'_, 'a>,
+    arg: &AnyEval<'a>,
+    fn_name: &'static str,
+    position: u8
+) -> Result<u8, InterpreterError> {
+    let value = cx.eval(arg)?;
+
+    value.get_primitive()
+        .and_then(DataType::get_integer)
+        .and_then(|i| u8::try_from(*i).ok())
+        .ok_or(NativeFnError::UnexpectedType {
+            function: fn_name,
+            argument_position: position,
+            got: value.variant_name(),
+            expected: "byte"
+        }.into())
+}
+
+pub fn mutable_bytes<'a>(bytes: Vec<u8>) -> Any<'a> {
+    Any::Composed(Box::new(Composed::MutableBytes(Rc::new(RefCell::new(bytes)))))
+}
+
+fn integer<'a>(value: usize) -> Any<'a> {
+    Any::Primitive(DataType::Integer(value as i32))
+}
+
+pub fn is_bytes<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(exact 1, args);
+
+    let value = cx.eval(&args[0])?;
+    Ok(Any::Primitive(DataType::Boolean(bytes_contents(&value).is_some())))
+}
+
+/// `(make-bytes length [byte])`, filled with zeros by default.
+pub fn make_bytes<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(at_least 1, args);
+    require_arity!(at_most 2, args);
+
+    let length = non_negative_int(cx, &args[0], "make-bytes", 1)?;
+    let byte = match args.get(1) {
+        Some(byte) => require_byte(cx, byte, "make-bytes", 2)?,
+        None => 0
+    };
+
+    Ok(mutable_bytes(vec![byte; length]))
+}
+
+/// `(bytes byte ...)`
+pub fn bytes<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    let bytes = args.iter()
+        .enumerate()
+        .map(|(idx, arg)| require_byte(cx, arg, "bytes", idx as u8 + 1))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(mutable_bytes(bytes))
+}
+
+pub fn bytes_length<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(exact 1, args);
+
+    let bytes = require_bytes(cx, &args[0], "bytes-length", 1)?;
+    Ok(integer(bytes.len()))
+}
+
+pub fn bytes_ref<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(exact 2, args);
+
+    let bytes = require_bytes(cx, &args[0], "bytes-ref", 1)?;
+    let index = non_negative_int(cx, &args[1], "bytes-ref", 2)?;
+
+    let byte = bytes.get(index).ok_or(InterpreterError::OutOfBounds { length: bytes.len(), got: index })?;
+    Ok(integer(*byte as usize))
+}
+
+/// `(bytes-set! bytes index byte)`, byte string literals can't be changed.
+pub fn bytes_set<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(exact 3, args);
+
+    let value = cx.eval(&args[0])?;
+    let target = value.get_composed()
+        .and_then(|c| c.get_mutablebytes())
+        .ok_or(NativeFnError::UnexpectedType {
+            function: "bytes-set!",
+            argument_position: 1,
+            got: value.variant_name(),
+            expected: "mutable bytes"
+        })?;
+
+    let index = non_negative_int(cx, &args[1], "bytes-set!", 2)?;
+    let byte = require_byte(cx, &args[2], "bytes-set!", 3)?;
+
+    let mut target = target.borrow_mut();
+    let length = target.len();
+    *target.get_mut(index).ok_or(InterpreterError::OutOfBounds { length, got: index })? = byte;
+
+    Ok(Any::Void(()))
+}
+
+/// `(subbytes bytes start [end])`
+pub fn subbytes<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(at_least 2, args);
+    require_arity!(at_most 3, args);
+
+    let bytes = require_bytes(cx, &args[0], "subbytes", 1)?;
+    let start = non_negative_int(cx, &args[1], "subbytes", 2)?;
+    let end = match args.get(2) {
+        Some(end) => non_negative_int(cx, end, "subbytes", 3)?,
+        None => bytes.len()
+    };
+
+    if end > bytes.len() || start > end {
+        return Err(InterpreterError::OutOfBounds { length: bytes.len(), got: end.max(start) });
+    }
+
+    Ok(mutable_bytes(bytes[start..end].to_vec()))
+}
+
+pub fn bytes_append<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    let mut out = Vec::new();
+
+    for (idx, arg) in args.iter().enumerate() {
+        out.extend(require_bytes(cx, arg, "bytes-append", idx as u8 + 1)?);
+    }
+
+    Ok(mutable_bytes(out))
+}
+
+/// `(bytes->string/utf-8 bytes [err-char])`, invalid sequences are replaced by `err-char` if
+/// it's given and fail otherwise.
+pub fn bytes_to_string_utf8<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(at_least 1, args);
+    require_arity!(at_most 2, args);
+
+    let bytes = require_bytes(cx, &args[0], "bytes->string/utf-8", 1)?;
+    let replacement = args.get(1)
+        .map(|c| require_character(cx, c, "bytes->string/utf-8", 2))
+        .transpose()?;
+
+    let text = match (String::from_utf8(bytes), replacement) {
+        (Ok(text), _) => text,
+        (Err(e), Some(replacement)) => String::from_utf8_lossy(e.as_bytes())
+            .replace(char::REPLACEMENT_CHARACTER, replacement.encode_utf8(&mut [0; 4])),
+        (Err(e), None) => return Err(InterpreterError::Runtime(
+            format!("bytes->string/utf-8: string is not a well-formed UTF-8 encoding ({e})")
+        ))
+    };
+
+    Ok(Any::Primitive(DataType::String(Cow::Owned(text))))
+}
+
+pub fn string_to_bytes_utf8<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(exact 1, args);
+
+    let text = require_string(cx, &args[0], "string->bytes/utf-8", 1)?;
+    Ok(mutable_bytes(text.as_bytes().to_vec()))
+}
+
+pub fn bytes_to_list<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(exact 1, args);
+
+    let bytes = require_bytes(cx, &args[0], "bytes->list", 1)?;
+    Ok(Any::Composed(Box::new(Composed::List(List(bytes.into_iter().map(|b| integer(b as usize)).collect())))))
+}
+
+pub fn list_to_bytes<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(exact 1, args);
+
+    let items = require_list(cx, &args[0], "list->bytes", 1)?;
+    let bytes = items.0.iter()
+        .map(|item| item.get_primitive()
+            .and_then(DataType::get_integer)
+            .and_then(|i| u8::try_from(*i).ok())
+            .ok_or(NativeFnError::UnexpectedType {
+                function: "list->bytes",
+                argument_position: 1,
+                got: item.variant_name(),
+                expected: "(listof byte?)"
+            }))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(mutable_bytes(bytes))
+}
+
+#[test]
+fn test_bytes() {
+    use crate::interpreter::eval_source;
+
+    let source = r#"(define buffer (make-bytes 3 (char->integer #\A)))
+(bytes-set! buffer 1 (char->integer #\newline))
+(define out (open-output-bytes))
+(write-bytes #"\x00\xffz" out)
+(display "λ" out)
+(define in (open-input-bytes #"read me"))
+(list #"abc" #"a\tb\"\\" #"\x00\3771" buffer (bytes 104 105) (bytes? #"x") (bytes? "x")
+  (bytes-length #"\x41\x42") (bytes-ref #"AB" 1) (subbytes #"hello" 1 3) (bytes-append #"a" #"b" #"c")
+  (bytes->string/utf-8 #"\316\273") (bytes->string/utf-8 #"a\377" #\?) (string->bytes/utf-8 "λ")
+  (bytes->list #"AB") (list->bytes (list 67 68)) (equal? #"ab" (bytes 97 98))
+  (get-output-bytes out) (read-string 4 in))"#;
+
+    assert_eq!(
+        eval_source(source).unwrap(),
+        r#"'(#"abc" #"a\tb\"\\" #"\0\3771" #"A\nA" #"hi" #t #f 2 66 #"el" #"abc" "λ" "a?" #"\316\273" (65 66) #"CD" #t #"\0\377z\316\273" "read")"#
+    );
+}
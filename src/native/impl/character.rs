@@ -0,0 +1,173 @@
+use std::cmp::Ordering;
+
+use crate::interpreter::any::AnyEval;
+use crate::interpreter::context::Context;
+use crate::interpreter::error::InterpreterError;
+use crate::macros::require_arity;
+use crate::native::error::NativeFnError;
+use crate::primitives::any::Any;
+use crate::primitives::DataType;
+
+pub fn require_character<'a>(
+    cx: &mut Context<'_, 'a>,
+    arg: &AnyEval<'a>,
+    fn_name: &'static str,
+    position: u8
+) -> Result<char, InterpreterError> {
+    match cx.eval(arg)? {
+        Any::Primitive(DataType::Character(c)) => Ok(c),
+        other => Err(NativeFnError::UnexpectedType {
+            function: fn_name,
+            argument_position: position,
+            got: other.variant_name(),
+            expected: "char"
+        }.into())
+    }
+}
+
+fn boolean<'a>(value: bool) -> Any<'a> {
+    Any::Primitive(DataType::Boolean(value))
+}
+
+fn character<'a>(c: char) -> Any<'a> {
+    Any::Primitive(DataType::Character(c))
+}
+
+pub fn is_char<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(exact 1, args);
+
+    let value = cx.eval(&args[0])?;
+    Ok(boolean(matches!(value, Any::Primitive(DataType::Character(_)))))
+}
+
+pub fn char_to_integer<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(exact 1, args);
+
+    let c = require_character(cx, &args[0], "char->integer", 1)?;
+    Ok(Any::Primitive(DataType::Integer(c as i32)))
+}
+
+/// `(integer->char code-point)`, fails for surrogates and values past the last code point.
+pub fn integer_to_char<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(exact 1, args);
+
+    let value = cx.eval(&args[0])?;
+    let c = value.get_primitive()
+        .and_then(DataType::get_integer)
+        .and_then(|i| u32::try_from(*i).ok())
+        .and_then(char::from_u32)
+        .ok_or(NativeFnError::UnexpectedType {
+            function: "integer->char",
+            argument_position: 1,
+            got: value.variant_name(),
+            expected: "valid code point"
+        })?;
+
+    Ok(character(c))
+}
+
+/// Maps the character with `map` when that gives a single character, like Racket's simple case
+/// conversions do.
+fn map_case<'a, I: Iterator<Item = char>>(
+    cx: &mut Context<'_, 'a>,
+    args: &[AnyEval<'a>],
+    fn_name: &'static str,
+    map: fn(char) -> I
+) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(exact 1, args);
+
+    let c = require_character(cx, &args[0], fn_name, 1)?;
+    let mut mapped = map(c);
+
+    Ok(character(match (mapped.next(), mapped.next()) {
+        (Some(single), None) => single,
+        _ => c
+    }))
+}
+
+pub fn char_upcase<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    map_case(cx, args, "char-upcase", char::to_uppercase)
+}
+
+pub fn char_downcase<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    map_case(cx, args, "char-downcase", char::to_lowercase)
+}
+
+fn char_test<'a>(
+    cx: &mut Context<'_, 'a>,
+    args: &[AnyEval<'a>],
+    fn_name: &'static str,
+    test: fn(char) -> bool
+) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(exact 1, args);
+
+    let c = require_character(cx, &args[0], fn_name, 1)?;
+    Ok(boolean(test(c)))
+}
+
+pub fn is_char_alphabetic<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    char_test(cx, args, "char-alphabetic?", char::is_alphabetic)
+}
+
+pub fn is_char_numeric<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    char_test(cx, args, "char-numeric?", char::is_numeric)
+}
+
+pub fn is_char_whitespace<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    char_test(cx, args, "char-whitespace?", char::is_whitespace)
+}
+
+/// Whether every pair of adjacent characters compares the way `accept` wants.
+fn compare_chars<'a>(
+    cx: &mut Context<'_, 'a>,
+    args: &[AnyEval<'a>],
+    fn_name: &'static str,
+    fold_case: bool,
+    accept: fn(Ordering) -> bool
+) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(at_least 1, args);
+
+    let chars = args.iter()
+        .enumerate()
+        .map(|(idx, arg)| require_character(cx, arg, fn_name, idx as u8 + 1))
+        .map(|c| c.map(|c| if fold_case { c.to_lowercase().next().unwrap_or(c) } else { c }))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(boolean(chars.windows(2).all(|pair| accept(pair[0].cmp(&pair[1])))))
+}
+
+macro_rules! char_comparisons {
+    ($($name: ident, $fn_name: literal, $fold_case: literal => $accept: expr),* $(,)?) => {$(
+        pub fn $name<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+            compare_chars(cx, args, $fn_name, $fold_case, $accept)
+        }
+    )*};
+}
+
+char_comparisons! {
+    char_eq, "char=?", false => Ordering::is_eq,
+    char_lt, "char<?", false => Ordering::is_lt,
+    char_gt, "char>?", false => Ordering::is_gt,
+    char_le, "char<=?", false => Ordering::is_le,
+    char_ge, "char>=?", false => Ordering::is_ge,
+    char_ci_eq, "char-ci=?", true => Ordering::is_eq,
+    char_ci_lt, "char-ci<?", true => Ordering::is_lt,
+    char_ci_gt, "char-ci>?", true => Ordering::is_gt,
+    char_ci_le, "char-ci<=?", true => Ordering::is_le,
+    char_ci_ge, "char-ci>=?", true => Ordering::is_ge,
+}
+
+#[test]
+fn test_characters() {
+    use crate::interpreter::eval_source;
+
+    let source = "(list #\\newline #\\tab #\\nul #\\λ #\\x41 #\\( #\\space (char->integer #\\λ) (integer->char 955)
+  (char-upcase #\\ä) (char-downcase #\\A) (char-alphabetic? #\\λ) (char-numeric? #\\7) (char-whitespace? #\\tab)
+  (char<? #\\a #\\b #\\c) (char=? #\\a #\\a #\\b) (char-ci=? #\\a #\\A) (char? #\\a) (char? \"a\")
+  (string #\\a #\\b) (string-length \"λx\") (string-ref \"aλ\" 1) (list->string (list #\\x41 #\\u3BB)))";
+
+    assert_eq!(
+        eval_source(source).unwrap(),
+        "'(#\\newline #\\tab #\\nul #\\λ #\\A #\\( #\\space 955 #\\λ #\\Ä #\\a #t #t #t #t #f #t #t #f \"ab\" 2 #\\λ \"Aλ\")"
+    );
+}
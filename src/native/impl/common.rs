@@ -2,14 +2,15 @@ use crate::ast::expr::Expr;
 use crate::interpreter::any::AnyEval;
 use crate::interpreter::context::Context;
 use crate::interpreter::error::InterpreterError;
+use crate::interpreter::eval_tree::EvalTree;
 use crate::macros::require_arity;
 use crate::native::error::{DeclaredFunctionError, NativeFnError};
 use crate::primitives::any::Any;
-use crate::primitives::composed::{Composed, Function};
+use crate::primitives::composed::{Composed, Function, LambdaFunction};
 use crate::primitives::DataType;
 
 pub fn define<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
-    require_arity!(exact 2, args);
+    require_arity!(at_least 2, args);
 
     let ident_error = |item| InterpreterError::NativeError(NativeFnError::IdentifierExpectedIn {
         call: "define",
@@ -54,3 +55,143 @@ pub fn define<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<
 
     Ok(Any::Void(()))
 }
+
+pub fn void<'a>(_: &mut Context<'_, 'a>, _: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    Ok(Any::Void(()))
+}
+
+/// `(values v)`, multiple values are only supported at the end of the body of `for/hash` and
+/// `for/fold`, which read them from the form itself.
+pub fn values<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    match args {
+        [] => Ok(Any::Void(())),
+        [value] => cx.level_down().eval(value),
+        _ => Err(InterpreterError::Runtime(
+            "values: multiple values are only supported at the end of for/hash and for/fold bodies".to_string()
+        ))
+    }
+}
+
+pub fn lambda<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(at_least 2, args);
+
+    let tree = EvalTree {
+        node: Some(AnyEval::Ident("lambda")),
+        children: args.to_vec(),
+        span: None
+    };
+
+    Ok(Any::Composed(Box::new(Composed::Lambda(LambdaFunction {
+        captured: cx.local_vars().table.iter().map(|(k, v)| (k.clone(), (**v).clone())).collect(),
+        ..tree.try_parse_lambda()?
+    }))))
+}
+
+/// Evaluates every expression in order, returning the value of the last one, which is in tail
+/// position if the sequence is.
+pub fn eval_sequence<'a>(cx: &mut Context<'_, 'a>, body: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    let Some((last, init)) = body.split_last() else {
+        return Ok(Any::Void(()));
+    };
+
+    for expr in init {
+        cx.non_tail(|cx| cx.eval(expr))?;
+    }
+
+    cx.eval(last)
+}
+
+pub fn begin<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(at_least 1, args);
+    eval_sequence(cx, args)
+}
+
+/// Splits a binding list like `([name value] ...)` into its names and unevaluated values.
+fn bindings<'a, 'b>(
+    args: &'b [AnyEval<'a>],
+    call: &'static str
+) -> Result<Vec<(&'a str, &'b AnyEval<'a>)>, InterpreterError> {
+    let Some(list) = args[0].get_expression() else {
+        return Err(InterpreterError::InvalidExpression);
+    };
+
+    let mut out = Vec::with_capacity(list.children.len() + 1);
+
+    for binding in list.node.iter().chain(list.children.iter()) {
+        let Some(binding) = binding.get_expression() else {
+            return Err(InterpreterError::InvalidExpression);
+        };
+
+        let name = binding.node.as_ref().ok_or(InterpreterError::InvalidExpression)?;
+        let Some(name) = name.get_ident() else {
+            return Err(NativeFnError::IdentifierExpectedIn {
+                call,
+                got: name.variant_name().to_string()
+            }.into());
+        };
+
+        if binding.children.len() != 1 {
+            return Err(InterpreterError::InvalidExpression);
+        }
+
+        out.push((*name, &binding.children[0]));
+    }
+
+    Ok(out)
+}
+
+pub fn r#let<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(at_least 2, args);
+
+    let mut scope = cx.tail_level_down();
+
+    for (name, value) in bindings(args, "let")? {
+        let value = cx.level_down().eval(value)?;
+        scope.vars_mut().insert(name, value);
+    }
+
+    eval_sequence(&mut scope, &args[1..])
+}
+
+pub fn letrec<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(at_least 2, args);
+
+    let mut scope = cx.tail_level_down();
+    let bindings = bindings(args, "letrec")?;
+
+    for (name, value) in &bindings {
+        let value = scope.level_down().eval(value)?;
+        scope.vars_mut().insert(name, value);
+    }
+
+    // lambdas captured the scope before the names bound after them, and their own, were in it
+    let group = bindings.iter()
+        .filter_map(|(name, _)| Some((name.to_string(), scope.get_local_var(name)?.clone())))
+        .collect::<Vec<_>>();
+
+    for (name, value) in &group {
+        if let Some(Composed::Lambda(lambda)) = value.get_composed().map(Box::as_ref) {
+            let mut lambda = lambda.clone();
+            lambda.captured.extend(group.iter().cloned());
+            scope.vars_mut().insert(name, Any::Composed(Box::new(Composed::Lambda(lambda))));
+        }
+    }
+
+    eval_sequence(&mut scope, &args[1..])
+}
+
+#[test]
+fn test_closures() {
+    use crate::interpreter::eval_source;
+
+    assert_eq!(eval_source("(define add5 (let ([k 5]) (lambda (y) (+ k y)))) (add5 1)").unwrap(), "6");
+    assert_eq!(eval_source("(let ([k 1]) (let ([f (lambda () k)]) (let ([k 2]) (f))))").unwrap(), "1");
+    assert_eq!(
+        eval_source("(define thunks (for/list ([i 3]) (lambda () i))) (map (lambda (f) (f)) thunks)").unwrap(),
+        "'(0 1 2)"
+    );
+    assert_eq!(
+        eval_source("(define (make) (letrec ([loop (lambda (i) (if (= i 0) 'done (loop (- i 1))))]) loop)) ((make) 5)").unwrap(),
+        "'done"
+    );
+}
@@ -6,15 +6,18 @@ use crate::primitives::any::Any;
 use crate::interpreter::context::Context;
 use crate::interpreter::error::InterpreterError;
 use crate::native::error::NativeFnError;
-use crate::primitives::composed::{Composed, List, Pair};
+use crate::primitives::composed::{Composed, List};
 
+/// Consing onto a list produces a list, anything else produces a pair. Lists and pairs are
+/// different values here, so `(cons 1 '(2))` has to build the list `'(1 2)` for `length`, `map`
+/// and the printer to see a list, like they do in Racket.
 pub fn cons<'a>(cx: &mut Context<'_, 'a>, inputs: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
     require_arity!(exact 2, inputs);
 
-    Ok(Any::Composed(Box::new(Composed::Pair(Pair {
-        left: cx.level_down().eval(&inputs[0])?,
-        right: cx.level_down().eval(&inputs[1])?
-    }))))
+    let left = cx.level_down().eval(&inputs[0])?;
+    let right = cx.level_down().eval(&inputs[1])?;
+
+    Ok(Any::cons(left, right))
 }
 
 pub fn list<'a>(cx: &mut Context<'_, 'a>, inputs: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
@@ -26,3 +29,13 @@ pub fn list<'a>(cx: &mut Context<'_, 'a>, inputs: &[AnyEval<'a>]) -> Result<Any<
 
     Ok(Any::Composed(Box::new(Composed::List(List(items)))))
 }
+
+#[test]
+fn test_cons() {
+    use crate::interpreter::eval_source;
+
+    assert_eq!(eval_source("(cons 1 (list 2 3))").unwrap(), "'(1 2 3)");
+    assert_eq!(eval_source("(length (cons 1 (cons 2 '())))").unwrap(), "2");
+    assert_eq!(eval_source("(map (lambda (x) (* x 2)) (cons 1 (list 2)))").unwrap(), "'(2 4)");
+    assert_eq!(eval_source("(cons 1 2)").unwrap(), "'(1 . 2)");
+}
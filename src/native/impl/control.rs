@@ -0,0 +1,72 @@
+use crate::interpreter::any::AnyEval;
+use crate::interpreter::context::Context;
+use crate::interpreter::error::InterpreterError;
+use crate::macros::require_arity;
+use crate::native::error::NativeFnError;
+use crate::native::r#impl::common::eval_sequence;
+use crate::primitives::any::Any;
+
+/// `(call/ec proc)`, calls the procedure with an escape continuation. Continuations can only jump
+/// out of the evaluation that captured them, so `call/cc` behaves the same way.
+pub fn call_ec<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(exact 1, args);
+
+    let procedure = cx.level_down().eval(&args[0])?;
+    cx.with_escape(|cx, k| cx.apply(&procedure, &[AnyEval::from_any(k)]))
+}
+
+/// `(let/ec k body ...)`, evaluates the body with `k` bound to an escape continuation.
+pub fn let_ec<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(at_least 2, args);
+
+    let name = args[0].get_ident()
+        .copied()
+        .ok_or(NativeFnError::IdentifierExpectedIn {
+            call: "let/ec",
+            got: args[0].variant_name().to_string()
+        })?;
+
+    cx.with_escape(|cx, k| {
+        let mut scope = cx.level_down();
+        scope.vars_mut().insert(name, k);
+        eval_sequence(&mut scope, &args[1..])
+    })
+}
+
+/// `(dynamic-wind before thunk after)`, runs `after` even when `thunk` is left through a
+/// continuation or an exception.
+pub fn dynamic_wind<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(exact 3, args);
+
+    let before = cx.level_down().eval(&args[0])?;
+    let thunk = cx.level_down().eval(&args[1])?;
+    let after = cx.level_down().eval(&args[2])?;
+
+    cx.apply(&before, &[])?;
+    let result = cx.apply(&thunk, &[]);
+    cx.apply(&after, &[])?;
+
+    result
+}
+
+#[test]
+fn test_escape_continuations() {
+    use crate::interpreter::eval_source;
+
+    let source = "(define (find-first pred items)
+  (let/ec return (for ([item items])
+    (when (pred item) (return item)))
+    #f))
+(define normal (dynamic-wind void (lambda () 'body) (lambda () 'ignored)))
+(define escaped (let/ec outer (let/ec inner
+    (dynamic-wind void (lambda () (inner 'jumped)) (lambda () (outer 'after-escape))))))
+(define raised (let/ec outer (with-handlers ([exn:fail? (lambda (e) 'handled)])
+    (dynamic-wind void (lambda () (error \"boom\")) (lambda () (outer 'after-raise))))))
+(define saved (call-with-escape-continuation (lambda (k) k)))
+(define stale (with-handlers ([exn:fail? (lambda (e) 'stale)]) (saved 'again)))
+(define uncaught (call/cc (lambda (k) (with-handlers ([(lambda (e) #t) (lambda (e) 'caught)]) (k 'escaped)))))
+(list (find-first string? (list 1 \"x\" 5)) (find-first string? (list 1 3)) normal escaped raised stale uncaught)";
+
+    assert_eq!(eval_source(source).unwrap(), "'(\"x\" #f body after-escape after-raise stale escaped)");
+    assert_eq!(eval_source("(define (f k) (let/ec k (k 1) 2)) (f 5)").unwrap(), "1");
+}
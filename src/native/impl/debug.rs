@@ -1,5 +1,7 @@
 use crate::{interpreter::{context::Context, error::InterpreterError}, primitives::{any::Any, DataType}};
 use crate::interpreter::any::AnyEval;
+use crate::display::InterpreterDisplay;
+use crate::expander::Expander;
 
 use super::super::error::NativeFnError;
 
@@ -9,6 +11,20 @@ pub fn ast<'a>(cx: &mut Context<'_, 'a>, _: &[AnyEval<'a>]) -> Result<Any<'a>, I
     Ok(Any::Void(()))
 }
 
+pub fn expand<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    let mut expander = Expander::new();
+
+    for arg in args {
+        let mut writer = String::new();
+        expander.expand(arg.clone().to_expr())?
+            .fmt(&mut writer, cx.interpreter())
+            .unwrap();
+        println!("{writer}");
+    }
+
+    Ok(Any::Void(()))
+}
+
 pub fn clear_terminal<'a>(cx: &mut Context, _: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
     print!("\x1B[2J\x1B[1;1H");
     Ok(Any::Void(()))
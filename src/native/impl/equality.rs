@@ -0,0 +1,101 @@
+use std::sync::Arc;
+
+use crate::interpreter::any::AnyEval;
+use crate::interpreter::context::Context;
+use crate::interpreter::error::InterpreterError;
+use crate::macros::require_arity;
+use crate::native::r#impl::bytes::bytes_contents;
+use crate::native::r#impl::string::string_contents;
+use crate::primitives::any::Any;
+use crate::primitives::composed::Composed;
+use crate::primitives::DataType;
+
+pub fn primitive_eqv(left: &DataType<'_>, right: &DataType<'_>) -> bool {
+    use DataType::*;
+
+    match (left, right) {
+        (String(l), String(r)) => l == r,
+        (Character(l), Character(r)) => l == r,
+        (Integer(l), Integer(r)) => l == r,
+        (Rational(l), Rational(r)) => l.left == r.left && l.right == r.right,
+        (Complex(l), Complex(r)) => l.real == r.real && l.imaginary == r.imaginary,
+        (Floating(l), Floating(r)) => l == r,
+        (Double(l), Double(r)) => l == r,
+        (Hex(l), Hex(r)) | (Octal(l), Octal(r)) | (Binary(l), Binary(r)) => l.inner == r.inner,
+        (Bytes(l), Bytes(r)) => l == r,
+        (Regex(l), Regex(r)) => l.repr == r.repr && l.source() == r.source(),
+        (Boolean(l), Boolean(r)) => l == r,
+        _ => false
+    }
+}
+
+pub fn eqv(left: &Any<'_>, right: &Any<'_>) -> bool {
+    match (left, right) {
+        (Any::Primitive(l), Any::Primitive(r)) => primitive_eqv(l, r),
+        (Any::Void(_), Any::Void(_)) => true,
+        // symbols and the empty list are compared by identity
+        (Any::Composed(l), Any::Composed(r)) => match (l.as_ref(), r.as_ref()) {
+            (Composed::Symbol(l), Composed::Symbol(r)) => l.0 == r.0,
+            (Composed::List(l), Composed::List(r)) => l.0.is_empty() && r.0.is_empty(),
+            (Composed::Eof(_), Composed::Eof(_)) => true,
+            _ => false
+        },
+        _ => false
+    }
+}
+
+pub fn equal(left: &Any<'_>, right: &Any<'_>) -> bool {
+    if eqv(left, right) {
+        return true;
+    }
+
+    if let (Some(left), Some(right)) = (string_contents(left), string_contents(right)) {
+        return left == right;
+    }
+
+    if let (Some(left), Some(right)) = (bytes_contents(left), bytes_contents(right)) {
+        return left == right;
+    }
+
+    if let (Some(left), Some(right)) = (left.list_items(), right.list_items()) {
+        return left.len() == right.len()
+            && left.iter().zip(right.iter()).all(|(l, r)| equal(l, r));
+    }
+
+    let (Any::Composed(left), Any::Composed(right)) = (left, right) else {
+        return false;
+    };
+
+    match (left.as_ref(), right.as_ref()) {
+        (Composed::Pair(l), Composed::Pair(r)) => equal(&l.left, &r.left) && equal(&l.right, &r.right),
+        (Composed::Vector(l), Composed::Vector(r)) => l.0.len() == r.0.len()
+            && l.0.iter().zip(r.0.iter()).all(|(l, r)| equal(l, r)),
+        (Composed::Hash(l), Composed::Hash(r)) => l.0.len() == r.0.len()
+            && l.0.iter().all(|(key, value)| r.0.iter()
+                .any(|(other_key, other)| equal(key, other_key) && equal(value, other))),
+        (Composed::Boxed(l), Composed::Boxed(r)) => equal(l, r),
+        (Composed::Path(l), Composed::Path(r)) => l == r,
+        (Composed::Struct(l), Composed::Struct(r)) => Arc::ptr_eq(&l.kind, &r.kind)
+            && l.kind.transparent
+            && l.fields.iter().zip(r.fields.iter()).all(|(l, r)| equal(l, r)),
+        _ => false
+    }
+}
+
+pub fn is_eqv<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(exact 2, args);
+
+    let left = cx.level_down().eval(&args[0])?;
+    let right = cx.level_down().eval(&args[1])?;
+
+    Ok(Any::Primitive(DataType::Boolean(eqv(&left, &right))))
+}
+
+pub fn is_equal<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(exact 2, args);
+
+    let left = cx.level_down().eval(&args[0])?;
+    let right = cx.level_down().eval(&args[1])?;
+
+    Ok(Any::Primitive(DataType::Boolean(equal(&left, &right))))
+}
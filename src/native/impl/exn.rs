@@ -0,0 +1,368 @@
+use std::io::ErrorKind;
+use std::sync::Arc;
+
+use lazy_static::lazy_static;
+
+use crate::expander::ExpandError;
+use crate::interpreter::any::AnyEval;
+use crate::interpreter::context::Context;
+use crate::interpreter::error::InterpreterError;
+use crate::interpreter::Interpreter;
+use crate::macros::require_arity;
+use crate::native::error::NativeFnError;
+use crate::native::r#impl::common::eval_sequence;
+use crate::native::r#impl::format::{display_string, format_string, write_string};
+use crate::primitives::any::Any;
+use crate::primitives::composed::Composed;
+use crate::primitives::structs::{Struct, StructOp, StructProcedure, StructType};
+use crate::primitives::DataType;
+
+fn exn_type(name: &str, parent: Option<&Arc<StructType>>, fields: &[&str]) -> Arc<StructType> {
+    Arc::new(StructType {
+        name: name.to_string(),
+        fields: fields.iter().map(|f| f.to_string()).collect(),
+        parent: parent.cloned(),
+        transparent: true
+    })
+}
+
+lazy_static! {
+    pub static ref EXN: Arc<StructType> = exn_type("exn", None, &["message", "continuation-marks"]);
+    pub static ref EXN_FAIL: Arc<StructType> = exn_type("exn:fail", Some(&EXN), &[]);
+    pub static ref EXN_FAIL_CONTRACT: Arc<StructType> = exn_type("exn:fail:contract", Some(&EXN_FAIL), &[]);
+    pub static ref EXN_FAIL_CONTRACT_DIVIDE_BY_ZERO: Arc<StructType>
+        = exn_type("exn:fail:contract:divide-by-zero", Some(&EXN_FAIL_CONTRACT), &[]);
+    pub static ref EXN_FAIL_CONTRACT_VARIABLE: Arc<StructType>
+        = exn_type("exn:fail:contract:variable", Some(&EXN_FAIL_CONTRACT), &[]);
+    pub static ref EXN_FAIL_USER: Arc<StructType> = exn_type("exn:fail:user", Some(&EXN_FAIL), &[]);
+    pub static ref EXN_FAIL_FILESYSTEM: Arc<StructType> = exn_type("exn:fail:filesystem", Some(&EXN_FAIL), &[]);
+    pub static ref EXN_FAIL_FILESYSTEM_EXISTS: Arc<StructType>
+        = exn_type("exn:fail:filesystem:exists", Some(&EXN_FAIL_FILESYSTEM), &[]);
+}
+
+/// Builtin exception type named like `name`, so user structs can inherit from them.
+pub fn builtin_type(name: &str) -> Option<Arc<StructType>> {
+    [
+        &*EXN,
+        &*EXN_FAIL,
+        &*EXN_FAIL_CONTRACT,
+        &*EXN_FAIL_CONTRACT_DIVIDE_BY_ZERO,
+        &*EXN_FAIL_CONTRACT_VARIABLE,
+        &*EXN_FAIL_USER,
+        &*EXN_FAIL_FILESYSTEM,
+        &*EXN_FAIL_FILESYSTEM_EXISTS
+    ].into_iter()
+        .find(|kind| kind.name == name)
+        .cloned()
+}
+
+pub fn make_exn<'a>(kind: &Arc<StructType>, message: String) -> Any<'a> {
+    Any::Composed(Box::new(Composed::Struct(Struct {
+        kind: Arc::clone(kind),
+        fields: vec![
+            Any::Primitive(DataType::String(message.into())),
+            Any::Primitive(DataType::Boolean(false))
+        ]
+    })))
+}
+
+/// Error raising the value, with the message shown if nothing handles it.
+pub fn raise_value(interpreter: &Interpreter<'_>, value: Any<'_>) -> InterpreterError {
+    let message = match exn_message_of(&value) {
+        Some(message) => message.to_string(),
+        None => format!("uncaught exception: {}", write_string(&value, interpreter))
+    };
+
+    InterpreterError::Raised { value: value.make_static(), message }
+}
+
+fn exn_message_of<'b>(value: &'b Any<'_>) -> Option<&'b str> {
+    let instance = value.get_composed()?.get_struct()?;
+
+    if !instance.kind.is_a(&EXN) {
+        return None;
+    }
+
+    instance.fields[0].get_primitive()?.get_string().map(|s| s.as_ref())
+}
+
+/// Value seen by `with-handlers` for the error, native errors become exn structs while syntax
+/// errors and continuation jumps can't be caught.
+pub fn error_value(error: &InterpreterError) -> Option<Any<'static>> {
+    let kind: &Arc<StructType> = match error.unlocated() {
+        InterpreterError::Raised { value, .. } => return Some(value.clone()),
+        InterpreterError::Expansion(_)
+            | InterpreterError::MissingTreeNode
+            | InterpreterError::Escape { .. } => return None,
+        InterpreterError::NativeError(NativeFnError::DivisionByZero) => &EXN_FAIL_CONTRACT_DIVIDE_BY_ZERO,
+        InterpreterError::UnknownIdentifier { .. } | InterpreterError::UndefinedFunction { .. } => &EXN_FAIL_CONTRACT_VARIABLE,
+        InterpreterError::NativeError(_)
+            | InterpreterError::DeclaredFnError(_)
+            | InterpreterError::OutOfBounds { .. }
+            | InterpreterError::NotAProcedure(_) => &EXN_FAIL_CONTRACT,
+        InterpreterError::Io(e) if e.kind() == ErrorKind::AlreadyExists => &EXN_FAIL_FILESYSTEM_EXISTS,
+        InterpreterError::Io(_) => &EXN_FAIL_FILESYSTEM,
+        _ => &EXN_FAIL
+    };
+
+    Some(make_exn(kind, error.message()))
+}
+
+pub fn raise<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(at_least 1, args);
+
+    let value = cx.level_down().eval(&args[0])?;
+    Err(raise_value(cx.interpreter(), value))
+}
+
+/// Message built by `error` and `raise-user-error` from either `'name`, `'name format v ...` or
+/// `message v ...`.
+fn error_message<'a>(
+    cx: &mut Context<'_, 'a>,
+    args: &[AnyEval<'a>],
+    fn_name: &'static str
+) -> Result<String, InterpreterError> {
+    let values = args.iter()
+        .map(|arg| cx.level_down().eval(arg))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let interpreter = cx.interpreter();
+
+    if let Some(name) = values[0].get_symbol() {
+        let Some(template) = values.get(1) else {
+            return Ok(format!("error: {name}"));
+        };
+
+        let template = template.get_primitive()
+            .and_then(DataType::get_string)
+            .ok_or(NativeFnError::UnexpectedType {
+                function: fn_name,
+                argument_position: 2,
+                got: template.variant_name(),
+                expected: "string"
+            })?;
+
+        return Ok(format!("{name}: {}", format_string(interpreter, fn_name, template, &values[2..])?));
+    }
+
+    match &values[0] {
+        Any::Primitive(DataType::String(message)) => Ok(values[1..].iter()
+            .fold(message.to_string(), |out, value| out + " " + &write_string(value, interpreter))),
+        other => Err(NativeFnError::UnexpectedType {
+            function: fn_name,
+            argument_position: 1,
+            got: other.variant_name(),
+            expected: "symbol or string"
+        }.into())
+    }
+}
+
+pub fn error<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(at_least 1, args);
+
+    let message = error_message(cx, args, "error")?;
+    Err(raise_value(cx.interpreter(), make_exn(&EXN_FAIL, message)))
+}
+
+pub fn raise_user_error<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(at_least 1, args);
+
+    let message = error_message(cx, args, "raise-user-error")?;
+    Err(raise_value(cx.interpreter(), make_exn(&EXN_FAIL_USER, message)))
+}
+
+fn ordinal(n: usize) -> String {
+    let suffix = match (n % 10, n % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th"
+    };
+
+    format!("{n}{suffix}")
+}
+
+/// `(raise-argument-error name expected value)` or `(raise-argument-error name expected bad-pos
+/// value ...)`, where the position indexes the given values starting at 0.
+pub fn raise_argument_error<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(at_least 3, args);
+
+    let values = args.iter()
+        .map(|arg| cx.level_down().eval(arg))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let interpreter = cx.interpreter();
+
+    let name = values[0].get_symbol()
+        .ok_or(NativeFnError::UnexpectedType {
+            function: "raise-argument-error",
+            argument_position: 1,
+            got: values[0].variant_name(),
+            expected: "symbol"
+        })?;
+
+    let expected = display_string(&values[1], interpreter);
+
+    let mut message = format!("{name}: contract violation\n  expected: {expected}\n  given: ");
+
+    if values.len() == 3 {
+        message += &write_string(&values[2], interpreter);
+    } else {
+        let position = values[2].get_primitive()
+            .and_then(DataType::get_integer)
+            .filter(|pos| **pos >= 0 && (**pos as usize) < values.len() - 3)
+            .ok_or(NativeFnError::UnexpectedType {
+                function: "raise-argument-error",
+                argument_position: 3,
+                got: values[2].variant_name(),
+                expected: "index of the given values"
+            })?;
+
+        let position = *position as usize;
+        message += &write_string(&values[3 + position], interpreter);
+        message += &format!("\n  argument position: {}", ordinal(position + 1));
+    }
+
+    Err(raise_value(interpreter, make_exn(&EXN_FAIL_CONTRACT, message)))
+}
+
+/// `(with-handlers ([predicate handler] ...) body ...)`, calls the handler of the first predicate
+/// accepting the raised value, re-raising it when none does.
+pub fn with_handlers<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(at_least 2, args);
+
+    let bad_clause = || ExpandError::BadSyntax {
+        form: "with-handlers",
+        reason: "expected handler clauses like [predicate handler]"
+    };
+
+    let clauses = args[0].get_expression()
+        .ok_or_else(bad_clause)?
+        .as_vec()
+        .into_iter()
+        .map(|clause| clause.get_expression()
+            .map(|c| c.as_vec())
+            .and_then(|c| <[_; 2]>::try_from(c).ok())
+            .ok_or_else(bad_clause))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let error = match eval_sequence(&mut cx.level_down(), &args[1..]) {
+        Err(error) => error,
+        ok => return ok
+    };
+
+    let Some(value) = error_value(&error) else {
+        return Err(error);
+    };
+
+    for [predicate, handler] in clauses {
+        let predicate = cx.level_down().eval(&predicate)?;
+        let accepted = cx.apply(&predicate, &[AnyEval::from_any(value.clone())])?;
+
+        if !matches!(accepted, Any::Primitive(DataType::Boolean(false))) {
+            let handler = cx.level_down().eval(&handler)?;
+            return cx.apply(&handler, &[AnyEval::from_any(value)]);
+        }
+    }
+
+    Err(error)
+}
+
+fn exn_procedure<'a>(
+    cx: &mut Context<'_, 'a>,
+    args: &[AnyEval<'a>],
+    kind: &Arc<StructType>,
+    op: StructOp
+) -> Result<Any<'a>, InterpreterError> {
+    let values = args.iter()
+        .map(|arg| cx.level_down().eval(arg))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    StructProcedure { name: kind.name.clone(), kind: Arc::clone(kind), op }.call(values)
+}
+
+macro_rules! exn_procedures {
+    ($($constructor: ident, $predicate: ident => $kind: ident),* $(,)?) => {$(
+        pub fn $constructor<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+            exn_procedure(cx, args, &$kind, StructOp::Constructor)
+        }
+
+        pub fn $predicate<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+            exn_procedure(cx, args, &$kind, StructOp::Predicate)
+        }
+    )*};
+}
+
+exn_procedures! {
+    exn, is_exn => EXN,
+    exn_fail, is_exn_fail => EXN_FAIL,
+    exn_fail_contract, is_exn_fail_contract => EXN_FAIL_CONTRACT,
+    exn_fail_contract_divide_by_zero, is_exn_fail_contract_divide_by_zero => EXN_FAIL_CONTRACT_DIVIDE_BY_ZERO,
+    exn_fail_contract_variable, is_exn_fail_contract_variable => EXN_FAIL_CONTRACT_VARIABLE,
+    exn_fail_user, is_exn_fail_user => EXN_FAIL_USER,
+    exn_fail_filesystem, is_exn_fail_filesystem => EXN_FAIL_FILESYSTEM,
+    exn_fail_filesystem_exists, is_exn_fail_filesystem_exists => EXN_FAIL_FILESYSTEM_EXISTS,
+}
+
+pub fn exn_message<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    exn_procedure(cx, args, &EXN, StructOp::Accessor(0))
+}
+
+pub fn exn_continuation_marks<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    exn_procedure(cx, args, &EXN, StructOp::Accessor(1))
+}
+
+#[test]
+fn test_exceptions() {
+    use crate::interpreter::eval_source;
+
+    let source = "(define (safe-div a b)
+  (with-handlers ([exn:fail:contract:divide-by-zero? (lambda (e) 'infinite)])
+    (/ a b)))
+(define caught (with-handlers ([string? (lambda (v) (string-append v \"!\"))])
+  (raise \"boom\")))
+(define message (with-handlers ([exn:fail? exn-message])
+  (error 'parse \"bad token ~a at ~s\" \"x\" #t)))
+(define items (make-vector 2 #f))
+(define contract (with-handlers ([exn:fail:contract? (lambda (e) #t)])
+  (vector-ref items (vector-length items))))
+(define user (with-handlers ([exn:fail:user? exn-message])
+  (raise-user-error \"no input\")))
+(define argument (with-handlers ([exn:fail:contract? (lambda (e) 'contract)])
+  (raise-argument-error 'f \"integer?\" #f)))
+(struct my-error exn:fail (code))
+(define custom (with-handlers ([my-error? my-error-code])
+  (raise (my-error \"custom\" #f \"E42\"))))
+(define outer (with-handlers ([exn:fail? (lambda (e) 'outer)])
+  (with-handlers ([string? (lambda (e) 'inner)])
+    (error \"not a string\" 'x))))
+(list (safe-div 1 0) caught message contract user argument custom outer)";
+
+    assert_eq!(
+        eval_source(source).unwrap(),
+        "'(infinite \"boom!\" \"parse: bad token x at #t\" #t \"no input\" contract \"E42\" outer)"
+    );
+
+    let uncaught = eval_source("(error \"failed\" 1 \"two\")").unwrap_err();
+    assert_eq!(uncaught.to_string(), "1:1: failed 1 \"two\"");
+
+    assert_eq!(
+        eval_source("(with-handlers ([exn:fail? exn-message]) (/ 1 0))").unwrap(),
+        "\"/: division by zero\""
+    );
+    assert!(matches!(
+        eval_source("(with-handlers (5) 1)").unwrap_err().unlocated(),
+        InterpreterError::Expansion(_)
+    ));
+    assert_eq!(
+        eval_source("(with-handlers ([exn:fail?]) 1)").unwrap_err().message(),
+        "with-handlers: expected handler clauses like [predicate handler]"
+    );
+
+    let argument = eval_source("(raise-argument-error 'f \"integer?\" 1 #t #f)").unwrap_err();
+    assert_eq!(
+        argument.to_string(),
+        "1:1: f: contract violation\n  expected: integer?\n  given: #f\n  argument position: 2nd"
+    );
+}
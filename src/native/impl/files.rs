@@ -0,0 +1,375 @@
+use std::borrow::Cow;
+use std::collections::LinkedList;
+use std::fs::{self, OpenOptions};
+use std::io::{self, ErrorKind};
+use std::path::{Path, PathBuf};
+
+use crate::interpreter::any::AnyEval;
+use crate::interpreter::context::Context;
+use crate::interpreter::error::InterpreterError;
+use crate::interpreter::Interpreter;
+use crate::macros::require_arity;
+use crate::native::error::NativeFnError;
+use crate::native::r#impl::exn::{make_exn, raise_value, EXN_FAIL_FILESYSTEM, EXN_FAIL_FILESYSTEM_EXISTS};
+use crate::native::r#impl::format::display_string;
+use crate::native::r#impl::parameter::with_builtin;
+use crate::native::r#impl::util::KeywordArgs;
+use crate::primitives::any::Any;
+use crate::primitives::composed::{Composed, List};
+use crate::primitives::port::Port;
+use crate::primitives::DataType;
+
+fn port_value<'a>(port: Port) -> Any<'a> {
+    Any::Composed(Box::new(Composed::Port(port)))
+}
+
+fn path_value<'a>(path: PathBuf) -> Any<'a> {
+    Any::Composed(Box::new(Composed::Path(path)))
+}
+
+fn boolean<'a>(value: bool) -> Any<'a> {
+    Any::Primitive(DataType::Boolean(value))
+}
+
+/// Raises an `exn:fail:filesystem`, or `exn:fail:filesystem:exists` if the error was caused by
+/// an existing file.
+fn filesystem_error(
+    interpreter: &Interpreter<'_>,
+    fn_name: &str,
+    action: &str,
+    path: &Path,
+    error: io::Error
+) -> InterpreterError {
+    let kind = match error.kind() {
+        ErrorKind::AlreadyExists => &*EXN_FAIL_FILESYSTEM_EXISTS,
+        _ => &*EXN_FAIL_FILESYSTEM
+    };
+
+    let message = format!("{fn_name}: {action}\n  path: {}\n  system error: {error}", path.display());
+    raise_value(interpreter, make_exn(kind, message))
+}
+
+/// Evaluates a path or a string naming one.
+fn require_path<'a>(
+    cx: &mut Context<'_, 'a>,
+    arg: &AnyEval<'a>,
+    fn_name: &'static str,
+    position: u8
+) -> Result<PathBuf, InterpreterError> {
+    match cx.level_down().eval(arg)? {
+        Any::Primitive(DataType::String(s)) => Ok(PathBuf::from(s.as_ref())),
+        Any::Composed(c) if c.is_path() => {
+            let Composed::Path(p) = *c else { unreachable!() };
+            Ok(p)
+        },
+        other => Err(NativeFnError::UnexpectedType {
+            function: fn_name,
+            argument_position: position,
+            got: other.variant_name(),
+            expected: "path-string?"
+        }.into())
+    }
+}
+
+/// Splits the arguments into the positional ones and the value of `#:exists`, which is the only
+/// keyword taken by the file procedures.
+fn exists_mode<'a, 'b>(
+    cx: &mut Context<'_, 'a>,
+    args: &'b [AnyEval<'a>],
+    fn_name: &'static str
+) -> Result<(Vec<&'b AnyEval<'a>>, Option<String>), InterpreterError> {
+    let args = KeywordArgs::parse(args, fn_name, &["#:exists"])?;
+
+    let mode = match args.get("#:exists") {
+        Some(mode) => {
+            let mode = cx.level_down().eval(mode)?;
+
+            Some(mode.get_symbol()
+                .ok_or(NativeFnError::UnexpectedType {
+                    function: fn_name,
+                    argument_position: args.positional.len() as u8 + 1,
+                    got: mode.variant_name(),
+                    expected: "symbol"
+                })?
+                .to_string())
+        },
+        None => None
+    };
+
+    Ok((args.positional, mode))
+}
+
+/// Opens the file for writing the way the `#:exists` mode says, failing by default if it exists.
+fn open_output(
+    interpreter: &Interpreter<'_>,
+    path: &Path,
+    mode: Option<&str>,
+    fn_name: &str
+) -> Result<Port, InterpreterError> {
+    let mut options = OpenOptions::new();
+    options.write(true);
+
+    match mode.unwrap_or("error") {
+        "error" => options.create_new(true),
+        "append" => options.append(true).create(true),
+        "replace" | "truncate" | "truncate/replace" => options.truncate(true).create(true),
+        "update" => &mut options,
+        "can-update" => options.create(true),
+        other => return Err(InterpreterError::Runtime(format!(
+            "{fn_name}: unknown #:exists mode '{other}"
+        )))
+    };
+
+    let file = options.open(path)
+        .map_err(|e| filesystem_error(interpreter, fn_name, "cannot open output file", path, e))?;
+
+    Ok(Port::output_file(path, file))
+}
+
+fn open_input(interpreter: &Interpreter<'_>, path: &Path, fn_name: &str) -> Result<Port, InterpreterError> {
+    Port::input_file(path)
+        .map_err(|e| filesystem_error(interpreter, fn_name, "cannot open input file", path, e))
+}
+
+pub fn open_input_file<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(exact 1, args);
+
+    let path = require_path(cx, &args[0], "open-input-file", 1)?;
+    Ok(port_value(open_input(cx.interpreter(), &path, "open-input-file")?))
+}
+
+/// `(open-output-file path [#:exists mode])`.
+pub fn open_output_file<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    let (args, mode) = exists_mode(cx, args, "open-output-file")?;
+    require_arity!(exact 1, args);
+
+    let path = require_path(cx, args[0], "open-output-file", 1)?;
+    Ok(port_value(open_output(cx.interpreter(), &path, mode.as_deref(), "open-output-file")?))
+}
+
+pub fn call_with_input_file<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(exact 2, args);
+
+    let path = require_path(cx, &args[0], "call-with-input-file", 1)?;
+    let procedure = cx.level_down().eval(&args[1])?;
+    let port = open_input(cx.interpreter(), &path, "call-with-input-file")?;
+
+    cx.apply(&procedure, &[AnyEval::from_any(port_value(port))])
+}
+
+/// `(call-with-output-file path proc [#:exists mode])`.
+pub fn call_with_output_file<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    let (args, mode) = exists_mode(cx, args, "call-with-output-file")?;
+    require_arity!(exact 2, args);
+
+    let path = require_path(cx, args[0], "call-with-output-file", 1)?;
+    let procedure = cx.level_down().eval(args[1])?;
+    let port = open_output(cx.interpreter(), &path, mode.as_deref(), "call-with-output-file")?;
+
+    cx.apply(&procedure, &[AnyEval::from_any(port_value(port))])
+}
+
+/// `(with-output-to-file path thunk [#:exists mode])`, calls the thunk with the current output
+/// port writing to the file.
+pub fn with_output_to_file<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    let (args, mode) = exists_mode(cx, args, "with-output-to-file")?;
+    require_arity!(exact 2, args);
+
+    let path = require_path(cx, args[0], "with-output-to-file", 1)?;
+    let thunk = cx.level_down().eval(args[1])?;
+    let port = open_output(cx.interpreter(), &path, mode.as_deref(), "with-output-to-file")?;
+
+    with_builtin(cx, "current-output-port", port_value(port), |cx| cx.apply(&thunk, &[]))
+}
+
+fn read_file(interpreter: &Interpreter<'_>, path: &Path, fn_name: &str) -> Result<String, InterpreterError> {
+    fs::read_to_string(path).map_err(|e| filesystem_error(interpreter, fn_name, "cannot open input file", path, e))
+}
+
+pub fn file_to_string<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(exact 1, args);
+
+    let path = require_path(cx, &args[0], "file->string", 1)?;
+    let text = read_file(cx.interpreter(), &path, "file->string")?;
+
+    Ok(Any::Primitive(DataType::String(Cow::Owned(text))))
+}
+
+pub fn file_to_lines<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(exact 1, args);
+
+    let path = require_path(cx, &args[0], "file->lines", 1)?;
+    let text = read_file(cx.interpreter(), &path, "file->lines")?;
+    let lines = text.lines()
+        .map(|line| Any::Primitive(DataType::String(Cow::Owned(line.to_string()))))
+        .collect::<LinkedList<_>>();
+
+    Ok(Any::Composed(Box::new(Composed::List(List(lines)))))
+}
+
+/// `(display-to-file value path [#:exists mode])`.
+pub fn display_to_file<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    let (args, mode) = exists_mode(cx, args, "display-to-file")?;
+    require_arity!(exact 2, args);
+
+    let value = cx.level_down().eval(args[0])?;
+    let path = require_path(cx, args[1], "display-to-file", 2)?;
+    let port = open_output(cx.interpreter(), &path, mode.as_deref(), "display-to-file")?;
+
+    port.write_str(&display_string(&value, cx.interpreter()))
+        .map_err(|e| filesystem_error(cx.interpreter(), "display-to-file", "error writing to file", &path, e))?;
+
+    Ok(Any::Void(()))
+}
+
+pub fn is_file_exists<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(exact 1, args);
+
+    let path = require_path(cx, &args[0], "file-exists?", 1)?;
+    Ok(boolean(path.is_file()))
+}
+
+pub fn is_directory_exists<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(exact 1, args);
+
+    let path = require_path(cx, &args[0], "directory-exists?", 1)?;
+    Ok(boolean(path.is_dir()))
+}
+
+/// `(directory-list [path])`, the sorted names of the entries of the directory, which defaults
+/// to the current one.
+pub fn directory_list<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(at_most 1, args);
+
+    let path = match args.first() {
+        Some(path) => require_path(cx, path, "directory-list", 1)?,
+        None => PathBuf::from(".")
+    };
+
+    let error = |e| filesystem_error(cx.interpreter(), "directory-list", "could not open directory", &path, e);
+    let mut names = fs::read_dir(&path)
+        .and_then(|entries| entries
+            .map(|entry| entry.map(|e| PathBuf::from(e.file_name())))
+            .collect::<Result<Vec<_>, _>>())
+        .map_err(error)?;
+
+    names.sort();
+    Ok(Any::Composed(Box::new(Composed::List(List(names.into_iter().map(path_value).collect())))))
+}
+
+pub fn delete_file<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(exact 1, args);
+
+    let path = require_path(cx, &args[0], "delete-file", 1)?;
+    fs::remove_file(&path)
+        .map_err(|e| filesystem_error(cx.interpreter(), "delete-file", "cannot delete file", &path, e))?;
+
+    Ok(Any::Void(()))
+}
+
+pub fn make_directory<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(exact 1, args);
+
+    let path = require_path(cx, &args[0], "make-directory", 1)?;
+    fs::create_dir(&path)
+        .map_err(|e| filesystem_error(cx.interpreter(), "make-directory", "cannot make directory", &path, e))?;
+
+    Ok(Any::Void(()))
+}
+
+/// `(rename-file-or-directory old new [exists-ok?])`, fails if `new` exists unless allowed.
+pub fn rename_file_or_directory<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(at_least 2, args);
+    require_arity!(at_most 3, args);
+
+    let old = require_path(cx, &args[0], "rename-file-or-directory", 1)?;
+    let new = require_path(cx, &args[1], "rename-file-or-directory", 2)?;
+    let exists_ok = match args.get(2) {
+        Some(arg) => !matches!(cx.level_down().eval(arg)?, Any::Primitive(DataType::Boolean(false))),
+        None => false
+    };
+
+    let result = match !exists_ok && new.exists() {
+        true => Err(io::Error::new(ErrorKind::AlreadyExists, "destination already exists")),
+        false => fs::rename(&old, &new)
+    };
+
+    result.map_err(|e| filesystem_error(cx.interpreter(), "rename-file-or-directory", "cannot rename file or directory", &old, e))?;
+    Ok(Any::Void(()))
+}
+
+/// `(build-path base sub ...)`, joins the paths with the platform separator.
+pub fn build_path<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(at_least 1, args);
+
+    let mut path = PathBuf::new();
+
+    for (idx, arg) in args.iter().enumerate() {
+        path.push(require_path(cx, arg, "build-path", idx as u8 + 1)?);
+    }
+
+    Ok(path_value(path))
+}
+
+pub fn path_to_string<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(exact 1, args);
+
+    let value = cx.level_down().eval(&args[0])?;
+    let path = value.get_composed()
+        .and_then(|c| c.get_path())
+        .ok_or(NativeFnError::UnexpectedType {
+            function: "path->string",
+            argument_position: 1,
+            got: value.variant_name(),
+            expected: "path"
+        })?;
+
+    Ok(Any::Primitive(DataType::String(Cow::Owned(path.to_string_lossy().into_owned()))))
+}
+
+pub fn string_to_path<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(exact 1, args);
+
+    let path = require_path(cx, &args[0], "string->path", 1)?;
+    Ok(path_value(path))
+}
+
+pub fn is_path<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(exact 1, args);
+
+    let value = cx.level_down().eval(&args[0])?;
+    Ok(boolean(value.get_composed().map(|c| c.is_path()).unwrap_or(false)))
+}
+
+#[test]
+fn test_files() {
+    use crate::interpreter::eval_source;
+
+    let dir = std::env::temp_dir().join(format!("rioxide-files-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+
+    let source = format!("(define dir \"{}\")
+(make-directory dir)
+(define notes (build-path dir \"notes.txt\"))
+(with-output-to-file notes (lambda () (display \"first\") (newline)))
+(call-with-output-file notes (lambda (out) (displayln 'second out)) #:exists 'append)
+(define exists (with-handlers ([exn:fail:filesystem:exists? (lambda (e) 'exists)])
+  (open-output-file notes)))
+(define first (call-with-input-file notes read-line))
+(display-to-file \"moved\" (build-path dir \"other.txt\"))
+(rename-file-or-directory (build-path dir \"other.txt\") (build-path dir \"moved.txt\"))
+(define listed (map path->string (directory-list dir)))
+(define contents (list (file->lines notes) (file->string (build-path dir \"moved.txt\"))))
+(delete-file notes)
+(define missing (with-handlers ([exn:fail:filesystem? (lambda (e) 'missing)])
+  (file->string notes)))
+(list exists first listed contents missing (file-exists? notes) (directory-exists? dir) (path? notes))", dir.display());
+
+    let result = eval_source(&source);
+    let _ = fs::remove_dir_all(&dir);
+
+    assert_eq!(
+        result.unwrap(),
+        "'(exists \"first\" (\"moved.txt\" \"notes.txt\") ((\"first\" \"second\") \"moved\") missing #f #t #t)"
+    );
+}
@@ -0,0 +1,143 @@
+use std::fmt;
+
+use crate::display::{InterpreterDisplay, RawDisplay};
+use crate::interpreter::error::InterpreterError;
+use crate::interpreter::Interpreter;
+use crate::primitives::any::Any;
+use crate::primitives::composed::Composed;
+use crate::primitives::DataType;
+
+/// Renders the value the way `display` does, which is the written form but with strings and
+/// characters, even nested ones, without their delimiters.
+pub fn display_string(value: &Any<'_>, interpreter: &Interpreter<'_>) -> String {
+    let mut out = String::new();
+    display_fmt(value, &mut out, interpreter).unwrap();
+    out
+}
+
+fn display_fmt(value: &Any<'_>, f: &mut String, interpreter: &Interpreter<'_>) -> fmt::Result {
+    let items = |f: &mut String, items: &mut dyn Iterator<Item = &Any<'_>>| {
+        for (idx, item) in items.enumerate() {
+            if idx > 0 {
+                f.push(' ');
+            }
+
+            display_fmt(item, f, interpreter)?;
+        }
+
+        Ok(())
+    };
+
+    match value {
+        Any::Primitive(DataType::String(s)) => f.push_str(s),
+        Any::Primitive(DataType::Character(c)) => f.push(*c),
+        Any::Primitive(DataType::Bytes(b)) => f.push_str(&String::from_utf8_lossy(b)),
+        Any::Composed(c) => match c.as_ref() {
+            Composed::List(l) => {
+                f.push('(');
+                items(f, &mut l.0.iter())?;
+                f.push(')');
+            },
+            Composed::Vector(v) => {
+                f.push_str("#(");
+                items(f, &mut v.0.iter())?;
+                f.push(')');
+            },
+            Composed::Pair(p) => {
+                f.push('(');
+                display_fmt(&p.left, f, interpreter)?;
+
+                let mut rest = &p.right;
+
+                while let Some(pair) = rest.get_composed().and_then(|c| c.get_pair()) {
+                    f.push(' ');
+                    display_fmt(&pair.left, f, interpreter)?;
+                    rest = &pair.right;
+                }
+
+                f.push_str(" . ");
+                display_fmt(rest, f, interpreter)?;
+                f.push(')');
+            },
+            Composed::Hash(h) => {
+                f.push_str("#hash(");
+
+                for (idx, (key, value)) in h.0.iter().enumerate() {
+                    if idx > 0 {
+                        f.push(' ');
+                    }
+
+                    f.push('(');
+                    display_fmt(key, f, interpreter)?;
+                    f.push_str(" . ");
+                    display_fmt(value, f, interpreter)?;
+                    f.push(')');
+                }
+
+                f.push(')');
+            },
+            Composed::Path(p) => f.push_str(&p.to_string_lossy()),
+            Composed::MutableString(s) => f.push_str(&s.borrow()),
+            Composed::MutableBytes(b) => f.push_str(&String::from_utf8_lossy(&b.borrow())),
+            other => other.raw_fmt(f, interpreter)?
+        },
+        other => other.raw_fmt(f, interpreter)?
+    }
+
+    Ok(())
+}
+
+/// Renders the value the way `write` does.
+pub fn write_string(value: &Any<'_>, interpreter: &Interpreter<'_>) -> String {
+    let mut out = String::new();
+    value.raw_fmt(&mut out, interpreter).unwrap();
+    out
+}
+
+/// Renders the value the way `print` and the REPL do.
+pub fn print_string(value: &Any<'_>, interpreter: &Interpreter<'_>) -> String {
+    let mut out = String::new();
+    value.fmt(&mut out, interpreter).unwrap();
+    out
+}
+
+/// Fills the `~a`, `~s`, `~v`, `~e`, `~n`, `~%` and `~~` directives of a `format` string.
+pub fn format_string(
+    interpreter: &Interpreter<'_>,
+    fn_name: &str,
+    template: &str,
+    args: &[Any<'_>]
+) -> Result<String, InterpreterError> {
+    let mut out = String::with_capacity(template.len());
+    let mut args = args.iter();
+    let mut chars = template.chars();
+
+    let mismatch = || InterpreterError::Runtime(format!(
+        "{fn_name}: format string requires a different number of arguments"
+    ));
+
+    while let Some(c) = chars.next() {
+        if c != '~' {
+            out.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            Some('a' | 'A') => out.push_str(&display_string(args.next().ok_or_else(mismatch)?, interpreter)),
+            Some('s' | 'S') => out.push_str(&write_string(args.next().ok_or_else(mismatch)?, interpreter)),
+            Some('v' | 'V' | 'e' | 'E') => out.push_str(&print_string(args.next().ok_or_else(mismatch)?, interpreter)),
+            Some('n' | '%') => out.push('\n'),
+            Some('~') => out.push('~'),
+            other => return Err(InterpreterError::Runtime(format!(
+                "{fn_name}: ill-formed pattern string, unknown directive ~{}",
+                other.map(String::from).unwrap_or_default()
+            )))
+        }
+    }
+
+    if args.next().is_some() {
+        return Err(mismatch());
+    }
+
+    Ok(out)
+}
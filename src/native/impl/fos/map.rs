@@ -1,9 +1,12 @@
 use std::collections::LinkedList;
 
-use crate::{interpreter::{any::AnyEval, context::Context, error::InterpreterError}, macros::require_arity, native::{error::NativeFnError, function::{NativeFn, NativeFunction}}, primitives::{any::Any, composed::{Composed, Function, LambdaFunction, List}}};
+use crate::{interpreter::{any::AnyEval, context::Context, error::InterpreterError}, macros::require_arity, primitives::{any::Any, composed::{Composed, List}}};
+use crate::native::r#impl::sequence::map_stream;
 
 use super::super::util::*;
 
+/// Maps over a list, producing a list, or over any other stream, producing a lazy stream so
+/// infinite streams can be mapped too.
 pub fn map<'a>(
     cx: &mut Context<'_, 'a>,
     args: &[AnyEval<'a>]
@@ -13,22 +16,17 @@ pub fn map<'a>(
     
     let fun = &args[0];
     let evaluated = cx.eval(&args[1])?;
-    let items = evaluated.get_composed()
-        .map(|i| i.get_list())
-        .flatten()
-        .ok_or(NativeFnError::UnexpectedType {
-            function: "map",
-            argument_position: 2,
-            got: args[1].variant_name(),
-            expected: "list"
-        })?;
+    let Some(items) = evaluated.list_items() else {
+        let procedure = cx.level_down().eval(fun)?;
+        return map_stream(procedure, evaluated, "map", 2);
+    };
 
     let callable = callable_for(cx, fun, "map", 1)?;
 
     let mut result = LinkedList::new();
 
-    for item in &items.0 {
-        result.push_back(callable.call(cx, &[AnyEval::from_any(item.clone())])?)
+    for item in items {
+        result.push_back(callable.call(cx, &[AnyEval::from_any(item)])?)
     }
 
     Ok(Any::Composed(Box::new(Composed::List(List(result)))))
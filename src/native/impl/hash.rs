@@ -0,0 +1,139 @@
+use std::collections::LinkedList;
+
+use crate::display::InterpreterDisplay;
+use crate::interpreter::any::AnyEval;
+use crate::interpreter::context::Context;
+use crate::interpreter::error::InterpreterError;
+use crate::macros::require_arity;
+use crate::native::error::NativeFnError;
+use crate::native::r#impl::equality::equal;
+use crate::primitives::any::Any;
+use crate::primitives::composed::{Composed, HashTable, List, Pair};
+use crate::primitives::DataType;
+
+pub fn require_hash<'a>(
+    cx: &mut Context<'_, 'a>,
+    arg: &AnyEval<'a>,
+    fn_name: &'static str,
+    position: u8,
+) -> Result<HashTable<'a>, InterpreterError>
+{
+    match cx.level_down().eval(arg)? {
+        Any::Composed(c) if c.is_hash() => {
+            let Composed::Hash(h) = *c else { unreachable!() };
+            Ok(h)
+        },
+        other => Err(NativeFnError::UnexpectedType {
+            function: fn_name,
+            argument_position: position,
+            got: other.variant_name(),
+            expected: "hash"
+        }.into())
+    }
+}
+
+/// Inserts the key into the table, replacing the value of an `equal?` key if present.
+pub fn hash_insert<'a>(table: &mut HashTable<'a>, key: Any<'a>, value: Any<'a>) {
+    match table.0.iter_mut().find(|(k, _)| equal(k, &key)) {
+        Some((_, old)) => *old = value,
+        None => table.0.push((key, value))
+    }
+}
+
+fn hash_value(table: HashTable<'_>) -> Any<'_> {
+    Any::Composed(Box::new(Composed::Hash(table)))
+}
+
+fn list_value(items: LinkedList<Any<'_>>) -> Any<'_> {
+    Any::Composed(Box::new(Composed::List(List(items))))
+}
+
+pub fn hash<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    if !args.len().is_multiple_of(2) {
+        return Err(InterpreterError::Runtime("hash: key does not have a value".to_string()));
+    }
+
+    let mut table = HashTable(Vec::with_capacity(args.len() / 2));
+
+    for pair in args.chunks(2) {
+        let key = cx.level_down().eval(&pair[0])?;
+        let value = cx.level_down().eval(&pair[1])?;
+        hash_insert(&mut table, key, value);
+    }
+
+    Ok(hash_value(table))
+}
+
+pub fn is_hash<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(exact 1, args);
+
+    let value = cx.level_down().eval(&args[0])?;
+    Ok(Any::Primitive(DataType::Boolean(value.get_composed().map(|c| c.is_hash()).unwrap_or(false))))
+}
+
+/// `(hash-ref hash key [failure])`, where failure is either the value to return when the key is
+/// missing or a procedure called without arguments to produce it.
+pub fn hash_ref<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(at_least 2, args);
+
+    let table = require_hash(cx, &args[0], "hash-ref", 1)?;
+    let key = cx.level_down().eval(&args[1])?;
+
+    if let Some((_, value)) = table.0.into_iter().find(|(k, _)| equal(k, &key)) {
+        return Ok(value);
+    }
+
+    let Some(failure) = args.get(2) else {
+        let mut given = String::new();
+        key.fmt(&mut given, cx.interpreter()).unwrap();
+
+        return Err(InterpreterError::Runtime(format!("hash-ref: no value found for key {given}")));
+    };
+
+    let failure = cx.level_down().eval(failure)?;
+
+    match failure.get_composed().map(Box::as_ref) {
+        Some(Composed::Function(_) | Composed::Lambda(_)) => cx.apply(&failure, &[]),
+        _ => Ok(failure)
+    }
+}
+
+pub fn hash_set<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(exact 3, args);
+
+    let mut table = require_hash(cx, &args[0], "hash-set", 1)?;
+    let key = cx.level_down().eval(&args[1])?;
+    let value = cx.level_down().eval(&args[2])?;
+    hash_insert(&mut table, key, value);
+
+    Ok(hash_value(table))
+}
+
+pub fn hash_count<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(exact 1, args);
+    Ok(Any::Primitive(DataType::Integer(require_hash(cx, &args[0], "hash-count", 1)?.0.len() as _)))
+}
+
+pub fn hash_keys<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(exact 1, args);
+
+    let table = require_hash(cx, &args[0], "hash-keys", 1)?;
+    Ok(list_value(table.0.into_iter().map(|(k, _)| k).collect()))
+}
+
+pub fn hash_values<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(exact 1, args);
+
+    let table = require_hash(cx, &args[0], "hash-values", 1)?;
+    Ok(list_value(table.0.into_iter().map(|(_, v)| v).collect()))
+}
+
+pub fn hash_to_list<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(exact 1, args);
+
+    let table = require_hash(cx, &args[0], "hash->list", 1)?;
+
+    Ok(list_value(table.0.into_iter()
+        .map(|(left, right)| Any::Composed(Box::new(Composed::Pair(Pair { left, right }))))
+        .collect()))
+}
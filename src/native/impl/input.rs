@@ -0,0 +1,293 @@
+use std::borrow::Cow;
+
+use crate::interpreter::any::AnyEval;
+use crate::interpreter::context::Context;
+use crate::interpreter::error::InterpreterError;
+use crate::lexer::Lexer;
+use crate::macros::require_arity;
+use crate::native::error::NativeFnError;
+use crate::native::r#impl::parameter::current_port;
+use crate::native::r#impl::util::non_negative_int;
+use crate::primitives::any::Any;
+use crate::primitives::composed::Composed;
+use crate::primitives::port::{Eof, Port};
+use crate::primitives::DataType;
+use crate::reader::Reader;
+
+pub fn eof_value<'a>() -> Any<'a> {
+    Any::Composed(Box::new(Composed::Eof(Eof)))
+}
+
+pub fn require_input_port<'a>(
+    cx: &mut Context<'_, 'a>,
+    arg: &AnyEval<'a>,
+    fn_name: &'static str,
+    position: u8
+) -> Result<Port, InterpreterError> {
+    match cx.level_down().eval(arg)? {
+        Any::Composed(c) if c.get_port().map(Port::is_input).unwrap_or(false) => {
+            let Composed::Port(p) = *c else { unreachable!() };
+            Ok(p)
+        },
+        other => Err(NativeFnError::UnexpectedType {
+            function: fn_name,
+            argument_position: position,
+            got: other.variant_name(),
+            expected: "input port"
+        }.into())
+    }
+}
+
+/// Port given at `position`, defaulting to the current input port.
+pub fn input_port<'a>(
+    cx: &mut Context<'_, 'a>,
+    args: &[AnyEval<'a>],
+    fn_name: &'static str,
+    position: usize
+) -> Result<Port, InterpreterError> {
+    match args.get(position) {
+        Some(port) => require_input_port(cx, port, fn_name, position as u8 + 1),
+        None => current_port(cx, "current-input-port")
+    }
+}
+
+fn or_eof<'a, T>(value: Option<T>, into: impl FnOnce(T) -> Any<'a>) -> Any<'a> {
+    value.map(into).unwrap_or_else(eof_value)
+}
+
+fn string_value<'a>(s: String) -> Any<'a> {
+    Any::Primitive(DataType::String(Cow::Owned(s)))
+}
+
+/// `(read-line [port mode])`, every mode but `'linefeed` also drops a carriage return before
+/// the line feed.
+pub fn read_line<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(at_most 2, args);
+
+    let port = input_port(cx, args, "read-line", 0)?;
+    let mode = match args.get(1) {
+        Some(mode) => {
+            let mode = cx.level_down().eval(mode)?;
+
+            match mode.get_symbol() {
+                Some(mode @ ("linefeed" | "return-linefeed" | "any" | "any-one")) => mode,
+                _ => return Err(NativeFnError::UnexpectedType {
+                    function: "read-line",
+                    argument_position: 2,
+                    got: mode.variant_name(),
+                    expected: "(or/c 'linefeed 'return-linefeed 'any 'any-one)"
+                }.into())
+            }
+        },
+        None => "linefeed"
+    };
+
+    let line = match mode {
+        "linefeed" => port.read_with(|s| s.contains('\n'), |buffer| buffer.read_line())?,
+        _ => port.read_line()?
+    };
+
+    Ok(or_eof(line, string_value))
+}
+
+fn read_char_with<'a>(
+    cx: &mut Context<'_, 'a>,
+    args: &[AnyEval<'a>],
+    fn_name: &'static str,
+    consume: bool
+) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(at_most 1, args);
+
+    let port = input_port(cx, args, fn_name, 0)?;
+    let c = port.read_with(|s| !s.is_empty(), |buffer| if consume {
+        buffer.read_char()
+    } else {
+        buffer.peek_char()
+    })?;
+
+    Ok(or_eof(c, |c| Any::Primitive(DataType::Character(c))))
+}
+
+pub fn read_char<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    read_char_with(cx, args, "read-char", true)
+}
+
+pub fn peek_char<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    read_char_with(cx, args, "peek-char", false)
+}
+
+/// `(read-string amount [port])`, returns fewer characters if the port ends before.
+pub fn read_string<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(at_least 1, args);
+    require_arity!(at_most 2, args);
+
+    let count = non_negative_int(cx, &args[0], "read-string", 1)?;
+    let port = input_port(cx, args, "read-string", 1)?;
+
+    if count == 0 {
+        return Ok(string_value(String::new()));
+    }
+
+    let text = port.read_with(|s| s.chars().count() >= count, |buffer| buffer.read_string(count))?;
+    Ok(or_eof(text, string_value))
+}
+
+/// `(read-bytes amount [port])`, returns fewer bytes if the port ends before.
+pub fn read_bytes<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(at_least 1, args);
+    require_arity!(at_most 2, args);
+
+    let count = non_negative_int(cx, &args[0], "read-bytes", 1)?;
+    let port = input_port(cx, args, "read-bytes", 1)?;
+
+    if count == 0 {
+        return Ok(Any::Primitive(DataType::Bytes(Cow::Owned(Vec::new()))));
+    }
+
+    let bytes = port.read_with(|s| s.len() >= count, |buffer| buffer.read_bytes(count))?;
+    Ok(or_eof(bytes, |b| Any::Primitive(DataType::Bytes(Cow::Owned(b)))))
+}
+
+/// `(char-ready? [port])`, string ports are always ready while the standard input is only ready
+/// when a line was read already and not consumed.
+pub fn is_char_ready<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(at_most 1, args);
+
+    let port = input_port(cx, args, "char-ready?", 0)?;
+    let ready = match port {
+        Port::Stdin => port.read_with(|_| true, |buffer| !buffer.remaining().is_empty())?,
+        _ => true
+    };
+
+    Ok(Any::Primitive(DataType::Boolean(ready)))
+}
+
+pub fn eof_object<'a>(_: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(exact 0, args);
+    Ok(eof_value())
+}
+
+pub fn is_eof_object<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(exact 1, args);
+
+    let value = cx.level_down().eval(&args[0])?;
+    Ok(Any::Primitive(DataType::Boolean(value.get_composed().map(|c| c.is_eof()).unwrap_or(false))))
+}
+
+/// Byte length of the first datum of `text`, including the whitespace before it, or `None` if
+/// the text ends before the datum does.
+fn datum_end(text: &str) -> Option<usize> {
+    let mut chars = text.char_indices().peekable();
+    let mut depth = 0usize;
+
+    while let Some((idx, c)) = chars.next() {
+        match c {
+            c if c.is_whitespace() => continue,
+            '\'' => continue,
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' => depth = depth.saturating_sub(1),
+            '"' => {
+                let close = loop {
+                    match chars.next()? {
+                        (_, '\\') => { chars.next()?; },
+                        (close, '"') => break close,
+                        _ => ()
+                    }
+                };
+
+                if depth == 0 {
+                    return Some(close + 1);
+                }
+
+                continue;
+            },
+            _ => {
+                // the character after `#\` belongs to the atom even if it's a delimiter
+                if c == '#' && chars.peek().map(|(_, c)| *c) == Some('\\') {
+                    chars.next();
+                    chars.next()?;
+                }
+
+                while let Some((_, c)) = chars.peek() {
+                    if c.is_whitespace() || "()[]{}\"'".contains(*c) {
+                        break;
+                    }
+
+                    chars.next();
+                }
+
+                let end = chars.peek().map(|(idx, _)| *idx).unwrap_or(text.len());
+
+                // prefixes like `#hash`, `#&` and `#rx` belong to the datum after them
+                if matches!(&text[idx..end], "#" | "#hash" | "#&" | "#rx" | "#px") {
+                    continue;
+                }
+
+                if depth == 0 {
+                    return Some(end);
+                }
+
+                continue;
+            }
+        }
+
+        if depth == 0 {
+            return Some(idx + c.len_utf8());
+        }
+    }
+
+    None
+}
+
+/// `(read [port])`, reads the next datum of the port.
+pub fn read<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(at_most 1, args);
+
+    let port = input_port(cx, args, "read", 0)?;
+    let text = port.read_with(|s| datum_end(s).is_some(), |buffer| {
+        let Some(end) = datum_end(buffer.remaining()) else {
+            return match buffer.remaining().trim().is_empty() {
+                true => Ok(None),
+                false => Err(InterpreterError::Runtime("read: expected a closing delimiter".to_string()))
+            };
+        };
+
+        let text = buffer.remaining()[..end].trim().to_string();
+        buffer.advance(end);
+        Ok(Some(text))
+    })??;
+
+    let Some(text) = text else { return Ok(eof_value()); };
+
+    let error = |e: &dyn std::fmt::Display| InterpreterError::Runtime(format!("read: {e}"));
+    let tokens = Lexer::new(&text).parse().map_err(|e| error(&e))?;
+    let datum = Reader::new(tokens).read_all()
+        .map_err(|e| error(&e))?
+        .into_iter()
+        .next()
+        .ok_or_else(|| error(&"bad syntax"))?;
+
+    Ok(datum.make_static())
+}
+
+#[test]
+fn test_read_family() {
+    use crate::interpreter::eval_source;
+
+    let source = "(define in (open-input-string \"first line\nsecond\r\nab(c d) 'x\"))
+(define lines (list (read-line in) (read-line in 'any)))
+(define chars (list (peek-char in) (read-char in) (read-string 1 in)))
+(define datums (list (read in) (read in) (read in)))
+(define rest (list (read-char in) (read-line in) (eof-object? (read-string 3 in))))
+(define piped (with-input-from-string \"one\ntwo\" (lambda () (for/list ([line (in-lines)]) line))))
+(list lines chars datums rest piped (char-ready? (open-input-string \"\")) (eof-object? (eof-object)))";
+
+    assert_eq!(
+        eval_source(source).unwrap(),
+        "'((\"first line\" \"second\") (#\\a #\\a \"b\") ((c d) 'x #<eof>) (#<eof> #<eof> #t) (\"one\" \"two\") #t #t)"
+    );
+
+    let strings = r##"(define in (open-input-string "\"str\" \"a\\\"b\" #\"xy\" (\"in\" list)"))
+(list (read in) (read in) (read in) (read in) (read in))"##;
+    assert_eq!(eval_source(strings).unwrap(), r##"'("str" "a\"b" #"xy" ("in" list) #<eof>)"##);
+}
@@ -0,0 +1,520 @@
+use std::cell::RefCell;
+use std::collections::LinkedList;
+use std::ops::{Add, Mul};
+
+use crate::interpreter::any::AnyEval;
+use crate::interpreter::context::Context;
+use crate::interpreter::error::InterpreterError;
+use crate::macros::require_arity;
+use crate::native::error::NativeFnError;
+use crate::native::r#impl::common::eval_sequence;
+use crate::native::r#impl::hash::hash_insert;
+use crate::native::r#impl::logic::boolean_value;
+use crate::native::r#impl::math::create_comparable;
+use crate::native::r#impl::sequence::into_sequence;
+use crate::primitives::any::Any;
+use crate::primitives::composed::{Composed, HashTable, List, Vector};
+use crate::primitives::ops::ComparisonOperator;
+use crate::primitives::DataType;
+
+enum Clause<'a> {
+    /// `[name sequence]` or `[(name ...) sequence]`
+    Bind(Vec<&'a str>, AnyEval<'a>),
+    When(AnyEval<'a>),
+    Unless(AnyEval<'a>),
+    Break(AnyEval<'a>)
+}
+
+enum Flow {
+    Continue,
+    Stop
+}
+
+struct Loop<'l, 'a> {
+    form: &'static str,
+    /// Whether every binding clause iterates inside the previous one, like `for*`, instead of
+    /// iterating in parallel until the next guard
+    nested: bool,
+    /// Binds the loop state in every new scope, like the accumulator of `for/fold`
+    state: &'l dyn Fn(&mut Context<'_, 'a>)
+}
+
+fn syntax_error(form: &'static str, reason: &str) -> InterpreterError {
+    InterpreterError::Runtime(format!("{form}: bad syntax, {reason}"))
+}
+
+fn parse_clauses<'a>(form: &'static str, arg: &AnyEval<'a>) -> Result<Vec<Clause<'a>>, InterpreterError> {
+    let items = arg.get_expression()
+        .ok_or(syntax_error(form, "expected a list of clauses"))?
+        .as_vec();
+
+    let mut out = Vec::with_capacity(items.len());
+    let mut items = items.into_iter();
+
+    while let Some(item) = items.next() {
+        let keyword = item.get_ident().copied();
+
+        let clause = match keyword {
+            Some(k @ ("#:when" | "#:unless" | "#:break")) => {
+                let guard = items.next()
+                    .ok_or(syntax_error(form, &format!("missing expression after {k}")))?;
+
+                match k {
+                    "#:when" => Clause::When(guard),
+                    "#:unless" => Clause::Unless(guard),
+                    _ => Clause::Break(guard)
+                }
+            },
+            _ => {
+                let binding = item.get_expression()
+                    .ok_or(syntax_error(form, "expected a clause like [name sequence]"))?
+                    .as_vec();
+
+                let [names, sequence] = <[AnyEval<'a>; 2]>::try_from(binding)
+                    .map_err(|_| syntax_error(form, "expected a clause like [name sequence]"))?;
+
+                let names = match &names {
+                    AnyEval::Ident(name) => vec![*name],
+                    AnyEval::Expression(tree) => tree.as_vec()
+                        .iter()
+                        .map(|n| n.get_ident().copied().ok_or(NativeFnError::IdentifierExpectedIn {
+                            call: form,
+                            got: n.variant_name().to_string()
+                        }))
+                        .collect::<Result<Vec<_>, _>>()?,
+                    other => return Err(NativeFnError::IdentifierExpectedIn {
+                        call: form,
+                        got: other.variant_name().to_string()
+                    }.into())
+                };
+
+                Clause::Bind(names, sequence)
+            }
+        };
+
+        out.push(clause);
+    }
+
+    Ok(out)
+}
+
+/// Binds an item of a sequence, destructuring pairs and lists when the clause has many names.
+fn bind<'a>(
+    scope: &mut Context<'_, 'a>,
+    form: &'static str,
+    names: &[&'a str],
+    value: Any<'a>
+) -> Result<(), InterpreterError> {
+    if let [name] = names {
+        scope.vars_mut().insert(name, value);
+        return Ok(());
+    }
+
+    let values = match value.get_composed().and_then(|c| c.get_pair()) {
+        Some(pair) => vec![pair.left.clone(), pair.right.clone()],
+        None => value.list_items().unwrap_or_default()
+    };
+
+    if values.len() != names.len() {
+        return Err(InterpreterError::Runtime(format!(
+            "{form}: expected {} values from the sequence, got {}",
+            names.len(),
+            values.len()
+        )));
+    }
+
+    for (name, value) in names.iter().zip(values) {
+        scope.vars_mut().insert(name, value);
+    }
+
+    Ok(())
+}
+
+fn run<'a>(
+    cx: &mut Context<'_, 'a>,
+    lp: &Loop<'_, 'a>,
+    clauses: &[Clause<'a>],
+    body: &mut dyn FnMut(&mut Context<'_, 'a>) -> Result<Flow, InterpreterError>
+) -> Result<Flow, InterpreterError> {
+    let Some((first, rest)) = clauses.split_first() else {
+        return body(cx);
+    };
+
+    match first {
+        Clause::When(guard) if !boolean_value(cx, guard)?.1 => return Ok(Flow::Continue),
+        Clause::Unless(guard) if boolean_value(cx, guard)?.1 => return Ok(Flow::Continue),
+        Clause::Break(guard) if boolean_value(cx, guard)?.1 => return Ok(Flow::Stop),
+        Clause::When(_) | Clause::Unless(_) | Clause::Break(_) => return run(cx, lp, rest, body),
+        Clause::Bind(..) => ()
+    }
+
+    let count = if lp.nested {
+        1
+    } else {
+        clauses.iter().take_while(|c| matches!(c, Clause::Bind(..))).count()
+    };
+
+    let (group, rest) = clauses.split_at(count);
+    let mut sequences = Vec::with_capacity(count);
+
+    for (idx, clause) in group.iter().enumerate() {
+        let Clause::Bind(names, sequence) = clause else { unreachable!() };
+        let value = cx.level_down().eval(sequence)?;
+        sequences.push((names, into_sequence(value, lp.form, idx as u8 + 1)?));
+    }
+
+    loop {
+        let mut scope = cx.level_down();
+        (lp.state)(&mut scope);
+
+        for (names, sequence) in sequences.iter_mut() {
+            let Some(value) = sequence.next(cx)? else {
+                return Ok(Flow::Continue);
+            };
+
+            bind(&mut scope, lp.form, names, value)?;
+        }
+
+        if let Flow::Stop = run(&mut scope, lp, rest, body)? {
+            return Ok(Flow::Stop);
+        }
+    }
+}
+
+fn iterate<'a>(
+    cx: &mut Context<'_, 'a>,
+    lp: &Loop<'_, 'a>,
+    clauses: &AnyEval<'a>,
+    body: &mut dyn FnMut(&mut Context<'_, 'a>) -> Result<Flow, InterpreterError>
+) -> Result<(), InterpreterError> {
+    let clauses = parse_clauses(lp.form, clauses)?;
+    let mut scope = cx.level_down();
+    (lp.state)(&mut scope);
+
+    run(&mut scope, lp, &clauses, body)?;
+    Ok(())
+}
+
+/// Runs a comprehension like `(for (clause ...) body ...)`, handing the value of the body on
+/// every iteration to the given closure.
+fn each_value<'a>(
+    cx: &mut Context<'_, 'a>,
+    args: &[AnyEval<'a>],
+    form: &'static str,
+    nested: bool,
+    mut f: impl FnMut(Any<'a>) -> Result<Flow, InterpreterError>
+) -> Result<(), InterpreterError> {
+    require_arity!(at_least 2, args);
+
+    let lp = Loop {
+        form,
+        nested,
+        state: &|_| ()
+    };
+
+    let body = &args[1..];
+    iterate(cx, &lp, &args[0], &mut |scope| f(eval_sequence(scope, body)?))
+}
+
+fn is_false(value: &Any<'_>) -> bool {
+    matches!(value, Any::Primitive(DataType::Boolean(false)))
+}
+
+fn discard_values<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>], form: &'static str, nested: bool) -> Result<Any<'a>, InterpreterError> {
+    each_value(cx, args, form, nested, |_| Ok(Flow::Continue))?;
+    Ok(Any::Void(()))
+}
+
+fn collect_list<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>], form: &'static str, nested: bool) -> Result<Any<'a>, InterpreterError> {
+    let mut items = LinkedList::new();
+
+    each_value(cx, args, form, nested, |value| {
+        items.push_back(value);
+        Ok(Flow::Continue)
+    })?;
+
+    Ok(Any::Composed(Box::new(Composed::List(List(items)))))
+}
+
+fn collect_vector<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>], form: &'static str, nested: bool) -> Result<Any<'a>, InterpreterError> {
+    let mut items = Vec::new();
+
+    each_value(cx, args, form, nested, |value| {
+        items.push(value);
+        Ok(Flow::Continue)
+    })?;
+
+    Ok(Any::Composed(Box::new(Composed::Vector(Vector(items)))))
+}
+
+/// Values of a body ending with `(values v ...)`, or the single value of any other body.
+fn body_values<'a>(scope: &mut Context<'_, 'a>, body: &[AnyEval<'a>]) -> Result<Vec<Any<'a>>, InterpreterError> {
+    let Some((last, init)) = body.split_last() else {
+        return Ok(vec![Any::Void(())]);
+    };
+
+    eval_sequence(scope, init)?;
+
+    let values = last.get_expression()
+        .filter(|tree| tree.node.as_ref().and_then(AnyEval::get_ident).copied() == Some("values"));
+
+    match values {
+        Some(tree) => tree.children.iter().map(|value| scope.level_down().eval(value)).collect(),
+        None => Ok(vec![scope.eval(last)?])
+    }
+}
+
+/// The body produces every entry with `(values key value)`, or as a `(cons key value)` pair.
+fn collect_hash<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>], form: &'static str, nested: bool) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(at_least 2, args);
+
+    let mut table = HashTable(Vec::new());
+
+    let lp = Loop {
+        form,
+        nested,
+        state: &|_| ()
+    };
+
+    let body = &args[1..];
+
+    iterate(cx, &lp, &args[0], &mut |scope| {
+        let (key, value) = match body_values(scope, body)?.as_slice() {
+            [key, value] => (key.clone(), value.clone()),
+            [entry] if entry.get_composed().map(|c| c.is_pair()).unwrap_or(false) => {
+                let pair = entry.get_composed().and_then(|c| c.get_pair()).unwrap();
+                (pair.left.clone(), pair.right.clone())
+            },
+            values => return Err(InterpreterError::Runtime(format!(
+                "{form}: expected the body to produce a key and a value, got {} values",
+                values.len()
+            )))
+        };
+
+        hash_insert(&mut table, key, value);
+        Ok(Flow::Continue)
+    })?;
+
+    Ok(Any::Composed(Box::new(Composed::Hash(table))))
+}
+
+/// `(for/fold ([accumulator init] ... [#:result result]) (clause ...) body ...)`, the body ends
+/// with `(values v ...)` when there are many accumulators. Since multiple values can't be
+/// returned, many accumulators need a `#:result` expression.
+fn fold_values<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>], form: &'static str, nested: bool) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(at_least 3, args);
+
+    let items = args[0].get_expression()
+        .ok_or(syntax_error(form, "expected a list of accumulators"))?
+        .as_vec();
+
+    let mut names = Vec::with_capacity(items.len());
+    let mut values = Vec::with_capacity(items.len());
+    let mut result = None;
+    let mut items = items.into_iter();
+
+    while let Some(item) = items.next() {
+        if item.get_ident().copied() == Some("#:result") {
+            result = Some(items.next().ok_or(syntax_error(form, "missing expression after #:result"))?);
+            continue;
+        }
+
+        let binding = item.get_expression()
+            .map(|tree| tree.as_vec())
+            .unwrap_or_default();
+
+        let (Some(name), [_, init]) = (binding.first().and_then(AnyEval::get_ident).copied(), binding.as_slice()) else {
+            return Err(syntax_error(form, "expected an accumulator like [name init]"));
+        };
+
+        names.push(name);
+        values.push(cx.level_down().eval(init)?);
+    }
+
+    if names.len() != 1 && result.is_none() {
+        return Err(syntax_error(form, "multiple values can't be returned, so a #:result expression is needed unless there's one accumulator"));
+    }
+
+    let values = RefCell::new(values);
+
+    let lp = Loop {
+        form,
+        nested,
+        state: &|scope| {
+            for (name, value) in names.iter().zip(values.borrow().iter()) {
+                scope.vars_mut().insert(name, value.clone());
+            }
+        }
+    };
+
+    let body = &args[2..];
+
+    iterate(cx, &lp, &args[1], &mut |scope| {
+        let next = body_values(scope, body)?;
+
+        if next.len() != names.len() {
+            return Err(InterpreterError::Runtime(format!(
+                "{form}: expected {} values from the body, got {}",
+                names.len(),
+                next.len()
+            )));
+        }
+
+        *values.borrow_mut() = next;
+        Ok(Flow::Continue)
+    })?;
+
+    let Some(result) = result else {
+        return Ok(values.into_inner().remove(0));
+    };
+
+    let mut scope = cx.level_down();
+    (lp.state)(&mut scope);
+    scope.eval(&result)
+}
+
+fn arithmetic<'a>(
+    cx: &mut Context<'_, 'a>,
+    args: &[AnyEval<'a>],
+    form: &'static str,
+    nested: bool,
+    initial: i32,
+    op: fn(ComparisonOperator, ComparisonOperator) -> Result<ComparisonOperator, NativeFnError>
+) -> Result<Any<'a>, InterpreterError> {
+    let mut values = Vec::new();
+
+    each_value(cx, args, form, nested, |value| {
+        values.push(value);
+        Ok(Flow::Continue)
+    })?;
+
+    let mut result = ComparisonOperator::from_primitive(&DataType::Integer(initial)).unwrap();
+
+    for value in values {
+        result = op(result, create_comparable(&value)?)?;
+    }
+
+    Ok(Any::Primitive(result.to_datatype()))
+}
+
+fn sum_values<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>], form: &'static str, nested: bool) -> Result<Any<'a>, InterpreterError> {
+    arithmetic(cx, args, form, nested, 0, ComparisonOperator::add)
+}
+
+fn product_values<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>], form: &'static str, nested: bool) -> Result<Any<'a>, InterpreterError> {
+    arithmetic(cx, args, form, nested, 1, ComparisonOperator::mul)
+}
+
+fn and_values<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>], form: &'static str, nested: bool) -> Result<Any<'a>, InterpreterError> {
+    let mut result = Any::Primitive(DataType::Boolean(true));
+
+    each_value(cx, args, form, nested, |value| {
+        let flow = if is_false(&value) { Flow::Stop } else { Flow::Continue };
+        result = value;
+        Ok(flow)
+    })?;
+
+    Ok(result)
+}
+
+fn or_values<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>], form: &'static str, nested: bool) -> Result<Any<'a>, InterpreterError> {
+    let mut result = Any::Primitive(DataType::Boolean(false));
+
+    each_value(cx, args, form, nested, |value| {
+        if is_false(&value) {
+            return Ok(Flow::Continue);
+        }
+
+        result = value;
+        Ok(Flow::Stop)
+    })?;
+
+    Ok(result)
+}
+
+fn first_value<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>], form: &'static str, nested: bool) -> Result<Any<'a>, InterpreterError> {
+    let mut result = Any::Primitive(DataType::Boolean(false));
+
+    each_value(cx, args, form, nested, |value| {
+        result = value;
+        Ok(Flow::Stop)
+    })?;
+
+    Ok(result)
+}
+
+fn last_value<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>], form: &'static str, nested: bool) -> Result<Any<'a>, InterpreterError> {
+    let mut result = Any::Primitive(DataType::Boolean(false));
+
+    each_value(cx, args, form, nested, |value| {
+        result = value;
+        Ok(Flow::Continue)
+    })?;
+
+    Ok(result)
+}
+
+/// Generates the native entry points of every comprehension, both the parallel `for/x` version
+/// and the nested `for*/x` one.
+macro_rules! comprehensions {
+    ($($fun: ident = $name: literal, $nested_fun: ident = $nested_name: literal => $implementation: ident),* $(,)?) => {
+        $(
+            pub fn $fun<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+                $implementation(cx, args, $name, false)
+            }
+
+            pub fn $nested_fun<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+                $implementation(cx, args, $nested_name, true)
+            }
+        )*
+    };
+}
+
+comprehensions! {
+    r#for = "for", for_nested = "for*" => discard_values,
+    for_list = "for/list", for_list_nested = "for*/list" => collect_list,
+    for_vector = "for/vector", for_vector_nested = "for*/vector" => collect_vector,
+    for_hash = "for/hash", for_hash_nested = "for*/hash" => collect_hash,
+    for_fold = "for/fold", for_fold_nested = "for*/fold" => fold_values,
+    for_sum = "for/sum", for_sum_nested = "for*/sum" => sum_values,
+    for_product = "for/product", for_product_nested = "for*/product" => product_values,
+    for_and = "for/and", for_and_nested = "for*/and" => and_values,
+    for_or = "for/or", for_or_nested = "for*/or" => or_values,
+    for_first = "for/first", for_first_nested = "for*/first" => first_value,
+    for_last = "for/last", for_last_nested = "for*/last" => last_value,
+}
+
+#[test]
+fn test_for_comprehensions() {
+    use crate::interpreter::eval_source;
+
+    assert_eq!(eval_source("(for/list ([i (in-range 3)] [c \"abc\"]) (cons i c))").unwrap(), "'((0 . #\\a) (1 . #\\b) (2 . #\\c))");
+    assert_eq!(eval_source("(for*/list ([i 2] [j (in-list (list 5 6))]) (+ i j))").unwrap(), "'(5 6 6 7)");
+    assert_eq!(eval_source("(for/sum ([i (in-range 1 5)] #:when (> i 2)) i)").unwrap(), "7");
+    assert_eq!(eval_source("(for/fold ([acc 0]) ([i (in-naturals)] #:break (> acc 10)) (+ acc i))").unwrap(), "15");
+    assert_eq!(eval_source("(for/first ([i (in-naturals)] #:unless (< i 5)) i)").unwrap(), "5");
+    assert_eq!(eval_source("(for/hash ([(k v) (hash \"a\" 1)]) (cons v k))").unwrap(), "'#hash((1 . \"a\"))");
+    assert_eq!(eval_source("(for/hash ([i 2]) (values i (* i 10)))").unwrap(), "'#hash((0 . 0) (1 . 10))");
+    assert_eq!(
+        eval_source("(for/fold ([sum 0] [count 0] #:result (list sum count)) ([x (in-range 0.5 3)]) (values (+ sum x) (+ count 1)))").unwrap(),
+        "'(4.5 3)"
+    );
+    assert!(eval_source("(for/fold ([a 0] [b 0]) ([i 3]) (values a b))").is_err());
+    assert!(eval_source("(for/hash ([i 2]) (values i i i))").is_err());
+}
+
+#[test]
+fn test_for_shadows_parameters() {
+    use crate::interpreter::eval_source;
+
+    assert_eq!(eval_source("(define (f n) (for/list ([n (in-range 3)]) n)) (f 5)").unwrap(), "'(0 1 2)");
+    assert_eq!(
+        eval_source("(define (f n) (for*/sum ([i (in-range n)] #:when (> i 2) [n (list i)]) n)) (f 6)").unwrap(),
+        "12"
+    );
+    assert_eq!(
+        eval_source("(define (f acc) (for/fold ([acc acc] #:result (* acc 10)) ([i 3]) (+ acc i))) (f 1)").unwrap(),
+        "40"
+    );
+    assert_eq!(eval_source("(define (f flag n) (for/list ([i n] #:when flag) i)) (f #t 2)").unwrap(), "'(0 1)");
+}
@@ -13,43 +13,33 @@ pub fn boolean_value<'a>(cx: &mut Context<'_, 'a>, arg: &AnyEval<'a>) -> Result<
 }
 
 pub fn and<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
-    if args.is_empty() {
+    let Some((last, init)) = args.split_last() else {
         return Ok(Any::Primitive(DataType::Boolean(true)));
-    }
-
-    let mut value = None;
-
-    for item in args.iter() {
-        let (item, b) = boolean_value(cx, item)?;
+    };
 
-        if b {
-            value = Some(item);
-        } else {
+    for item in init {
+        if !boolean_value(cx, item)?.1 {
             return Ok(Any::Primitive(DataType::Boolean(false)));
         }
     }
 
-    Ok(value.unwrap())
+    cx.tail_level_down().eval(last)
 }
 
 pub fn or<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
-    if args.is_empty() {
+    let Some((last, init)) = args.split_last() else {
         return Ok(Any::Primitive(DataType::Boolean(false)));
-    }
-
-    let mut value = None;
+    };
 
-    for item in args.iter() {
+    for item in init {
         let (item, b) = boolean_value(cx, item)?;
 
         if b {
             return Ok(item);
-        } else {
-            value = Some(item);
         }
     }
 
-    Ok(Any::Primitive(DataType::Boolean(false)))
+    cx.tail_level_down().eval(last)
 }
 
 pub fn not<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
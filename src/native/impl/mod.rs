@@ -9,3 +9,23 @@ pub mod list;
 pub mod util;
 pub mod logic;
 pub mod branch;
+pub mod equality;
+pub mod pattern;
+pub mod structs;
+pub mod vector;
+pub mod hash;
+pub mod sequence;
+pub mod iteration;
+pub mod promise;
+pub mod format;
+pub mod exn;
+pub mod control;
+pub mod parameter;
+pub mod output;
+pub mod ports;
+pub mod input;
+pub mod files;
+pub mod character;
+pub mod regexp;
+pub mod bytes;
+pub mod boxes;
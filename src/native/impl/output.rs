@@ -0,0 +1,160 @@
+use crate::interpreter::any::AnyEval;
+use crate::interpreter::context::Context;
+use crate::interpreter::error::InterpreterError;
+use crate::interpreter::Interpreter;
+use crate::macros::require_arity;
+use crate::native::error::NativeFnError;
+use crate::native::r#impl::bytes::require_bytes;
+use crate::native::r#impl::format::{display_string, format_string, print_string, write_string};
+use crate::native::r#impl::parameter::current_port;
+use crate::native::r#impl::string::require_string;
+use crate::primitives::any::Any;
+use crate::primitives::composed::Composed;
+use crate::primitives::port::Port;
+use crate::primitives::DataType;
+
+pub fn require_output_port<'a>(
+    cx: &mut Context<'_, 'a>,
+    arg: &AnyEval<'a>,
+    fn_name: &'static str,
+    position: u8
+) -> Result<Port, InterpreterError> {
+    match cx.level_down().eval(arg)? {
+        Any::Composed(c) if c.get_port().map(Port::is_output).unwrap_or(false) => {
+            let Composed::Port(p) = *c else { unreachable!() };
+            Ok(p)
+        },
+        other => Err(NativeFnError::UnexpectedType {
+            function: fn_name,
+            argument_position: position,
+            got: other.variant_name(),
+            expected: "output port"
+        }.into())
+    }
+}
+
+/// Port given at `position`, defaulting to the current output port.
+fn output_port<'a>(
+    cx: &mut Context<'_, 'a>,
+    args: &[AnyEval<'a>],
+    fn_name: &'static str,
+    position: usize
+) -> Result<Port, InterpreterError> {
+    match args.get(position) {
+        Some(port) => require_output_port(cx, port, fn_name, position as u8 + 1),
+        None => current_port(cx, "current-output-port")
+    }
+}
+
+/// Writes the rendering of `(value [port])`, followed by `end`.
+fn write_value<'a>(
+    cx: &mut Context<'_, 'a>,
+    args: &[AnyEval<'a>],
+    fn_name: &'static str,
+    render: fn(&Any<'_>, &Interpreter<'_>) -> String,
+    end: &str
+) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(at_least 1, args);
+
+    let value = cx.level_down().eval(&args[0])?;
+    let port = output_port(cx, args, fn_name, 1)?;
+
+    port.write_str(&(render(&value, cx.interpreter()) + end))?;
+    Ok(Any::Void(()))
+}
+
+pub fn display<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    write_value(cx, args, "display", display_string, "")
+}
+
+pub fn displayln<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    write_value(cx, args, "displayln", display_string, "\n")
+}
+
+pub fn write<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    write_value(cx, args, "write", write_string, "")
+}
+
+pub fn writeln<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    write_value(cx, args, "writeln", write_string, "\n")
+}
+
+pub fn print<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    write_value(cx, args, "print", print_string, "")
+}
+
+/// `(write-bytes bytes [port])`, returns the amount of bytes written.
+pub fn write_bytes<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(at_least 1, args);
+    require_arity!(at_most 2, args);
+
+    let bytes = require_bytes(cx, &args[0], "write-bytes", 1)?;
+    let port = output_port(cx, args, "write-bytes", 1)?;
+
+    port.write_bytes(&bytes)?;
+    Ok(Any::Primitive(DataType::Integer(bytes.len() as i32)))
+}
+
+pub fn newline<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    output_port(cx, args, "newline", 0)?.write_str("\n")?;
+    Ok(Any::Void(()))
+}
+
+/// Formats the template at `args[0]` with the rest of the arguments.
+fn format_args<'a>(
+    cx: &mut Context<'_, 'a>,
+    args: &[AnyEval<'a>],
+    fn_name: &'static str
+) -> Result<String, InterpreterError> {
+    require_arity!(at_least 1, args);
+
+    let template = require_string(cx, &args[0], fn_name, 1)?;
+    let values = args[1..].iter()
+        .map(|arg| cx.level_down().eval(arg))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    format_string(cx.interpreter(), fn_name, &template, &values)
+}
+
+pub fn format<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    let out = format_args(cx, args, "format")?;
+    Ok(Any::Primitive(DataType::String(out.into())))
+}
+
+pub fn printf<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    let out = format_args(cx, args, "printf")?;
+    current_port(cx, "current-output-port")?.write_str(&out)?;
+    Ok(Any::Void(()))
+}
+
+pub fn eprintf<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    let out = format_args(cx, args, "eprintf")?;
+    current_port(cx, "current-error-port")?.write_str(&out)?;
+    Ok(Any::Void(()))
+}
+
+pub fn fprintf<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(at_least 2, args);
+
+    let port = require_output_port(cx, &args[0], "fprintf", 1)?;
+    let out = format_args(cx, &args[1..], "fprintf")?;
+    port.write_str(&out)?;
+    Ok(Any::Void(()))
+}
+
+#[test]
+fn test_format() {
+    use crate::interpreter::eval_source;
+
+    assert_eq!(
+        eval_source("(format \"~a|~s|~v\" \"text\" \"text\" \"text\")").unwrap(),
+        "\"text|\\\"text\\\"|\\\"text\\\"\""
+    );
+    assert_eq!(
+        eval_source("(format \"~a ~s ~v\" (list \"a\" #\\b 'c) (list \"a\" #\\b 'c) (list \"a\" 'c))").unwrap(),
+        "\"(a b c) (\\\"a\\\" #\\\\b c) '(\\\"a\\\" c)\""
+    );
+    assert_eq!(eval_source("(format \"~a~~~a\" (vector 1) (cons 1 'x))").unwrap(), "\"#(1)~(1 . x)\"");
+    assert!(eval_source("(format \"~a ~a\" 1)").is_err());
+    assert_eq!(eval_source("(void (display \"\") (newline (current-error-port)))").unwrap(), "");
+}
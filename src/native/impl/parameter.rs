@@ -0,0 +1,194 @@
+use crate::interpreter::any::AnyEval;
+use crate::interpreter::context::Context;
+use crate::interpreter::error::InterpreterError;
+use crate::interpreter::vars::OwnedStorage;
+use crate::macros::require_arity;
+use crate::native::error::NativeFnError;
+use crate::native::r#impl::common::eval_sequence;
+use crate::primitives::any::Any;
+use crate::primitives::composed::{Composed, Vector};
+use crate::primitives::parameter::Parameter;
+use crate::primitives::port::{Eof, Port};
+use crate::primitives::sequence::{Sequence, Stream};
+use crate::primitives::DataType;
+
+fn parameter_value(parameter: Parameter) -> Any<'static> {
+    Any::Composed(Box::new(Composed::Parameter(parameter)))
+}
+
+/// Defines the builtin parameters, `eof` and `empty-stream` as globals, unless they were already defined by a previous run
+/// sharing the same variables.
+pub fn define_builtins(vars: &mut OwnedStorage) {
+    if vars.get("current-output-port").is_some() {
+        return;
+    }
+
+    let port = |port| Any::Composed(Box::new(Composed::Port(port)));
+
+    vars.insert("current-input-port", parameter_value(Parameter::new(port(Port::Stdin), None)));
+    vars.insert("current-output-port", parameter_value(Parameter::new(port(Port::Stdout), None)));
+    vars.insert("current-error-port", parameter_value(Parameter::new(port(Port::Stderr), None)));
+    vars.insert("eof", Any::Composed(Box::new(Composed::Eof(Eof))));
+    vars.insert("empty-stream", Any::Composed(Box::new(Composed::Sequence(Sequence::Stream(Stream::Empty)))));
+    vars.insert("current-command-line-arguments", parameter_value(Parameter::new(
+        Any::Composed(Box::new(Composed::Vector(Vector(Vec::new())))),
+        None
+    )));
+}
+
+fn builtin<'b>(vars: &'b OwnedStorage, name: &str) -> Option<&'b Parameter> {
+    vars.get(name)
+        .and_then(|v| v.get_composed())
+        .and_then(|c| c.get_parameter())
+}
+
+/// Sets the value of a builtin parameter, like the port used by `current-output-port`.
+pub fn set_builtin(vars: &OwnedStorage, name: &str, value: Any<'_>) {
+    if let Some(parameter) = builtin(vars, name) {
+        parameter.replace(value);
+    }
+}
+
+/// Sets the vector of strings returned by `current-command-line-arguments`.
+pub fn set_command_line_arguments(vars: &OwnedStorage, arguments: &[String]) {
+    set_builtin(vars, "current-command-line-arguments", Any::Composed(Box::new(Composed::Vector(Vector(arguments.iter()
+        .map(|arg| Any::Primitive(DataType::String(arg.clone().into())))
+        .collect())))));
+}
+
+/// Current value of one of the port parameters, like `current-output-port`.
+pub fn current_port(cx: &Context<'_, '_>, parameter: &'static str) -> Result<Port, InterpreterError> {
+    cx.get_var(parameter)
+        .and_then(|v| v.get_composed())
+        .and_then(|c| c.get_parameter())
+        .map(|p| p.get())
+        .and_then(|v| v.get_composed().and_then(|c| c.get_port()).cloned())
+        .ok_or(InterpreterError::Runtime(format!("{parameter}: not a port")))
+}
+
+/// Calls `body` with the builtin parameter set to `value`, restoring the previous value once it
+/// returns, like `parameterize` does.
+pub fn with_builtin<'a, F>(
+    cx: &mut Context<'_, 'a>,
+    name: &'static str,
+    value: Any<'a>,
+    body: F
+) -> Result<Any<'a>, InterpreterError>
+where
+    F: FnOnce(&mut Context<'_, 'a>) -> Result<Any<'a>, InterpreterError>
+{
+    let parameter = cx.get_var(name)
+        .and_then(|v| v.get_composed())
+        .and_then(|c| c.get_parameter())
+        .cloned()
+        .ok_or(InterpreterError::Runtime(format!("{name}: not a parameter")))?;
+
+    let old = parameter.replace(value);
+    let result = body(cx);
+    parameter.replace(old);
+
+    result
+}
+
+fn require_parameter<'a>(
+    cx: &mut Context<'_, 'a>,
+    arg: &AnyEval<'a>,
+    fn_name: &'static str,
+    position: u8
+) -> Result<Parameter, InterpreterError> {
+    match cx.level_down().eval(arg)? {
+        Any::Composed(c) if c.is_parameter() => {
+            let Composed::Parameter(p) = *c else { unreachable!() };
+            Ok(p)
+        },
+        other => Err(NativeFnError::UnexpectedType {
+            function: fn_name,
+            argument_position: position,
+            got: other.variant_name(),
+            expected: "parameter"
+        }.into())
+    }
+}
+
+/// `(make-parameter value [guard])`, the guard is only applied to the values given later, not to
+/// the initial one.
+pub fn make_parameter<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(at_least 1, args);
+
+    let value = cx.level_down().eval(&args[0])?;
+    let guard = args.get(1)
+        .map(|guard| cx.level_down().eval(guard))
+        .transpose()?;
+
+    Ok(parameter_value(Parameter::new(value, guard)))
+}
+
+pub fn is_parameter<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(exact 1, args);
+
+    let value = cx.level_down().eval(&args[0])?;
+    Ok(Any::Primitive(DataType::Boolean(value.get_composed().map(|c| c.is_parameter()).unwrap_or(false))))
+}
+
+/// `(parameterize ([parameter value] ...) body ...)`, the previous values are restored once the
+/// body is left, either normally, by an exception or by a continuation.
+pub fn parameterize<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(at_least 2, args);
+
+    let bindings = args[0].get_expression()
+        .ok_or(InterpreterError::InvalidExpression)?
+        .as_vec();
+
+    let mut values = Vec::with_capacity(bindings.len());
+
+    for binding in bindings {
+        let [parameter, value] = binding.get_expression()
+            .map(|b| b.as_vec())
+            .and_then(|b| <[_; 2]>::try_from(b).ok())
+            .ok_or(InterpreterError::InvalidExpression)?;
+
+        let parameter = require_parameter(cx, &parameter, "parameterize", 1)?;
+        let value = cx.level_down().eval(&value)?;
+        let value = parameter.guarded(cx, value)?;
+        values.push((parameter, value));
+    }
+
+    let previous = values.into_iter()
+        .map(|(parameter, value)| {
+            let old = parameter.replace(value);
+            (parameter, old)
+        })
+        .collect::<Vec<_>>();
+
+    let result = eval_sequence(&mut cx.level_down(), &args[1..]);
+
+    for (parameter, old) in previous.into_iter().rev() {
+        parameter.replace(old);
+    }
+
+    result
+}
+
+#[test]
+fn test_parameters() {
+    use crate::interpreter::eval_source;
+
+    let source = "(define level (make-parameter 'low))
+(define label (make-parameter \"none\" (lambda (v) (if (string? v) 'custom v))))
+(define (show) (list (level) (label)))
+(define inside (parameterize ([level 'high] [label \"text\"]) (show)))
+(define restored (show))
+(define after-error (with-handlers ([exn:fail? (lambda (e) (level))])
+  (parameterize ([level 'failing]) (error \"fail\"))))
+(define nested (parameterize ([level 'outer]) (parameterize ([level 'inner]) (level))))
+(level 'set)
+(list inside restored after-error nested (level) (parameter? level) (parameter? show))";
+
+    assert_eq!(
+        eval_source(source).unwrap(),
+        "'((high custom) (low \"none\") low inner set #t #f)"
+    );
+
+    assert_eq!(eval_source("(current-output-port)").unwrap(), "#<output-port:stdout>");
+    assert_eq!(eval_source("(current-command-line-arguments)").unwrap(), "'#()");
+}
@@ -0,0 +1,346 @@
+use std::collections::LinkedList;
+
+use crate::display::InterpreterDisplay;
+use crate::interpreter::any::AnyEval;
+use crate::interpreter::context::Context;
+use crate::interpreter::error::InterpreterError;
+use crate::interpreter::eval_tree::EvalTree;
+use crate::macros::require_arity;
+use crate::native::r#impl::common::eval_sequence;
+use crate::native::r#impl::equality::equal;
+use crate::native::r#impl::logic::boolean_value;
+use crate::native::r#impl::util::callable_for;
+use crate::primitives::any::Any;
+use crate::primitives::composed::{Composed, List};
+use crate::primitives::DataType;
+use crate::reader;
+
+type Bindings<'a> = Vec<(&'a str, Any<'a>)>;
+
+fn pattern_error(reason: &str) -> InterpreterError {
+    InterpreterError::Runtime(format!("match: syntax error in pattern, {reason}"))
+}
+
+fn is_ellipsis(pattern: &AnyEval<'_>) -> bool {
+    pattern.get_ident().map(|i| *i == "...").unwrap_or(false)
+}
+
+/// Names bound by a pattern, needed to bind empty lists when an ellipsis matches nothing and to
+/// keep the arguments of a procedure out of the clause.
+pub fn pattern_vars<'a>(pattern: &AnyEval<'a>, out: &mut Vec<&'a str>) {
+    match pattern {
+        AnyEval::Ident(i) if *i != "_" && *i != "..." => out.push(*i),
+        AnyEval::Expression(tree) => {
+            let skip = match tree.node.as_ref().and_then(AnyEval::get_ident) {
+                Some(&"quote") => return,
+                Some(&"?") => 1,
+                _ => 0
+            };
+
+            for child in tree.children.iter().skip(skip) {
+                pattern_vars(child, out);
+            }
+        },
+        _ => ()
+    }
+}
+
+fn match_all<'a>(
+    cx: &mut Context<'_, 'a>,
+    patterns: &[AnyEval<'a>],
+    values: &[Any<'a>],
+    bindings: &mut Bindings<'a>
+) -> Result<bool, InterpreterError> {
+    for (pattern, value) in patterns.iter().zip(values.iter()) {
+        if !matches(cx, pattern, value, bindings)? {
+            return Ok(false);
+        }
+    }
+
+    Ok(true)
+}
+
+/// `(list p ...)`, where a single sub pattern may be followed by `...` to match any number of
+/// items, binding each of its variables to the list of the matched values.
+fn match_list<'a>(
+    cx: &mut Context<'_, 'a>,
+    patterns: &[AnyEval<'a>],
+    value: &Any<'a>,
+    bindings: &mut Bindings<'a>
+) -> Result<bool, InterpreterError> {
+    let Some(items) = value.list_items() else { return Ok(false); };
+
+    let Some(ellipsis) = patterns.iter().position(is_ellipsis) else {
+        return Ok(items.len() == patterns.len() && match_all(cx, patterns, &items, bindings)?);
+    };
+
+    if ellipsis == 0 || patterns[ellipsis + 1..].iter().any(is_ellipsis) {
+        return Err(pattern_error("ellipsis must follow a single pattern in a list"));
+    }
+
+    let before = &patterns[..ellipsis - 1];
+    let repeated = &patterns[ellipsis - 1];
+    let after = &patterns[ellipsis + 1..];
+
+    if items.len() < before.len() + after.len() {
+        return Ok(false);
+    }
+
+    let middle_end = items.len() - after.len();
+
+    if !match_all(cx, before, &items[..before.len()], bindings)?
+        || !match_all(cx, after, &items[middle_end..], bindings)?
+    {
+        return Ok(false);
+    }
+
+    let mut vars = Vec::new();
+    pattern_vars(repeated, &mut vars);
+    let mut collected = vec![LinkedList::new(); vars.len()];
+
+    for item in &items[before.len()..middle_end] {
+        let mut inner = Vec::new();
+
+        if !matches(cx, repeated, item, &mut inner)? {
+            return Ok(false);
+        }
+
+        for (name, value) in inner {
+            if let Some(idx) = vars.iter().position(|v| *v == name) {
+                collected[idx].push_back(value);
+            }
+        }
+    }
+
+    for (name, values) in vars.into_iter().zip(collected) {
+        bindings.push((name, Any::Composed(Box::new(Composed::List(List(values))))));
+    }
+
+    Ok(true)
+}
+
+fn match_cons<'a>(
+    cx: &mut Context<'_, 'a>,
+    patterns: &[AnyEval<'a>],
+    value: &Any<'a>,
+    bindings: &mut Bindings<'a>
+) -> Result<bool, InterpreterError> {
+    if patterns.len() != 2 {
+        return Err(pattern_error("cons expects two patterns"));
+    }
+
+    if let Some(pair) = value.get_composed().and_then(|c| c.get_pair()) {
+        return match_all(cx, patterns, &[pair.left.clone(), pair.right.clone()], bindings);
+    }
+
+    let Some(mut items) = value.list_items() else { return Ok(false); };
+
+    if items.is_empty() {
+        return Ok(false);
+    }
+
+    let head = items.remove(0);
+    let tail = Any::Composed(Box::new(Composed::List(List(items.into_iter().collect()))));
+
+    match_all(cx, patterns, &[head, tail], bindings)
+}
+
+fn match_tree<'a>(
+    cx: &mut Context<'_, 'a>,
+    tree: &EvalTree<'a>,
+    value: &Any<'a>,
+    bindings: &mut Bindings<'a>
+) -> Result<bool, InterpreterError> {
+    let Some(head) = tree.node.as_ref().and_then(AnyEval::get_ident).copied() else {
+        return Err(pattern_error("expected an identifier at the head of the pattern"));
+    };
+    let children = tree.children.as_slice();
+
+    match head {
+        "quote" if children.len() == 1 => {
+            let datum = reader::datum(children[0].clone().to_expr());
+            Ok(equal(&datum, value))
+        },
+        "list" => match_list(cx, children, value, bindings),
+        "cons" => match_cons(cx, children, value, bindings),
+        "?" => {
+            let Some(predicate) = children.first() else {
+                return Err(pattern_error("? expects a predicate"));
+            };
+
+            let predicate = callable_for(cx, predicate, "match", 1)?;
+            let result = predicate.call(cx, &[AnyEval::from_any(value.clone())])?;
+
+            if let Any::Primitive(DataType::Boolean(false)) = result {
+                return Ok(false);
+            }
+
+            for pattern in &children[1..] {
+                if !matches(cx, pattern, value, bindings)? {
+                    return Ok(false);
+                }
+            }
+
+            Ok(true)
+        },
+        "and" => {
+            for pattern in children {
+                if !matches(cx, pattern, value, bindings)? {
+                    return Ok(false);
+                }
+            }
+
+            Ok(true)
+        },
+        "or" => {
+            for pattern in children {
+                let len = bindings.len();
+
+                if matches(cx, pattern, value, bindings)? {
+                    return Ok(true);
+                }
+
+                bindings.truncate(len);
+            }
+
+            Ok(false)
+        },
+        "not" => {
+            let mut ignored = Vec::new();
+
+            for pattern in children {
+                if matches(cx, pattern, value, &mut ignored)? {
+                    return Ok(false);
+                }
+            }
+
+            Ok(true)
+        },
+        other => {
+            let kind = cx.get_var(other)
+                .and_then(|v| v.get_composed())
+                .and_then(|c| c.get_structprocedure())
+                .map(|p| std::sync::Arc::clone(&p.kind))
+                .ok_or(pattern_error(&format!("unknown pattern form {other}")))?;
+
+            if children.len() != kind.field_count() {
+                return Err(pattern_error(&format!("wrong number of fields for struct {other}")));
+            }
+
+            let Some(instance) = value.get_composed()
+                .and_then(|c| c.get_struct())
+                .filter(|s| s.kind.is_a(&kind)) else {
+                return Ok(false);
+            };
+
+            let fields = instance.fields[..children.len()].to_vec();
+            match_all(cx, children, &fields, bindings)
+        }
+    }
+}
+
+/// Checks whether the value matches the pattern, pushing the variables it binds on success.
+pub fn matches<'a>(
+    cx: &mut Context<'_, 'a>,
+    pattern: &AnyEval<'a>,
+    value: &Any<'a>,
+    bindings: &mut Bindings<'a>
+) -> Result<bool, InterpreterError> {
+    let len = bindings.len();
+
+    let matched = match pattern {
+        AnyEval::Ident(i) if *i == "_" => true,
+        // a name used twice has to match equal values
+        AnyEval::Ident(i) => match bindings.iter().find(|(name, _)| name == i) {
+            Some((_, bound)) => equal(bound, value),
+            None => {
+                bindings.push((*i, value.clone()));
+                true
+            }
+        },
+        AnyEval::Expression(tree) => match_tree(cx, tree, value, bindings)?,
+        literal => equal(&cx.eval(literal)?, value)
+    };
+
+    if !matched {
+        bindings.truncate(len);
+    }
+
+    Ok(matched)
+}
+
+/// `(match value [pattern body ...] ...)`, where every clause may have a guard right after the
+/// pattern, like `[pattern #:when guard body ...]`.
+pub fn r#match<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(at_least 1, args);
+
+    let value = cx.level_down().eval(&args[0])?;
+
+    for clause in &args[1..] {
+        let clause = clause.get_expression()
+            .ok_or(InterpreterError::InvalidExpression)?
+            .as_vec();
+
+        let Some((pattern, mut body)) = clause.split_first() else {
+            return Err(InterpreterError::InvalidExpression);
+        };
+
+        let guard = match body {
+            [keyword, guard, rest @ ..] if keyword.get_ident().map(|k| *k == "#:when").unwrap_or(false) => {
+                body = rest;
+                Some(guard)
+            },
+            _ => None
+        };
+
+        if body.is_empty() {
+            return Err(InterpreterError::InvalidExpression);
+        }
+
+        let mut bindings = Vec::new();
+
+        if !matches(cx, pattern, &value, &mut bindings)? {
+            continue;
+        }
+
+        let mut scope = cx.level_down();
+
+        for (name, value) in bindings {
+            scope.vars_mut().insert(name, value);
+        }
+
+        if let Some(guard) = guard {
+            if !boolean_value(&mut scope, guard)?.1 {
+                continue;
+            }
+        }
+
+        return eval_sequence(&mut scope, body);
+    }
+
+    let mut given = String::new();
+    value.fmt(&mut given, cx.interpreter()).unwrap();
+
+    Err(InterpreterError::Runtime(format!("match: no matching clause for {given}")))
+}
+
+#[test]
+fn test_match_patterns() {
+    use crate::interpreter::eval_source;
+
+    assert_eq!(eval_source("(match (list 1 2 3) [(list a b ...) b])").unwrap(), "'(2 3)");
+    assert_eq!(eval_source("(match (cons 1 2) [(cons h t) (+ h t)])").unwrap(), "3");
+    assert_eq!(eval_source("(match 3 [x #:when (> x 5) 'big] [_ 'small])").unwrap(), "'small");
+    assert_eq!(
+        eval_source("(struct point (x y))\n(match (point 1 2) [(point a (? (lambda (v) (> v 1)) b)) b])").unwrap(),
+        "2"
+    );
+    assert!(eval_source("(match 1 [2 3])").is_err());
+
+    assert_eq!(eval_source("(match (list 1 2) [(list x x) 'same] [_ 'diff])").unwrap(), "'diff");
+    assert_eq!(eval_source("(match (list 1 1) [(list x x) 'same] [_ 'diff])").unwrap(), "'same");
+
+    // pattern variables shadow the arguments of the procedure
+    assert_eq!(eval_source("(define (f x) (match 5 [x x])) (f 1)").unwrap(), "5");
+    assert_eq!(eval_source("(define (f x y) (match (list 1 2) [(list x z) (list x y z)])) (f 9 8)").unwrap(), "'(1 8 2)");
+}
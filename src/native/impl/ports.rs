@@ -0,0 +1,161 @@
+use crate::interpreter::any::AnyEval;
+use crate::interpreter::context::Context;
+use crate::interpreter::error::InterpreterError;
+use crate::macros::require_arity;
+use crate::native::error::NativeFnError;
+use crate::native::r#impl::bytes::{mutable_bytes, require_bytes};
+use crate::native::r#impl::parameter::with_builtin;
+use crate::native::r#impl::string::require_string;
+use crate::primitives::any::Any;
+use crate::primitives::composed::Composed;
+use crate::primitives::port::Port;
+use crate::primitives::DataType;
+
+fn port_value<'a>(port: Port) -> Any<'a> {
+    Any::Composed(Box::new(Composed::Port(port)))
+}
+
+fn string_value<'a>(s: String) -> Any<'a> {
+    Any::Primitive(DataType::String(s.into()))
+}
+
+fn port_check<'a>(
+    cx: &mut Context<'_, 'a>,
+    args: &[AnyEval<'a>],
+    check: fn(&Port) -> bool
+) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(exact 1, args);
+
+    let value = cx.level_down().eval(&args[0])?;
+    Ok(Any::Primitive(DataType::Boolean(value.get_composed()
+        .and_then(|c| c.get_port())
+        .map(check)
+        .unwrap_or(false))))
+}
+
+pub fn is_port<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    port_check(cx, args, |_| true)
+}
+
+pub fn is_input_port<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    port_check(cx, args, Port::is_input)
+}
+
+pub fn is_output_port<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    port_check(cx, args, Port::is_output)
+}
+
+pub fn open_output_string<'a>(_: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(exact 0, args);
+    Ok(port_value(Port::output_string()))
+}
+
+pub fn open_input_string<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(exact 1, args);
+
+    let text = require_string(cx, &args[0], "open-input-string", 1)?;
+    Ok(port_value(Port::input_string(text)))
+}
+
+pub fn get_output_string<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(exact 1, args);
+
+    let value = cx.level_down().eval(&args[0])?;
+    let contents = value.get_composed()
+        .and_then(|c| c.get_port())
+        .and_then(Port::contents)
+        .ok_or(NativeFnError::UnexpectedType {
+            function: "get-output-string",
+            argument_position: 1,
+            got: value.variant_name(),
+            expected: "string output port"
+        })?;
+
+    Ok(string_value(contents))
+}
+
+/// `(open-output-bytes)`, string ports take bytes too so this is an output string port.
+pub fn open_output_bytes<'a>(_: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(exact 0, args);
+    Ok(port_value(Port::output_string()))
+}
+
+/// `(open-input-bytes bytes)`, the bytes are decoded as UTF-8 with invalid sequences read as
+/// U+FFFD, like `read-char` does.
+pub fn open_input_bytes<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(exact 1, args);
+
+    let bytes = require_bytes(cx, &args[0], "open-input-bytes", 1)?;
+    Ok(port_value(Port::input_string(String::from_utf8_lossy(&bytes))))
+}
+
+pub fn get_output_bytes<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(exact 1, args);
+
+    let value = cx.level_down().eval(&args[0])?;
+    let contents = value.get_composed()
+        .and_then(|c| c.get_port())
+        .and_then(Port::contents_bytes)
+        .ok_or(NativeFnError::UnexpectedType {
+            function: "get-output-bytes",
+            argument_position: 1,
+            got: value.variant_name(),
+            expected: "bytes output port"
+        })?;
+
+    Ok(mutable_bytes(contents))
+}
+
+/// `(with-output-to-string thunk)`, returns everything the thunk writes to the current output
+/// port.
+pub fn with_output_to_string<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(exact 1, args);
+
+    let thunk = cx.level_down().eval(&args[0])?;
+    let port = Port::output_string();
+
+    with_builtin(cx, "current-output-port", port_value(port.clone()), |cx| cx.apply(&thunk, &[]))?;
+    Ok(string_value(port.contents().unwrap_or_default()))
+}
+
+/// `(call-with-output-string proc)`, returns everything the procedure writes to the port it's
+/// called with.
+pub fn call_with_output_string<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(exact 1, args);
+
+    let procedure = cx.level_down().eval(&args[0])?;
+    let port = Port::output_string();
+
+    cx.apply(&procedure, &[AnyEval::from_any(port_value(port.clone()))])?;
+    Ok(string_value(port.contents().unwrap_or_default()))
+}
+
+/// `(with-input-from-string text thunk)`, calls the thunk with the current input port reading
+/// from the text.
+pub fn with_input_from_string<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(exact 2, args);
+
+    let text = require_string(cx, &args[0], "with-input-from-string", 1)?;
+    let thunk = cx.level_down().eval(&args[1])?;
+
+    with_builtin(cx, "current-input-port", port_value(Port::input_string(text)), |cx| cx.apply(&thunk, &[]))
+}
+
+#[test]
+fn test_string_ports() {
+    use crate::interpreter::eval_source;
+
+    let source = "(define out (open-output-string))
+(write 'sym out)
+(display \" and \" out)
+(fprintf out \"~s\" \"text\")
+(define captured (with-output-to-string (lambda () (display \"inside\") (newline))))
+(define called (call-with-output-string (lambda (port) (display (list 1 'a) port))))
+(define input (with-input-from-string \"abc\" (lambda () (current-input-port))))
+(list (get-output-string out) captured called input (output-port? out) (input-port? (open-input-string \"x\")) (port? input) (current-input-port))";
+
+    assert_eq!(
+        eval_source(source).unwrap(),
+        "'(\"sym and \\\"text\\\"\" \"inside\\n\" \"(1 a)\" #<input-port:string> #t #t #t #<input-port:stdin>)"
+    );
+}
@@ -0,0 +1,114 @@
+use crate::interpreter::any::AnyEval;
+use crate::interpreter::context::Context;
+use crate::interpreter::error::InterpreterError;
+use crate::interpreter::eval_tree::EvalTree;
+use crate::macros::require_arity;
+use crate::native::error::NativeFnError;
+use crate::primitives::any::Any;
+use crate::primitives::composed::Composed;
+use crate::primitives::promise::Promise;
+use crate::primitives::DataType;
+
+/// Sequences the body of a delaying form into a single expression.
+fn body<'a>(args: &[AnyEval<'a>]) -> AnyEval<'a> {
+    match args {
+        [expr] => expr.clone(),
+        _ => AnyEval::Expression(Box::new(EvalTree {
+            node: Some(AnyEval::Ident("begin")),
+            children: args.to_vec(),
+            span: None
+        }))
+    }
+}
+
+fn promise_value<'a>(promise: Promise) -> Any<'a> {
+    Any::Composed(Box::new(Composed::Promise(promise)))
+}
+
+/// `(delay body ...)`, the body is evaluated the first time the promise is forced.
+pub fn delay<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(at_least 1, args);
+    Ok(promise_value(Promise::delay(cx, body(args))))
+}
+
+/// `(delay/force expr)` and `(lazy body ...)`, where the body produces a promise, which is forced
+/// too when the created promise is forced.
+pub fn delay_force<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(at_least 1, args);
+    Ok(promise_value(Promise::delay_force(cx, body(args))))
+}
+
+/// `(make-promise value)`, a promise already forced to the given value, or the value itself when
+/// it is a promise already.
+pub fn make_promise<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(exact 1, args);
+
+    let value = cx.level_down().eval(&args[0])?;
+
+    if value.get_composed().map(|c| c.is_promise()).unwrap_or(false) {
+        return Ok(value);
+    }
+
+    Ok(promise_value(Promise::forced(value)))
+}
+
+/// `(force value)`, forcing promises and returning any other value as is.
+pub fn force<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(exact 1, args);
+
+    let value = cx.level_down().eval(&args[0])?;
+
+    match value.get_composed().and_then(|c| c.get_promise()) {
+        Some(promise) => promise.force(cx),
+        None => Ok(value)
+    }
+}
+
+pub fn is_promise<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(exact 1, args);
+
+    let value = cx.level_down().eval(&args[0])?;
+    Ok(Any::Primitive(DataType::Boolean(value.get_composed().map(|c| c.is_promise()).unwrap_or(false))))
+}
+
+pub fn is_promise_forced<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(exact 1, args);
+
+    let value = cx.level_down().eval(&args[0])?;
+    let promise = value.get_composed()
+        .and_then(|c| c.get_promise())
+        .ok_or(NativeFnError::UnexpectedType {
+            function: "promise-forced?",
+            argument_position: 1,
+            got: value.variant_name(),
+            expected: "promise"
+        })?;
+
+    Ok(Any::Primitive(DataType::Boolean(promise.is_forced())))
+}
+
+#[test]
+fn test_promises() {
+    use crate::interpreter::eval_source;
+
+    assert_eq!(eval_source("(define p (delay (void)))\n(promise-forced? p)").unwrap(), "#f");
+    assert_eq!(eval_source("(define p (delay (void)))\n(force p)\n(promise-forced? p)").unwrap(), "#t");
+    assert_eq!(eval_source("(force (delay/force (delay (+ 1 2))))").unwrap(), "3");
+    assert_eq!(eval_source("(force (lazy (make-promise 4)))").unwrap(), "4");
+    assert_eq!(eval_source("(promise? (make-promise 1))").unwrap(), "#t");
+    assert_eq!(eval_source("(force 5)").unwrap(), "5");
+    assert_eq!(eval_source("(define q (delay 3)) (force (delay/force q)) (promise-forced? q)").unwrap(), "#t");
+
+    // chains are forced in constant space
+    assert_eq!(
+        eval_source("(define (l n) (lazy (if (= n 0) (delay 'end) (l (- n 1))))) (force (l 10000))").unwrap(),
+        "'end"
+    );
+
+    // exceptions are kept like values, so the body runs once
+    assert_eq!(eval_source("(define out (open-output-string))
+(define p (delay (display \"ran \" out) (error \"boom\")))
+(define first (with-handlers ([exn:fail? exn-message]) (force p)))
+(define second (with-handlers ([exn:fail? exn-message]) (force p)))
+(list first second (get-output-string out))").unwrap(), "'(\"boom\" \"boom\" \"ran \")");
+}
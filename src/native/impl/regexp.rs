@@ -0,0 +1,351 @@
+use std::borrow::Cow;
+use std::collections::LinkedList;
+
+use crate::interpreter::any::AnyEval;
+use crate::interpreter::context::Context;
+use crate::interpreter::error::InterpreterError;
+use crate::macros::require_arity;
+use crate::native::error::NativeFnError;
+use crate::native::r#impl::string::{require_string, string_contents};
+use crate::native::r#impl::util::non_negative_int;
+use crate::primitives::any::Any;
+use crate::primitives::composed::{Composed, List};
+use crate::primitives::{DataType, RegexRepr, Regexp};
+
+fn regex_error(fn_name: &'static str, error: impl std::fmt::Display) -> InterpreterError {
+    InterpreterError::Runtime(format!("{fn_name}: {error}"))
+}
+
+/// Regular expression at `position`, strings are compiled as `regexp` would.
+pub fn require_regex<'a>(
+    cx: &mut Context<'_, 'a>,
+    arg: &AnyEval<'a>,
+    fn_name: &'static str,
+    position: u8
+) -> Result<Regexp, InterpreterError> {
+    let value = cx.eval(arg)?;
+
+    if let Some(regex) = value.get_primitive().and_then(DataType::get_regex) {
+        return Ok(regex.clone());
+    }
+
+    match string_contents(&value) {
+        Some(pattern) => Regexp::new(&pattern, RegexRepr::Rx).map_err(|e| regex_error(fn_name, e)),
+        None => Err(NativeFnError::UnexpectedType {
+            function: fn_name,
+            argument_position: position,
+            got: value.variant_name(),
+            expected: "(or/c regexp? string?)"
+        }.into())
+    }
+}
+
+fn string_value<'a>(s: &str) -> Any<'a> {
+    Any::Primitive(DataType::String(Cow::Owned(s.to_string())))
+}
+
+fn list<'a>(items: LinkedList<Any<'a>>) -> Any<'a> {
+    Any::Composed(Box::new(Composed::List(List(items))))
+}
+
+fn boolean<'a>(value: bool) -> Any<'a> {
+    Any::Primitive(DataType::Boolean(value))
+}
+
+/// Byte spans of a match and its groups, `None` for groups that didn't take part in it.
+type Groups = Vec<Option<(usize, usize)>>;
+
+/// Text matched by `group`, or `#f` if the group didn't take part in the match.
+fn group<'a>(text: &str, groups: &Groups, group: usize) -> Any<'a> {
+    match groups[group] {
+        Some((start, end)) => string_value(&text[start..end]),
+        None => boolean(false)
+    }
+}
+
+fn next_match(
+    regex: &Regexp,
+    text: &str,
+    start: usize,
+    fn_name: &'static str
+) -> Result<Option<Groups>, InterpreterError> {
+    let mut locations = regex.inner.capture_locations();
+    let found = regex.inner.captures_read_at(&mut locations, text.as_bytes(), start)
+        .map_err(|e| regex_error(fn_name, e))?;
+
+    Ok(found.map(|_| (0..locations.len()).map(|idx| locations.get(idx)).collect()))
+}
+
+/// Every match of `regex`, an empty match is also found right after the previous match while the
+/// next search after it starts one character later, like Racket does.
+fn all_matches(regex: &Regexp, text: &str, fn_name: &'static str) -> Result<Vec<Groups>, InterpreterError> {
+    let mut matches = Vec::new();
+    let mut start = 0;
+
+    while start <= text.len() {
+        let Some(groups) = next_match(regex, text, start, fn_name)? else { break; };
+        let Some((match_start, match_end)) = groups[0] else { break; };
+
+        start = match match_start == match_end {
+            true => match_end + text[match_end..].chars().next().map(char::len_utf8).unwrap_or(1),
+            false => match_end
+        };
+
+        matches.push(groups);
+    }
+
+    Ok(matches)
+}
+
+/// Part of the input string that's searched, given by the optional character positions after it.
+fn input<'a>(
+    cx: &mut Context<'_, 'a>,
+    args: &[AnyEval<'a>],
+    fn_name: &'static str
+) -> Result<String, InterpreterError> {
+    let text = require_string(cx, &args[1], fn_name, 2)?;
+    let length = text.chars().count();
+
+    let start = match args.get(2) {
+        Some(start) => non_negative_int(cx, start, fn_name, 3)?,
+        None => 0
+    };
+
+    let end = match args.get(3) {
+        Some(end) => non_negative_int(cx, end, fn_name, 4)?,
+        None => length
+    };
+
+    if end > length || start > end {
+        return Err(InterpreterError::OutOfBounds { length, got: end.max(start) });
+    }
+
+    Ok(text.chars().skip(start).take(end - start).collect())
+}
+
+/// `(regexp string)`
+pub fn regexp<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(exact 1, args);
+
+    let pattern = require_string(cx, &args[0], "regexp", 1)?;
+    let regex = Regexp::new(&pattern, RegexRepr::Rx).map_err(|e| regex_error("regexp", e))?;
+    Ok(Any::Primitive(DataType::Regex(regex)))
+}
+
+/// `(pregexp string)`
+pub fn pregexp<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(exact 1, args);
+
+    let pattern = require_string(cx, &args[0], "pregexp", 1)?;
+    let regex = Regexp::new(&pattern, RegexRepr::Px).map_err(|e| regex_error("pregexp", e))?;
+    Ok(Any::Primitive(DataType::Regex(regex)))
+}
+
+pub fn is_regexp<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(exact 1, args);
+
+    let value = cx.eval(&args[0])?;
+    Ok(boolean(value.get_primitive().map(DataType::is_regex).unwrap_or(false)))
+}
+
+/// `(regexp-match pattern input [start end])`, the match followed by its groups or `#f`.
+pub fn regexp_match<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(at_least 2, args);
+    require_arity!(at_most 4, args);
+
+    let regex = require_regex(cx, &args[0], "regexp-match", 1)?;
+    let text = input(cx, args, "regexp-match")?;
+
+    Ok(match next_match(&regex, &text, 0, "regexp-match")? {
+        Some(groups) => list((0..groups.len()).map(|idx| group(&text, &groups, idx)).collect()),
+        None => boolean(false)
+    })
+}
+
+/// `(regexp-match* pattern input [start end])`, every match without its groups.
+pub fn regexp_match_all<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(at_least 2, args);
+    require_arity!(at_most 4, args);
+
+    let regex = require_regex(cx, &args[0], "regexp-match*", 1)?;
+    let text = input(cx, args, "regexp-match*")?;
+
+    let matches = all_matches(&regex, &text, "regexp-match*")?;
+    Ok(list(matches.iter().map(|groups| group(&text, groups, 0)).collect()))
+}
+
+pub fn is_regexp_match<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(at_least 2, args);
+    require_arity!(at_most 4, args);
+
+    let regex = require_regex(cx, &args[0], "regexp-match?", 1)?;
+    let text = input(cx, args, "regexp-match?")?;
+
+    let found = regex.inner.is_match(text.as_bytes()).map_err(|e| regex_error("regexp-match?", e))?;
+    Ok(boolean(found))
+}
+
+/// Expands the references of an insert string, `&` and `\0` being the whole match, `\n` the
+/// nth group and `\&` or `\\` the literal characters.
+fn expand_insert(insert: &str, text: &str, groups: &Groups, out: &mut String) {
+    let mut chars = insert.chars();
+
+    while let Some(c) = chars.next() {
+        let group = match c {
+            '&' => Some(0),
+            '\\' => match chars.next() {
+                Some(digit @ '0'..='9') => digit.to_digit(10).map(|d| d as usize),
+                Some('$') => continue,
+                Some(other) => {
+                    out.push(other);
+                    continue;
+                },
+                None => {
+                    out.push('\\');
+                    continue;
+                }
+            },
+            _ => None
+        };
+
+        match group {
+            Some(group) => {
+                if let Some(Some((start, end))) = groups.get(group) {
+                    out.push_str(&text[*start..*end]);
+                }
+            },
+            None => out.push(c)
+        }
+    }
+}
+
+fn replace<'a>(
+    cx: &mut Context<'_, 'a>,
+    args: &[AnyEval<'a>],
+    fn_name: &'static str,
+    all: bool
+) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(exact 3, args);
+
+    let regex = require_regex(cx, &args[0], fn_name, 1)?;
+    let text = require_string(cx, &args[1], fn_name, 2)?;
+    let insert = cx.eval(&args[2])?;
+    let insert_text = string_contents(&insert).map(Cow::into_owned);
+
+    let matches = match all {
+        true => all_matches(&regex, &text, fn_name)?,
+        false => next_match(&regex, &text, 0, fn_name)?.into_iter().collect()
+    };
+
+    let mut out = String::with_capacity(text.len());
+    let mut last = 0;
+
+    for groups in matches {
+        let Some((start, end)) = groups[0] else { continue; };
+
+        out.push_str(&text[last..start]);
+        last = end;
+
+        match insert_text.as_deref() {
+            Some(insert) => expand_insert(insert, &text, &groups, &mut out),
+            None => {
+                let args = (0..groups.len())
+                    .map(|idx| AnyEval::from_any(group(&text, &groups, idx)))
+                    .collect::<Vec<_>>();
+
+                let replaced = cx.apply(&insert, &args)?;
+                let replaced = string_contents(&replaced).ok_or(NativeFnError::UnexpectedType {
+                    function: fn_name,
+                    argument_position: 3,
+                    got: replaced.variant_name(),
+                    expected: "string?"
+                })?;
+
+                out.push_str(&replaced);
+            }
+        }
+    }
+
+    out.push_str(&text[last..]);
+    Ok(string_value(&out))
+}
+
+/// `(regexp-replace pattern input insert)`, the insert being a string or a procedure called with
+/// the match and its groups.
+pub fn regexp_replace<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    replace(cx, args, "regexp-replace", false)
+}
+
+/// `(regexp-replace* pattern input insert)`, like `regexp-replace` for every match.
+pub fn regexp_replace_all<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    replace(cx, args, "regexp-replace*", true)
+}
+
+/// `(regexp-split pattern input [start end])`, the text between matches including the empty
+/// pieces at the ends.
+pub fn regexp_split<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(at_least 2, args);
+    require_arity!(at_most 4, args);
+
+    let regex = require_regex(cx, &args[0], "regexp-split", 1)?;
+    let text = input(cx, args, "regexp-split")?;
+
+    let mut pieces = LinkedList::new();
+    let mut last = 0;
+
+    for groups in all_matches(&regex, &text, "regexp-split")? {
+        let Some((start, end)) = groups[0] else { continue; };
+
+        pieces.push_back(string_value(&text[last..start]));
+        last = end;
+    }
+
+    pieces.push_back(string_value(&text[last..]));
+    Ok(list(pieces))
+}
+
+/// `(regexp-quote string)`, escapes every character with a special meaning in a pattern.
+pub fn regexp_quote<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(exact 1, args);
+
+    let text = require_string(cx, &args[0], "regexp-quote", 1)?;
+    let mut quoted = String::with_capacity(text.len());
+
+    for c in text.chars() {
+        if "\\^$.|?*+()[]{}".contains(c) {
+            quoted.push('\\');
+        }
+
+        quoted.push(c);
+    }
+
+    Ok(string_value(&quoted))
+}
+
+#[test]
+fn test_regexps() {
+    use crate::interpreter::eval_source;
+
+    let source = r#"(list #rx"a+" #px"\\d+" (regexp "x|y") (regexp? (pregexp "z")) (regexp? "z")
+  (regexp-match #px"(\\w+)@(\\w+)?" "mail: me@ rest") (regexp-match #px"\\d+" "no digits")
+  (regexp-match "b" "abcb" 2) (regexp-match* #px"\\d+" "1, 22 and 333") (regexp-match? #rx"^a" "abc")
+  (regexp-replace #rx"(o)" "foo" "[\\1&]") (regexp-replace* #px"\\s+" "a  b   c" "_")
+  (regexp-replace* #rx"[aeiou]" "banana" (lambda (m) (string-upcase m)))
+  (regexp-split #rx", *" "a, b,c") (regexp-split #rx"" "abc") (regexp-split #rx" *" "12  34") (regexp-quote "1+1=2?")
+  (equal? #rx"a" (regexp "a")))"#;
+
+    assert_eq!(
+        eval_source(source).unwrap(),
+        r#"'(#rx"a+" #px"\\d+" #rx"x|y" #t #f ("me@" "me" #f) #f ("b") ("1" "22" "333") #t "f[oo]o" "a_b_c" "bAnAnA" ("a" "b" "c") ("" "a" "b" "c" "") ("" "1" "2" "" "3" "4" "") "1\\+1=2\\?" #t)"#
+    );
+
+    // `#rx` patterns have no classes or bounded repetitions
+    let flavours = r#"(list (regexp-match #rx"\\d" "d1") (regexp-match #px"\\d" "d1")
+  (regexp-match #rx"a{2}" "aa a{2}") (regexp-match #px"a{2}" "aa a{2}") (regexp-match (regexp "[\\]") "a\\")
+  (regexp-match (pregexp "\\w+") "!ok"))"#;
+
+    assert_eq!(
+        eval_source(flavours).unwrap(),
+        r#"'(("d") ("1") ("a{2}") ("aa") ("\\") ("ok"))"#
+    );
+}
@@ -0,0 +1,614 @@
+use std::borrow::Cow;
+use std::collections::LinkedList;
+
+use crate::interpreter::any::AnyEval;
+use crate::interpreter::context::Context;
+use crate::interpreter::error::InterpreterError;
+use crate::macros::require_arity;
+use crate::native::error::NativeFnError;
+use crate::native::r#impl::input::input_port;
+use crate::native::r#impl::util::non_negative_int;
+use crate::primitives::any::Any;
+use crate::primitives::composed::{Composed, List, Pair, Vector};
+use crate::primitives::promise::Promise;
+use crate::primitives::sequence::{Sequence, Stream};
+use crate::primitives::DataType;
+
+/// Iterator over the items of any value usable as a sequence.
+pub enum SequenceIter<'a> {
+    Items(std::vec::IntoIter<Any<'a>>),
+    Range {
+        next: i32,
+        end: Option<i32>,
+        step: i32
+    },
+    RealRange {
+        next: f64,
+        end: f64,
+        step: f64
+    },
+    /// Sequences whose items are produced on demand, walked with [`uncons`]
+    Lazy(Any<'a>)
+}
+
+impl<'a> SequenceIter<'a> {
+    pub fn next(&mut self, cx: &mut Context<'_, 'a>) -> Result<Option<Any<'a>>, InterpreterError> {
+        match self {
+            Self::Items(items) => Ok(items.next()),
+            Self::Range { next, end, step } => {
+                let finished = match *end {
+                    Some(end) if *step >= 0 => *next >= end,
+                    Some(end) => *next <= end,
+                    None => false
+                };
+
+                if finished {
+                    return Ok(None);
+                }
+
+                let current = *next;
+                *next += *step;
+
+                Ok(Some(Any::Primitive(DataType::Integer(current))))
+            },
+            Self::RealRange { next, end, step } => {
+                let finished = if *step >= 0.0 { *next >= *end } else { *next <= *end };
+
+                if finished {
+                    return Ok(None);
+                }
+
+                let current = *next;
+                *next += *step;
+
+                Ok(Some(Any::Primitive(DataType::Floating(current as f32))))
+            },
+            Self::Lazy(sequence) => {
+                let Some((first, rest)) = uncons(cx, sequence)? else {
+                    return Ok(None);
+                };
+
+                *sequence = rest;
+                Ok(Some(first))
+            }
+        }
+    }
+}
+
+fn character(c: char) -> Any<'static> {
+    Any::Primitive(DataType::Character(c))
+}
+
+fn hash_pairs<'a>(items: Vec<(Any<'a>, Any<'a>)>) -> Vec<Any<'a>> {
+    items.into_iter()
+        .map(|(left, right)| Any::Composed(Box::new(Composed::Pair(Pair { left, right }))))
+        .collect()
+}
+
+fn stream_value(stream: Stream<'_>) -> Any<'_> {
+    Any::Composed(Box::new(Composed::Sequence(Sequence::Stream(stream))))
+}
+
+/// Whether the value can be iterated: lists, vectors, strings, hashes, non negative integers,
+/// ranges and streams.
+pub fn is_sequence(value: &Any<'_>) -> bool {
+    if value.list_items().is_some() {
+        return true;
+    }
+
+    match value {
+        Any::Primitive(DataType::String(_)) => true,
+        Any::Primitive(DataType::Integer(n)) => *n >= 0,
+        Any::Composed(c) => matches!(
+            c.as_ref(),
+            Composed::Vector(_) | Composed::Hash(_) | Composed::Sequence(_) | Composed::MutableString(_)
+        ),
+        _ => false
+    }
+}
+
+fn require_sequence(value: &Any<'_>, fn_name: &'static str, position: u8) -> Result<(), InterpreterError> {
+    if is_sequence(value) {
+        Ok(())
+    } else {
+        Err(NativeFnError::UnexpectedType {
+            function: fn_name,
+            argument_position: position,
+            got: value.variant_name(),
+            expected: "sequence"
+        }.into())
+    }
+}
+
+/// Builds an iterator over the given value: lists, vectors, strings (as characters), hashes
+/// (as key/value pairs), non negative integers `n` (as the range from 0 to n) and sequences.
+pub fn into_sequence<'a>(
+    value: Any<'a>,
+    fn_name: &'static str,
+    position: u8
+) -> Result<SequenceIter<'a>, InterpreterError> {
+    require_sequence(&value, fn_name, position)?;
+
+    if let Some(items) = value.list_items() {
+        return Ok(SequenceIter::Items(items.into_iter()));
+    }
+
+    if let Some(s) = value.get_composed().and_then(|c| c.get_mutablestring()) {
+        return Ok(SequenceIter::Items(s.borrow().chars().map(character).collect::<Vec<_>>().into_iter()));
+    }
+
+    Ok(match value {
+        Any::Primitive(DataType::String(s)) => SequenceIter::Items(s.chars()
+            .map(character)
+            .collect::<Vec<_>>()
+            .into_iter()),
+        Any::Primitive(DataType::Integer(n)) => SequenceIter::Range {
+            next: 0,
+            end: Some(n),
+            step: 1
+        },
+        Any::Composed(c) => match *c {
+            Composed::Vector(v) => SequenceIter::Items(v.0.into_iter()),
+            Composed::Hash(h) => SequenceIter::Items(hash_pairs(h.0).into_iter()),
+            Composed::Sequence(Sequence::Range { start, end, step }) => SequenceIter::Range {
+                next: start,
+                end,
+                step
+            },
+            Composed::Sequence(Sequence::RealRange { start, end, step }) => SequenceIter::RealRange {
+                next: start,
+                end,
+                step
+            },
+            other => SequenceIter::Lazy(Any::Composed(Box::new(other)))
+        },
+        _ => unreachable!()
+    })
+}
+
+/// Splits a sequence into its first item and the sequence of the remaining ones, producing only
+/// the first item of lazy sequences. Returns `None` when the sequence is empty.
+pub fn uncons<'a>(
+    cx: &mut Context<'_, 'a>,
+    sequence: &Any<'a>
+) -> Result<Option<(Any<'a>, Any<'a>)>, InterpreterError> {
+    if let Some(mut items) = sequence.list_items() {
+        if items.is_empty() {
+            return Ok(None);
+        }
+
+        let first = items.remove(0);
+        let rest = Any::Composed(Box::new(Composed::List(List(items.into_iter().collect()))));
+        return Ok(Some((first, rest)));
+    }
+
+    let stream = match sequence {
+        Any::Primitive(DataType::String(s)) => {
+            let Some(first) = s.chars().next() else { return Ok(None); };
+            let rest = Any::Primitive(DataType::String(Cow::Owned(s[first.len_utf8()..].to_string())));
+            return Ok(Some((character(first), rest)));
+        },
+        Any::Primitive(DataType::Integer(n)) if *n >= 0 => {
+            let range = Sequence::Range { start: 0, end: Some(*n), step: 1 };
+            return uncons(cx, &Any::Composed(Box::new(Composed::Sequence(range))));
+        },
+        Any::Composed(c) => match c.as_ref() {
+            Composed::Vector(v) => {
+                let Some((first, rest)) = v.0.split_first() else { return Ok(None); };
+                let rest = Any::Composed(Box::new(Composed::Vector(Vector(rest.to_vec()))));
+                return Ok(Some((first.clone(), rest)));
+            },
+            Composed::Hash(h) => {
+                let pairs = Any::Composed(Box::new(Composed::List(List(hash_pairs(h.0.clone()).into_iter().collect()))));
+                return uncons(cx, &pairs);
+            },
+            Composed::Sequence(Sequence::Range { start, end, step }) => {
+                let mut iter = SequenceIter::Range { next: *start, end: *end, step: *step };
+                let Some(first) = iter.next(cx)? else { return Ok(None); };
+                let rest = Sequence::Range { start: start + step, end: *end, step: *step };
+                return Ok(Some((first, Any::Composed(Box::new(Composed::Sequence(rest))))));
+            },
+            Composed::Sequence(Sequence::RealRange { start, end, step }) => {
+                let mut iter = SequenceIter::RealRange { next: *start, end: *end, step: *step };
+                let Some(first) = iter.next(cx)? else { return Ok(None); };
+                let rest = Sequence::RealRange { start: start + step, end: *end, step: *step };
+                return Ok(Some((first, Any::Composed(Box::new(Composed::Sequence(rest))))));
+            },
+            Composed::MutableString(s) => {
+                let text = Any::Primitive(DataType::String(Cow::Owned(s.borrow().clone())));
+                return uncons(cx, &text);
+            },
+            Composed::Sequence(Sequence::Lines(port)) => {
+                let Some(line) = port.read_line()? else { return Ok(None); };
+                return Ok(Some((Any::Primitive(DataType::String(Cow::Owned(line))), sequence.clone())));
+            },
+            Composed::Sequence(Sequence::Stream(stream)) => stream,
+            other => return Err(NativeFnError::UnexpectedType {
+                function: "stream-rest",
+                argument_position: 1,
+                got: other.variant_name(),
+                expected: "sequence"
+            }.into())
+        },
+        other => return Err(NativeFnError::UnexpectedType {
+            function: "stream-rest",
+            argument_position: 1,
+            got: other.variant_name(),
+            expected: "sequence"
+        }.into())
+    };
+
+    match stream {
+        Stream::Empty => Ok(None),
+        Stream::Cons { first, rest } => Ok(Some((first.force(cx)?, rest.force(cx)?))),
+        Stream::Map { procedure, source } => {
+            let Some((first, rest)) = uncons(cx, source)? else { return Ok(None); };
+            let first = cx.apply(procedure, &[AnyEval::from_any(first)])?;
+
+            Ok(Some((first, stream_value(Stream::Map { procedure: procedure.clone(), source: rest }))))
+        },
+        Stream::Filter { procedure, source } => {
+            let mut source = source.clone();
+
+            while let Some((first, rest)) = uncons(cx, &source)? {
+                let keep = cx.apply(procedure, &[AnyEval::from_any(first.clone())])?;
+
+                if !matches!(keep, Any::Primitive(DataType::Boolean(false))) {
+                    return Ok(Some((first, stream_value(Stream::Filter { procedure: procedure.clone(), source: rest }))));
+                }
+
+                source = rest;
+            }
+
+            Ok(None)
+        },
+        Stream::Take { count: 0, .. } => Ok(None),
+        Stream::Take { count, source } => {
+            let Some((first, rest)) = uncons(cx, source)? else { return Ok(None); };
+            Ok(Some((first, stream_value(Stream::Take { count: count - 1, source: rest }))))
+        }
+    }
+}
+
+fn integer<'a>(
+    cx: &mut Context<'_, 'a>,
+    arg: &AnyEval<'a>,
+    fn_name: &'static str,
+    position: u8
+) -> Result<i32, InterpreterError> {
+    let value = cx.level_down().eval(arg)?;
+
+    value.get_primitive()
+        .and_then(|p| p.get_integer())
+        .copied()
+        .ok_or(NativeFnError::UnexpectedType {
+            function: fn_name,
+            argument_position: position,
+            got: value.variant_name(),
+            expected: "integer"
+        }.into())
+}
+
+fn sequence_value<'a>(sequence: Sequence<'a>) -> Any<'a> {
+    Any::Composed(Box::new(Composed::Sequence(sequence)))
+}
+
+fn real<'a>(
+    cx: &mut Context<'_, 'a>,
+    arg: &AnyEval<'a>,
+    fn_name: &'static str,
+    position: u8
+) -> Result<DataType<'a>, InterpreterError> {
+    let value = cx.level_down().eval(arg)?;
+
+    match value {
+        Any::Primitive(n @ (DataType::Integer(_) | DataType::Floating(_) | DataType::Double(_))) => Ok(n),
+        other => Err(NativeFnError::UnexpectedType {
+            function: fn_name,
+            argument_position: position,
+            got: other.variant_name(),
+            expected: "real number"
+        }.into())
+    }
+}
+
+fn as_f64(n: &DataType<'_>) -> f64 {
+    match n {
+        DataType::Integer(i) => *i as f64,
+        DataType::Floating(f) => *f as f64,
+        DataType::Double(d) => *d,
+        _ => unreachable!()
+    }
+}
+
+/// `(in-range end)`, `(in-range start end)` or `(in-range start end step)`, the items are
+/// integers only if every bound is.
+pub fn in_range<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(at_least 1, args);
+
+    if args.len() > 3 {
+        return Err(NativeFnError::ArityMismatch { expected: 3, got: args.len() as _ }.into());
+    }
+
+    let bounds = args.iter()
+        .enumerate()
+        .map(|(idx, arg)| real(cx, arg, "in-range", idx as u8 + 1))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let (start, end, step) = match bounds.as_slice() {
+        [end] => (DataType::Integer(0), end.clone(), DataType::Integer(1)),
+        [start, end] => (start.clone(), end.clone(), DataType::Integer(1)),
+        [start, end, step] => (start.clone(), end.clone(), step.clone()),
+        _ => unreachable!()
+    };
+
+    if as_f64(&step) == 0.0 {
+        return Err(InterpreterError::Runtime("in-range: step cannot be zero".to_string()));
+    }
+
+    Ok(sequence_value(match (start, end, step) {
+        (DataType::Integer(start), DataType::Integer(end), DataType::Integer(step))
+            => Sequence::Range { start, end: Some(end), step },
+        (start, end, step) => Sequence::RealRange { start: as_f64(&start), end: as_f64(&end), step: as_f64(&step) }
+    }))
+}
+
+/// `(in-naturals [start])`, the infinite sequence of integers starting at `start`.
+pub fn in_naturals<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    let start = match args {
+        [] => 0,
+        [start] => integer(cx, start, "in-naturals", 1)?,
+        _ => return Err(NativeFnError::ArityMismatch { expected: 1, got: args.len() as _ }.into())
+    };
+
+    if start < 0 {
+        return Err(NativeFnError::UnexpectedType {
+            function: "in-naturals",
+            argument_position: 1,
+            got: "integer",
+            expected: "non negative integer"
+        }.into());
+    }
+
+    Ok(sequence_value(Sequence::Range { start, end: None, step: 1 }))
+}
+
+/// Evaluates the single argument of an `in-*` function, checking it is of the expected kind.
+fn in_kind<'a>(
+    cx: &mut Context<'_, 'a>,
+    args: &[AnyEval<'a>],
+    fn_name: &'static str,
+    expected: &'static str,
+    is_kind: fn(&Any<'a>) -> bool
+) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(exact 1, args);
+
+    let value = cx.level_down().eval(&args[0])?;
+
+    if !is_kind(&value) {
+        return Err(NativeFnError::UnexpectedType {
+            function: fn_name,
+            argument_position: 1,
+            got: value.variant_name(),
+            expected
+        }.into());
+    }
+
+    Ok(value)
+}
+
+pub fn in_list<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    in_kind(cx, args, "in-list", "list", |v| v.list_items().is_some())
+}
+
+pub fn in_vector<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    in_kind(cx, args, "in-vector", "vector", |v| v.get_composed().map(|c| c.is_vector()).unwrap_or(false))
+}
+
+pub fn in_string<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    in_kind(cx, args, "in-string", "string", |v| v.get_primitive().map(|p| p.is_string()).unwrap_or(false))
+}
+
+/// `(in-hash hash)`, producing every entry as a key/value pair, which binding clauses like
+/// `[(key value) (in-hash h)]` destructure.
+pub fn in_hash<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    in_kind(cx, args, "in-hash", "hash", |v| v.get_composed().map(|c| c.is_hash()).unwrap_or(false))
+}
+
+/// `(in-lines)`, the lines read from the standard input, without their line terminators.
+/// `(in-lines [port])`, the port defaults to the current input port.
+pub fn in_lines<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(at_most 1, args);
+
+    let port = input_port(cx, args, "in-lines", 0)?;
+    Ok(sequence_value(Sequence::Lines(port)))
+}
+
+pub fn is_sequence_native<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(exact 1, args);
+
+    let value = cx.level_down().eval(&args[0])?;
+    Ok(Any::Primitive(DataType::Boolean(is_sequence(&value))))
+}
+
+pub fn sequence_to_list<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(exact 1, args);
+
+    let value = cx.level_down().eval(&args[0])?;
+    collect_list(cx, value, "sequence->list")
+}
+
+pub fn stream_to_list<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(exact 1, args);
+
+    let value = cx.level_down().eval(&args[0])?;
+    require_stream(&value, "stream->list", 1)?;
+    collect_list(cx, value, "stream->list")
+}
+
+fn collect_list<'a>(
+    cx: &mut Context<'_, 'a>,
+    value: Any<'a>,
+    fn_name: &'static str
+) -> Result<Any<'a>, InterpreterError> {
+    let mut sequence = into_sequence(value, fn_name, 1)?;
+    let mut items = LinkedList::new();
+
+    while let Some(item) = sequence.next(cx)? {
+        items.push_back(item);
+    }
+
+    Ok(Any::Composed(Box::new(Composed::List(List(items)))))
+}
+
+/// `(stream-cons first rest)`, neither expression is evaluated until the stream is accessed.
+pub fn stream_cons<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(exact 2, args);
+
+    Ok(stream_value(Stream::Cons {
+        first: Promise::delay(cx, args[0].clone()),
+        rest: Promise::delay(cx, args[1].clone())
+    }))
+}
+
+/// `(stream item ...)`, a stream of already evaluated items.
+pub fn stream<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    let mut out = stream_value(Stream::Empty);
+
+    for arg in args.iter().rev() {
+        out = stream_value(Stream::Cons {
+            first: Promise::forced(cx.level_down().eval(arg)?),
+            rest: Promise::forced(out)
+        });
+    }
+
+    Ok(out)
+}
+
+/// Whether the value is a stream, which lists, ranges and the values made by the `stream`
+/// functions are.
+pub fn is_stream_value(value: &Any<'_>) -> bool {
+    value.list_items().is_some() || matches!(
+        value.get_composed().map(Box::as_ref),
+        Some(Composed::Sequence(Sequence::Stream(_) | Sequence::Range { .. } | Sequence::RealRange { .. }))
+    )
+}
+
+pub fn require_stream(value: &Any<'_>, fn_name: &'static str, position: u8) -> Result<(), InterpreterError> {
+    if is_stream_value(value) {
+        Ok(())
+    } else {
+        Err(NativeFnError::UnexpectedType {
+            function: fn_name,
+            argument_position: position,
+            got: value.variant_name(),
+            expected: "stream"
+        }.into())
+    }
+}
+
+pub fn is_stream<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(exact 1, args);
+
+    let value = cx.level_down().eval(&args[0])?;
+    Ok(Any::Primitive(DataType::Boolean(is_stream_value(&value))))
+}
+
+fn require_stream_item<'a>(
+    cx: &mut Context<'_, 'a>,
+    args: &[AnyEval<'a>],
+    fn_name: &'static str
+) -> Result<(Any<'a>, Any<'a>), InterpreterError> {
+    require_arity!(exact 1, args);
+
+    let value = cx.level_down().eval(&args[0])?;
+    require_sequence(&value, fn_name, 1)?;
+
+    uncons(cx, &value)?
+        .ok_or(InterpreterError::Runtime(format!("{fn_name}: the stream is empty")))
+}
+
+pub fn is_stream_empty<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(exact 1, args);
+
+    let value = cx.level_down().eval(&args[0])?;
+    require_sequence(&value, "stream-empty?", 1)?;
+
+    Ok(Any::Primitive(DataType::Boolean(uncons(cx, &value)?.is_none())))
+}
+
+pub fn stream_first<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    Ok(require_stream_item(cx, args, "stream-first")?.0)
+}
+
+pub fn stream_rest<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    Ok(require_stream_item(cx, args, "stream-rest")?.1)
+}
+
+/// `(stream-take stream count)`, a stream of the first `count` items of the given sequence.
+pub fn stream_take<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(exact 2, args);
+
+    let source = cx.level_down().eval(&args[0])?;
+    require_sequence(&source, "stream-take", 1)?;
+    let count = non_negative_int(cx, &args[1], "stream-take", 2)?;
+
+    Ok(stream_value(Stream::Take { count, source }))
+}
+
+/// Lazily maps the procedure over the source stream.
+pub fn map_stream<'a>(
+    procedure: Any<'a>,
+    source: Any<'a>,
+    fn_name: &'static str,
+    position: u8
+) -> Result<Any<'a>, InterpreterError> {
+    require_stream(&source, fn_name, position)?;
+    Ok(stream_value(Stream::Map { procedure, source }))
+}
+
+/// Lazily keeps the items of the source stream the predicate holds for.
+pub fn filter_stream<'a>(
+    procedure: Any<'a>,
+    source: Any<'a>,
+    fn_name: &'static str,
+    position: u8
+) -> Result<Any<'a>, InterpreterError> {
+    require_stream(&source, fn_name, position)?;
+    Ok(stream_value(Stream::Filter { procedure, source }))
+}
+
+pub fn stream_map<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(exact 2, args);
+
+    let procedure = cx.level_down().eval(&args[0])?;
+    let source = cx.level_down().eval(&args[1])?;
+    map_stream(procedure, source, "stream-map", 2)
+}
+
+pub fn stream_filter<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(exact 2, args);
+
+    let procedure = cx.level_down().eval(&args[0])?;
+    let source = cx.level_down().eval(&args[1])?;
+    filter_stream(procedure, source, "stream-filter", 2)
+}
+
+#[test]
+fn test_lazy_sequences() {
+    use crate::interpreter::eval_source;
+
+    assert_eq!(eval_source("(sequence->list (stream-take (stream-map (lambda (x) (* x x)) (in-naturals)) 4))").unwrap(), "'(0 1 4 9)");
+    assert_eq!(
+        eval_source("(define (from n) (stream-cons n (from (+ 1 n))))\n(stream-first (stream-rest (stream-filter (lambda (x) (> x 10)) (from 0))))").unwrap(),
+        "12"
+    );
+    assert_eq!(eval_source("(for/list ([x (stream-take (filter (lambda (x) (> x 2)) (in-naturals)) 2)]) x)").unwrap(), "'(3 4)");
+    assert_eq!(eval_source("(sequence->list (in-vector (vector 1 2)))").unwrap(), "'(1 2)");
+    assert_eq!(eval_source("(stream->list (stream-map (lambda (x) (+ x 1)) (stream-cons 1 (stream 2))))").unwrap(), "'(2 3)");
+    assert_eq!(eval_source("(list (stream-empty? empty-stream) (stream->list empty-stream))").unwrap(), "'(#t ())");
+
+    assert!(eval_source("(map (lambda (x) x) 5)").is_err());
+    assert!(eval_source("(filter (lambda (x) #t) \"abc\")").is_err());
+    assert!(eval_source("(stream->list (vector 1))").is_err());
+}
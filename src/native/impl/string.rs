@@ -1,8 +1,11 @@
-use std::{borrow::Cow, collections::LinkedList};
-use crate::{interpreter::{any::AnyEval, context::Context, error::InterpreterError}, primitives::{any::Any, composed::{Composed, List}}};
+use std::{borrow::Cow, cell::RefCell, cmp::Ordering, collections::LinkedList, rc::Rc};
+use crate::{interpreter::{any::AnyEval, context::Context, error::InterpreterError}, primitives::{any::Any, composed::{Composed, List, Symbol}}};
+use crate::interpreter::Interpreter;
 use crate::macros::require_arity;
 use crate::native::error::NativeFnError;
-use crate::native::r#impl::util::non_negative_int;
+use crate::native::r#impl::character::require_character;
+use crate::native::r#impl::format::{display_string, print_string, write_string};
+use crate::native::r#impl::util::{non_negative_int, KeywordArgs};
 use crate::primitives::DataType;
 
 pub fn require_string<'a>(
@@ -14,6 +17,11 @@ pub fn require_string<'a>(
 {
     match cx.eval(arg)? {
         Any::Primitive(DataType::String(s)) => Ok(s),
+        Any::Composed(c) if c.is_mutablestring() => {
+            let Composed::MutableString(s) = *c else { unreachable!() };
+            let text = s.borrow().clone();
+            Ok(Cow::Owned(text))
+        },
         other => Err(NativeFnError::UnexpectedType {
             function: fn_name,
             argument_position: position,
@@ -23,17 +31,32 @@ pub fn require_string<'a>(
     }
 }
 
+/// Text of either kind of string.
+pub fn string_contents<'b>(value: &'b Any<'_>) -> Option<Cow<'b, str>> {
+    match value {
+        Any::Primitive(DataType::String(s)) => Some(Cow::Borrowed(s.as_ref())),
+        Any::Composed(c) => c.get_mutablestring().map(|s| Cow::Owned(s.borrow().clone())),
+        _ => None
+    }
+}
+
+fn mutable_string<'a>(s: String) -> Any<'a> {
+    Any::Composed(Box::new(Composed::MutableString(Rc::new(RefCell::new(s)))))
+}
+
+fn string_value<'a>(s: String) -> Any<'a> {
+    Any::Primitive(DataType::String(Cow::Owned(s)))
+}
+
+fn boolean<'a>(value: bool) -> Any<'a> {
+    Any::Primitive(DataType::Boolean(value))
+}
+
 pub fn is_string<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
     require_arity!(exact 1, args);
 
-    let string = cx.eval(&args[0])?
-        .get_primitive()
-        .map(|p| p.get_string())
-        .flatten()
-        .map(|_| true)
-        .unwrap_or(false);
-
-    Ok(Any::Primitive(DataType::Boolean(string)))
+    let value = cx.eval(&args[0])?;
+    Ok(Any::Primitive(DataType::Boolean(string_contents(&value).is_some())))
 }
 
 pub fn string_append<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
@@ -48,36 +71,35 @@ pub fn string_append<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Resu
     Ok(Any::Primitive(DataType::String(Cow::Owned(first))))
 }
 
-pub fn make_string<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
-    require_arity!(exact 2, args);
+/// `(string char ...)`.
+pub fn string<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    let string = args.iter()
+        .enumerate()
+        .map(|(idx, arg)| require_character(cx, arg, "string", idx as u8 + 1))
+        .collect::<Result<String, _>>()?;
 
-    let times = non_negative_int(cx, &args[0], "make-string", 1)?;
+    Ok(mutable_string(string))
+}
 
-    let character = args[1]
-        .get_primitive()
-        .map(|p| p.get_character())
-        .flatten()
-        .ok_or(NativeFnError::UnexpectedType {
-            function: "make-string",
-            argument_position: 1,
-            got: args[1].variant_name(),
-            expected: "character"
-        })?;
-    
-    let mut result = String::with_capacity(times as usize);
+/// `(make-string length [char])`, the character defaults to a space.
+pub fn make_string<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(at_least 1, args);
+    require_arity!(at_most 2, args);
 
-    for _ in 0..times {
-        result.push_str(&*character);
-    }
+    let times = non_negative_int(cx, &args[0], "make-string", 1)?;
+    let character = match args.get(1) {
+        Some(c) => require_character(cx, c, "make-string", 2)?,
+        None => ' '
+    };
 
-    Ok(Any::Primitive(DataType::String(Cow::Owned(result))))
+    Ok(mutable_string(std::iter::repeat_n(character, times).collect()))
 }
 
 pub fn len<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
     require_arity!(exact 1, args);
 
     Ok(Any::Primitive(DataType::Integer(
-        require_string(cx, &args[0], "string-length", 1)?.len() as i32
+        require_string(cx, &args[0], "string-length", 1)?.chars().count() as i32
     )))
 }
 
@@ -86,8 +108,11 @@ pub fn string_ref<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<
 
     let string = require_string(cx, &args[0], "string-ref", 1)?;
     let index = non_negative_int(cx, &args[1], "string-ref", 2)?;
+    let character = string.chars()
+        .nth(index)
+        .ok_or(InterpreterError::OutOfBounds { length: string.chars().count(), got: index })?;
 
-    Ok(Any::Primitive(DataType::Character(Cow::Owned(string[index..index+1].to_string()))))
+    Ok(Any::Primitive(DataType::Character(character)))
 }
 
 pub fn substring<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
@@ -113,11 +138,14 @@ pub fn substring<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<A
         }
     }
 
-    let res = if let Some(end) = end {
-        string[start..end].to_string()
-    } else {
-        string[start..].to_string()
-    };
+    let length = string.chars().count();
+    let end = end.unwrap_or(length);
+
+    if end > length {
+        return Err(InterpreterError::OutOfBounds { length, got: end });
+    }
+
+    let res = string.chars().skip(start).take(end - start).collect::<String>();
 
     Ok(Any::Primitive(DataType::String(Cow::Owned(res))))
 }
@@ -128,13 +156,601 @@ pub fn string_to_list<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Res
 
     let mut result = LinkedList::new();
 
-    for i in string.chars() {
-        result.push_back(
-            Any::Primitive(DataType::Character(
-                DataType::parse_character(&i.to_string())
-            ).make_static()
-        ));
+    for c in string.chars() {
+        result.push_back(Any::Primitive(DataType::Character(c)));
     }
 
     Ok(Any::Composed(Box::new(Composed::List(List(result)))))
 }
+
+fn map_string<'a>(
+    cx: &mut Context<'_, 'a>,
+    args: &[AnyEval<'a>],
+    fn_name: &'static str,
+    map: fn(&str) -> String
+) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(exact 1, args);
+
+    let string = require_string(cx, &args[0], fn_name, 1)?;
+    Ok(string_value(map(&string)))
+}
+
+pub fn string_upcase<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    map_string(cx, args, "string-upcase", str::to_uppercase)
+}
+
+pub fn string_downcase<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    map_string(cx, args, "string-downcase", str::to_lowercase)
+}
+
+/// Whether the value of the keyword is anything but `#f`, or `default` if it wasn't given.
+fn keyword_flag<'a>(
+    cx: &mut Context<'_, 'a>,
+    args: &KeywordArgs<'a, '_>,
+    keyword: &str,
+    default: bool
+) -> Result<bool, InterpreterError> {
+    match args.get(keyword) {
+        Some(value) => Ok(!matches!(cx.eval(value)?, Any::Primitive(DataType::Boolean(false)))),
+        None => Ok(default)
+    }
+}
+
+fn keyword_string<'a>(
+    cx: &mut Context<'_, 'a>,
+    args: &KeywordArgs<'a, '_>,
+    keyword: &str,
+    fn_name: &'static str,
+    default: &str
+) -> Result<String, InterpreterError> {
+    match args.get(keyword) {
+        Some(value) => Ok(require_string(cx, value, fn_name, args.positional.len() as u8 + 1)?.into_owned()),
+        None => Ok(default.to_string())
+    }
+}
+
+fn keyword_int<'a>(
+    cx: &mut Context<'_, 'a>,
+    args: &KeywordArgs<'a, '_>,
+    keyword: &str,
+    fn_name: &'static str
+) -> Result<Option<usize>, InterpreterError> {
+    args.get(keyword)
+        .map(|value| non_negative_int(cx, value, fn_name, args.positional.len() as u8 + 1))
+        .transpose()
+}
+
+/// Optional separator at `position`, `None` meaning whitespace.
+fn separator<'a>(
+    cx: &mut Context<'_, 'a>,
+    args: &KeywordArgs<'a, '_>,
+    position: usize,
+    fn_name: &'static str
+) -> Result<Option<Cow<'a, str>>, InterpreterError> {
+    args.positional.get(position)
+        .map(|sep| require_string(cx, sep, fn_name, position as u8 + 1))
+        .transpose()
+}
+
+/// Removes the separator from the ends of the text, every repetition of it if `repeat`, or any
+/// whitespace if there's no separator.
+fn trim_ends<'b>(text: &'b str, sep: Option<&str>, left: bool, right: bool, repeat: bool) -> &'b str {
+    let mut text = text;
+
+    match sep {
+        None => {
+            if left {
+                text = text.trim_start();
+            }
+
+            if right {
+                text = text.trim_end();
+            }
+        },
+        Some("") => (),
+        Some(sep) => {
+            while let Some(rest) = text.strip_prefix(sep).filter(|_| left) {
+                text = rest;
+
+                if !repeat {
+                    break;
+                }
+            }
+
+            while let Some(rest) = text.strip_suffix(sep).filter(|_| right) {
+                text = rest;
+
+                if !repeat {
+                    break;
+                }
+            }
+        }
+    }
+
+    text
+}
+
+/// `(string-trim text [sep #:left? #:right? #:repeat?])`.
+pub fn string_trim<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    let args = KeywordArgs::parse(args, "string-trim", &["#:left?", "#:right?", "#:repeat?"])?;
+    require_arity!(at_least 1, args.positional);
+    require_arity!(at_most 2, args.positional);
+
+    let text = require_string(cx, args.positional[0], "string-trim", 1)?;
+    let sep = separator(cx, &args, 1, "string-trim")?;
+    let left = keyword_flag(cx, &args, "#:left?", true)?;
+    let right = keyword_flag(cx, &args, "#:right?", true)?;
+    let repeat = keyword_flag(cx, &args, "#:repeat?", false)?;
+
+    Ok(string_value(trim_ends(&text, sep.as_deref(), left, right, repeat).to_string()))
+}
+
+/// `(string-split text [sep #:trim? #:repeat?])`, splitting on whitespace by default.
+pub fn string_split<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    let args = KeywordArgs::parse(args, "string-split", &["#:trim?", "#:repeat?"])?;
+    require_arity!(at_least 1, args.positional);
+    require_arity!(at_most 2, args.positional);
+
+    let text = require_string(cx, args.positional[0], "string-split", 1)?;
+    let sep = separator(cx, &args, 1, "string-split")?;
+    let trim = keyword_flag(cx, &args, "#:trim?", true)?;
+    let repeat = keyword_flag(cx, &args, "#:repeat?", false)? || sep.is_none();
+
+    let text = match trim {
+        true => trim_ends(&text, sep.as_deref(), true, true, repeat),
+        false => &text
+    };
+
+    if text.is_empty() {
+        return Ok(Any::Composed(Box::new(Composed::List(List(LinkedList::new())))));
+    }
+
+    let pieces = match sep.as_deref() {
+        Some("") => vec![text],
+        Some(sep) => text.split(sep).collect::<Vec<_>>(),
+        None => text.split(char::is_whitespace).collect()
+    };
+
+    // repeated separators leave empty pieces between them, but the ones at the ends are kept
+    let last = pieces.len() - 1;
+    let items = pieces.into_iter()
+        .enumerate()
+        .filter(|(idx, piece)| !(repeat && piece.is_empty() && *idx != 0 && *idx != last))
+        .map(|(_, piece)| string_value(piece.to_string()))
+        .collect();
+
+    Ok(Any::Composed(Box::new(Composed::List(List(items)))))
+}
+
+/// `(string-join strings [sep])`, the separator defaults to a space.
+pub fn string_join<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(at_least 1, args);
+    require_arity!(at_most 2, args);
+
+    let value = cx.eval(&args[0])?;
+    let unexpected = |got| NativeFnError::UnexpectedType {
+        function: "string-join",
+        argument_position: 1,
+        got,
+        expected: "list of strings"
+    };
+
+    let strings = value.list_items()
+        .ok_or(unexpected(value.variant_name()))?
+        .iter()
+        .map(|item| string_contents(item).map(Cow::into_owned).ok_or(unexpected(item.variant_name())))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let sep = match args.get(1) {
+        Some(sep) => require_string(cx, sep, "string-join", 2)?,
+        None => Cow::Borrowed(" ")
+    };
+
+    Ok(string_value(strings.join(&sep)))
+}
+
+/// `(string-replace text from to [#:all? all])`, replaces every match unless `all` is `#f`.
+pub fn string_replace<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    let args = KeywordArgs::parse(args, "string-replace", &["#:all?"])?;
+    require_arity!(exact 3, args.positional);
+
+    let text = require_string(cx, args.positional[0], "string-replace", 1)?;
+    let from = require_string(cx, args.positional[1], "string-replace", 2)?;
+    let to = require_string(cx, args.positional[2], "string-replace", 3)?;
+
+    let replaced = match keyword_flag(cx, &args, "#:all?", true)? {
+        true => text.replace(from.as_ref(), &to),
+        false => text.replacen(from.as_ref(), &to, 1)
+    };
+
+    Ok(string_value(replaced))
+}
+
+fn string_test<'a>(
+    cx: &mut Context<'_, 'a>,
+    args: &[AnyEval<'a>],
+    fn_name: &'static str,
+    test: fn(&str, &str) -> bool
+) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(exact 2, args);
+
+    let text = require_string(cx, &args[0], fn_name, 1)?;
+    let other = require_string(cx, &args[1], fn_name, 2)?;
+
+    Ok(boolean(test(&text, &other)))
+}
+
+pub fn string_contains<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    string_test(cx, args, "string-contains?", |text, contained| text.contains(contained))
+}
+
+pub fn string_prefix<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    string_test(cx, args, "string-prefix?", |text, prefix| text.starts_with(prefix))
+}
+
+pub fn string_suffix<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    string_test(cx, args, "string-suffix?", |text, suffix| text.ends_with(suffix))
+}
+
+/// Whether every pair of adjacent strings compares the way `accept` wants.
+fn compare_strings<'a>(
+    cx: &mut Context<'_, 'a>,
+    args: &[AnyEval<'a>],
+    fn_name: &'static str,
+    fold_case: bool,
+    accept: fn(Ordering) -> bool
+) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(at_least 1, args);
+
+    let strings = args.iter()
+        .enumerate()
+        .map(|(idx, arg)| require_string(cx, arg, fn_name, idx as u8 + 1))
+        .map(|s| s.map(|s| if fold_case { s.to_lowercase() } else { s.into_owned() }))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(boolean(strings.windows(2).all(|pair| accept(pair[0].cmp(&pair[1])))))
+}
+
+macro_rules! string_comparisons {
+    ($($name: ident, $fn_name: literal, $fold_case: literal => $accept: expr),* $(,)?) => {$(
+        pub fn $name<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+            compare_strings(cx, args, $fn_name, $fold_case, $accept)
+        }
+    )*};
+}
+
+string_comparisons! {
+    string_eq, "string=?", false => Ordering::is_eq,
+    string_lt, "string<?", false => Ordering::is_lt,
+    string_gt, "string>?", false => Ordering::is_gt,
+    string_le, "string<=?", false => Ordering::is_le,
+    string_ge, "string>=?", false => Ordering::is_ge,
+    string_ci_eq, "string-ci=?", true => Ordering::is_eq,
+    string_ci_lt, "string-ci<?", true => Ordering::is_lt,
+    string_ci_gt, "string-ci>?", true => Ordering::is_gt,
+    string_ci_le, "string-ci<=?", true => Ordering::is_le,
+    string_ci_ge, "string-ci>=?", true => Ordering::is_ge,
+}
+
+fn radix<'a>(
+    cx: &mut Context<'_, 'a>,
+    args: &[AnyEval<'a>],
+    fn_name: &'static str
+) -> Result<u32, InterpreterError> {
+    let Some(arg) = args.get(1) else { return Ok(10); };
+
+    match non_negative_int(cx, arg, fn_name, 2)? {
+        radix @ (2 | 8 | 10 | 16) => Ok(radix as u32),
+        _ => Err(NativeFnError::UnexpectedType {
+            function: fn_name,
+            argument_position: 2,
+            got: arg.variant_name(),
+            expected: "(or/c 2 8 10 16)"
+        }.into())
+    }
+}
+
+/// Parses the whole text as a number, unlike the lexer which stops at the first invalid character.
+fn parse_number(text: &str, radix: u32) -> Option<DataType<'static>> {
+    if radix != 10 {
+        return i32::from_str_radix(text, radix).ok().map(DataType::Integer);
+    }
+
+    if !text.chars().all(|c| c.is_ascii_digit() || "+-./eE".contains(c)) {
+        return None;
+    }
+
+    if let Ok(integer) = text.parse::<i32>() {
+        return Some(DataType::Integer(integer));
+    }
+
+    if let Some((left, right)) = text.split_once('/') {
+        let right = right.parse::<i32>().ok().filter(|r| *r != 0)?;
+        return Some(DataType::Rational(crate::primitives::Rational { left: left.parse().ok()?, right }));
+    }
+
+    if text.contains(['e', 'E']) {
+        return text.parse::<f64>().ok().map(DataType::Double);
+    }
+
+    text.parse::<f32>().ok().map(DataType::Floating)
+}
+
+/// `(string->number text [radix])`, returns `#f` if the text isn't a number.
+pub fn string_to_number<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(at_least 1, args);
+    require_arity!(at_most 2, args);
+
+    let text = require_string(cx, &args[0], "string->number", 1)?;
+    let radix = radix(cx, args, "string->number")?;
+
+    Ok(Any::Primitive(parse_number(&text, radix).unwrap_or(DataType::Boolean(false))))
+}
+
+/// Value of the real numbers, the ones `~r` formats.
+fn real_value(value: &DataType<'_>) -> Option<f64> {
+    Some(match value {
+        DataType::Integer(i) => *i as f64,
+        DataType::Floating(f) => *f as f64,
+        DataType::Double(d) => *d,
+        DataType::Rational(r) => r.left as f64 / r.right as f64,
+        _ => return None
+    })
+}
+
+fn integer_in_radix(integer: i32, radix: u32) -> String {
+    let sign = if integer < 0 { "-" } else { "" };
+    let digits = integer.unsigned_abs();
+
+    match radix {
+        2 => format!("{sign}{digits:b}"),
+        8 => format!("{sign}{digits:o}"),
+        16 => format!("{sign}{digits:x}"),
+        _ => integer.to_string()
+    }
+}
+
+/// `(number->string number [radix])`, only integers can use a radix other than 10.
+pub fn number_to_string<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(at_least 1, args);
+    require_arity!(at_most 2, args);
+
+    let value = cx.eval(&args[0])?;
+    let radix = radix(cx, args, "number->string")?;
+
+    let text = match &value {
+        Any::Primitive(DataType::Integer(i)) => integer_in_radix(*i, radix),
+        Any::Primitive(p) if radix == 10 && real_value(p).is_some() => display_string(&value, cx.interpreter()),
+        other => return Err(NativeFnError::UnexpectedType {
+            function: "number->string",
+            argument_position: 1,
+            got: other.variant_name(),
+            expected: if radix == 10 { "number" } else { "exact integer" }
+        }.into())
+    };
+
+    Ok(string_value(text))
+}
+
+pub fn string_copy<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(exact 1, args);
+
+    let string = require_string(cx, &args[0], "string-copy", 1)?;
+    Ok(mutable_string(string.into_owned()))
+}
+
+/// `(string-set! string index char)`, only strings made by `string`, `make-string` or
+/// `string-copy` can be changed.
+pub fn string_set<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(exact 3, args);
+
+    let value = cx.eval(&args[0])?;
+    let target = value.get_composed()
+        .and_then(|c| c.get_mutablestring())
+        .ok_or(NativeFnError::UnexpectedType {
+            function: "string-set!",
+            argument_position: 1,
+            got: value.variant_name(),
+            expected: "mutable string"
+        })?;
+
+    let index = non_negative_int(cx, &args[1], "string-set!", 2)?;
+    let character = require_character(cx, &args[2], "string-set!", 3)?;
+
+    let mut target = target.borrow_mut();
+    let (start, old) = target.char_indices()
+        .nth(index)
+        .ok_or(InterpreterError::OutOfBounds { length: target.chars().count(), got: index })?;
+
+    target.replace_range(start..start + old.len_utf8(), character.encode_utf8(&mut [0; 4]));
+    Ok(Any::Void(()))
+}
+
+pub fn string_to_symbol<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(exact 1, args);
+
+    let name = require_string(cx, &args[0], "string->symbol", 1)?;
+    Ok(Any::Composed(Box::new(Composed::Symbol(Symbol(&name)))).make_static())
+}
+
+pub fn is_symbol<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(exact 1, args);
+
+    let value = cx.eval(&args[0])?;
+    Ok(Any::Primitive(DataType::Boolean(value.get_symbol().is_some())))
+}
+
+pub fn symbol_to_string<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(exact 1, args);
+
+    let value = cx.eval(&args[0])?;
+    let name = value.get_symbol()
+        .ok_or(NativeFnError::UnexpectedType {
+            function: "symbol->string",
+            argument_position: 1,
+            got: value.variant_name(),
+            expected: "symbol"
+        })?;
+
+    Ok(string_value(name.to_string()))
+}
+
+/// Pads the text up to `width` characters by repeating `pad` on the sides `align` leaves free.
+fn pad(text: String, width: usize, align: &str, pad: &str) -> String {
+    let length = text.chars().count();
+
+    if length >= width || pad.is_empty() {
+        return text;
+    }
+
+    let missing = width - length;
+    let (left, right) = match align {
+        "right" => (missing, 0),
+        "center" => (missing / 2, missing - missing / 2),
+        _ => (0, missing)
+    };
+
+    let fill = |count| pad.chars().cycle().take(count).collect::<String>();
+    fill(left) + &text + &fill(right)
+}
+
+/// Renders the values with `render` and fits them to the width keywords, like `~a` does.
+fn tilde_format<'a>(
+    cx: &mut Context<'_, 'a>,
+    args: &[AnyEval<'a>],
+    fn_name: &'static str,
+    render: fn(&Any<'_>, &Interpreter<'_>) -> String
+) -> Result<Any<'a>, InterpreterError> {
+    let args = KeywordArgs::parse(args, fn_name, &[
+        "#:separator", "#:width", "#:min-width", "#:max-width", "#:align", "#:pad-string", "#:limit-marker"
+    ])?;
+
+    let values = args.positional.iter()
+        .map(|arg| cx.eval(arg))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let separator = keyword_string(cx, &args, "#:separator", fn_name, "")?;
+    let width = keyword_int(cx, &args, "#:width", fn_name)?;
+    let min_width = keyword_int(cx, &args, "#:min-width", fn_name)?.or(width).unwrap_or(0);
+    let max_width = keyword_int(cx, &args, "#:max-width", fn_name)?.or(width);
+    let pad_string = keyword_string(cx, &args, "#:pad-string", fn_name, " ")?;
+    let limit_marker = keyword_string(cx, &args, "#:limit-marker", fn_name, "")?;
+
+    let align = match args.get("#:align") {
+        Some(align) => cx.eval(align)?.get_symbol().unwrap_or("left"),
+        None => "left"
+    };
+
+    let mut text = values.iter()
+        .map(|value| render(value, cx.interpreter()))
+        .collect::<Vec<_>>()
+        .join(&separator);
+
+    if let Some(max_width) = max_width.filter(|max| text.chars().count() > *max) {
+        let marker = limit_marker.chars().take(max_width).collect::<String>();
+        text = text.chars().take(max_width - marker.chars().count()).collect::<String>() + &marker;
+    }
+
+    Ok(string_value(pad(text, min_width, align, &pad_string)))
+}
+
+pub fn tilde_a<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    tilde_format(cx, args, "~a", display_string)
+}
+
+pub fn tilde_s<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    tilde_format(cx, args, "~s", write_string)
+}
+
+pub fn tilde_v<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    tilde_format(cx, args, "~v", print_string)
+}
+
+/// `(~r number [#:precision precision #:min-width width #:pad-string pad #:base base])`, the
+/// precision is either the maximum amount of decimals or `'(= decimals)` for an exact amount.
+pub fn tilde_r<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    let args = KeywordArgs::parse(args, "~r", &["#:precision", "#:min-width", "#:pad-string", "#:base"])?;
+    require_arity!(exact 1, args.positional);
+
+    let value = cx.eval(args.positional[0])?;
+    let number = value.get_primitive()
+        .and_then(real_value)
+        .ok_or(NativeFnError::UnexpectedType {
+            function: "~r",
+            argument_position: 1,
+            got: value.variant_name(),
+            expected: "rational?"
+        })?;
+
+    let (decimals, exact) = match args.get("#:precision") {
+        Some(precision) => {
+            let precision = cx.eval(precision)?;
+            let exact = precision.list_items()
+                .filter(|items| items.len() == 2 && items[0].get_symbol() == Some("="))
+                .map(|items| items[1].clone());
+
+            let decimals = exact.as_ref().unwrap_or(&precision)
+                .get_primitive()
+                .and_then(DataType::get_integer)
+                .filter(|decimals| **decimals >= 0)
+                .ok_or(NativeFnError::UnexpectedType {
+                    function: "~r",
+                    argument_position: 2,
+                    got: precision.variant_name(),
+                    expected: "(or/c exact-nonnegative-integer? (list/c '= exact-nonnegative-integer?))"
+                })?;
+
+            (*decimals as usize, exact.is_some())
+        },
+        None => (6, false)
+    };
+
+    let base = keyword_int(cx, &args, "#:base", "~r")?.unwrap_or(10);
+    let min_width = keyword_int(cx, &args, "#:min-width", "~r")?.unwrap_or(1);
+    let pad_string = keyword_string(cx, &args, "#:pad-string", "~r", " ")?;
+
+    let text = match &value {
+        Any::Primitive(DataType::Integer(i)) if !exact => integer_in_radix(*i, base as u32),
+        _ if base != 10 => return Err(InterpreterError::Runtime(
+            "~r: only exact integers can be formatted in a base other than 10".to_string()
+        )),
+        _ => {
+            let text = format!("{number:.decimals$}");
+
+            match exact || decimals == 0 {
+                true if decimals == 0 => text + ".",
+                true => text,
+                false => text.trim_end_matches('0').to_string()
+            }
+        }
+    };
+
+    Ok(string_value(pad(text, min_width, "right", &pad_string)))
+}
+
+#[test]
+fn test_string_library() {
+    use crate::interpreter::eval_source;
+
+    let source = "(define words (string-split \"  the quick  brown fox \"))
+(define fields (string-split \"a,b,,c\" \",\"))
+(define buffer (make-string 3 #\\a))
+(string-set! buffer 1 #\\b)
+(define copy (string-copy \"abc\"))
+(string-set! copy 0 #\\z)
+(list words fields (string-join words \"-\") (string-trim \"__x__\" \"_\" #:repeat? #t)
+  (string-upcase \"abc\") (string-replace \"a.b.c\" \".\" \"/\" #:all? #f)
+  (string-contains? \"hello\" \"ell\") (string-prefix? \"hello\" \"he\") (string-suffix? \"hello\" \"lo\")
+  (string<? \"apple\" \"banana\" \"cherry\") (string-ci=? \"Hello\" \"hELLO\") (string=? buffer \"aba\")
+  (string->number \"42\") (string->number \"ff\" 16) (string->number \"4x\") (number->string 255 2)
+  copy (string->symbol \"sym\") (~a \"x\" 42 #:min-width 5 #:align 'right #:pad-string \".\")
+  (~r 3.14159 #:precision 2) (~r 2 #:min-width 3 #:pad-string \"0\") (~r 1.5 #:precision '(= 3)))";
+
+    assert_eq!(
+        eval_source(source).unwrap(),
+        "'((\"the\" \"quick\" \"brown\" \"fox\") (\"a\" \"b\" \"\" \"c\") \"the-quick-brown-fox\" \"x\" \
+\"ABC\" \"a/b.c\" #t #t #t #t #t #t 42 255 #f \"11111111\" \"zbc\" sym \"..x42\" \"3.14\" \"002\" \"1.500\")"
+    );
+
+    assert!(eval_source("(string-set! \"literal\" 0 #\\x)").is_err());
+}
@@ -0,0 +1,80 @@
+use std::sync::Arc;
+
+use crate::interpreter::any::AnyEval;
+use crate::interpreter::context::Context;
+use crate::interpreter::error::InterpreterError;
+use crate::macros::require_arity;
+use crate::native::error::NativeFnError;
+use crate::native::r#impl::exn;
+use crate::primitives::any::Any;
+use crate::primitives::composed::Composed;
+use crate::primitives::structs::StructType;
+
+fn ident<'a>(arg: &AnyEval<'a>) -> Result<&'a str, InterpreterError> {
+    arg.get_ident()
+        .copied()
+        .ok_or(NativeFnError::IdentifierExpectedIn {
+            call: "struct",
+            got: arg.variant_name().to_string()
+        }.into())
+}
+
+/// `(struct name [parent] (field ...) option ...)`, defines the constructor `name`, the predicate
+/// `name?`, an accessor `name-field` for every field and the type itself as `struct:name`.
+pub fn r#struct<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(at_least 2, args);
+
+    let name = ident(&args[0])?;
+    let mut rest = args[1..].iter();
+
+    let parent = match &args[1] {
+        AnyEval::Ident(parent) => {
+            rest.next();
+
+            let key = format!("struct:{parent}");
+            let kind = cx.get_var(&key)
+                .and_then(|v| v.get_composed())
+                .and_then(|c| c.get_structtype())
+                .cloned()
+                .or_else(|| exn::builtin_type(parent))
+                .ok_or(InterpreterError::Runtime(format!("struct: parent struct type not defined: {parent}")))?;
+
+            Some(kind)
+        },
+        _ => None
+    };
+
+    let fields = rest.next()
+        .and_then(AnyEval::get_expression)
+        .ok_or(InterpreterError::InvalidExpression)?
+        .as_vec()
+        .iter()
+        .map(ident)
+        .map(|f| f.map(String::from))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut transparent = false;
+
+    for option in rest {
+        match ident(option)? {
+            "#:transparent" => transparent = true,
+            other => return Err(InterpreterError::Runtime(format!("struct: unsupported option {other}")))
+        }
+    }
+
+    let kind = Arc::new(StructType {
+        name: name.to_string(),
+        fields,
+        parent,
+        transparent
+    });
+
+    for procedure in kind.procedures() {
+        let key = procedure.name.clone();
+        cx.vars_mut().insert(&key, Any::Composed(Box::new(Composed::StructProcedure(procedure))));
+    }
+
+    cx.vars_mut().insert(&format!("struct:{name}"), Any::Composed(Box::new(Composed::StructType(kind))));
+
+    Ok(Any::Void(()))
+}
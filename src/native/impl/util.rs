@@ -1,17 +1,23 @@
-use crate::{interpreter::{any::AnyEval, context::Context, error::InterpreterError}, native::{error::NativeFnError, function::NativeFunction}, primitives::{any::Any, composed::{Function, LambdaFunction}}};
+use crate::{interpreter::{any::AnyEval, context::{Context, Procedure}, error::InterpreterError}, native::{error::NativeFnError, function::NativeFunction}, primitives::{any::Any, composed::{Composed, Function, LambdaFunction}, continuation::Continuation, parameter::Parameter, structs::StructProcedure}};
 
 pub enum Callable<'a> {
     Lambda(LambdaFunction<'a>),
     Function(Function<'a>),
-    Native(NativeFunction)
+    Native(NativeFunction),
+    Struct(StructProcedure),
+    Continuation(Continuation),
+    Parameter(Parameter)
 }
 
 impl<'a> Callable<'a> {
     pub fn call(&self, cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
         match self {
-            Self::Lambda(l) => l.body.call(&mut cx.level_down(), args),
-            Self::Function(f) => f.body.call(&mut cx.level_down(), args),
-            Self::Native(n) => n.call(&mut cx.level_down(), args)
+            Self::Lambda(l) => cx.call_procedure(Procedure::lambda(l), args, None),
+            Self::Function(f) => cx.call_procedure(Procedure::function(f), args, None),
+            Self::Native(n) => n.call(&mut cx.level_down(), args),
+            Self::Struct(s) => s.call(args.iter().map(Any::from).collect()),
+            Self::Continuation(k) => Err(k.escape(args.iter().map(Any::from).collect())),
+            Self::Parameter(p) => p.apply(cx, args)
         }
     }
 
@@ -19,6 +25,7 @@ impl<'a> Callable<'a> {
         match self {
             Self::Function(f) => f.arity,
             Self::Lambda(l) => l.arity,
+            Self::Struct(s) => Some(s.arity()),
             _ => None
         }
     }
@@ -32,35 +39,33 @@ pub fn callable_for<'a>(
 ) -> Result<Callable<'a>, InterpreterError>
 where
 {
+    let unexpected = |got| NativeFnError::UnexpectedType {
+        function: fn_name,
+        argument_position: arg_pos,
+        got,
+        expected: "lambda or function"
+    };
+
     match fun {
-        AnyEval::Ident(i) => {
-            if cx.interpreter().is_native(i) {
-                Ok(Callable::Native(*cx.interpreter().native_vars()
-                    .get(i).unwrap()))
-            } else if cx.is_declared_function(i) {
-                Ok(Callable::Function(unsafe {
-                    cx.interpreter().vars().get(i)
-                        .unwrap()
-                        .get_composed_unchecked()
-                        .get_function_unchecked()
-                        .clone()
-                }))
-            } else {
-                return Err(InterpreterError::UndefinedFunction(i.to_string()));
-            }
+        AnyEval::Ident(i) if cx.interpreter().is_native(i) => {
+            Ok(Callable::Native(*cx.interpreter().native_vars()
+                .get(i).unwrap()))
         },
-        AnyEval::Composed(c) if c.is_function() => {
-            Ok(Callable::Function(unsafe { c.get_function_unchecked().clone() }))
-        },
-        AnyEval::Expression(e) => {
-            Ok(Callable::Lambda(e.clone().try_parse_lambda()?))
+        other => match cx.eval(other)? {
+            Any::Composed(c) => match *c {
+                Composed::Function(f) if f.body.body.is_empty() && cx.interpreter().is_native(f.name) => {
+                    Ok(Callable::Native(*cx.interpreter().native_vars()
+                        .get(f.name).unwrap()))
+                },
+                Composed::Function(f) => Ok(Callable::Function(f)),
+                Composed::Lambda(l) => Ok(Callable::Lambda(l)),
+                Composed::StructProcedure(s) => Ok(Callable::Struct(s)),
+                Composed::Continuation(k) => Ok(Callable::Continuation(k)),
+                Composed::Parameter(p) => Ok(Callable::Parameter(p)),
+                c => Err(unexpected(c.variant_name()).into())
+            },
+            value => Err(unexpected(value.variant_name()).into())
         }
-        _ => return Err(NativeFnError::UnexpectedType {
-            function: fn_name,
-            argument_position: arg_pos,
-            got: fun.variant_name(),
-            expected: "lambda or function"
-        }.into())
     }
 }
 
@@ -86,3 +91,49 @@ pub fn non_negative_int<'a>(
             expected: "non negative integer"
         })? as usize)
 }
+
+/// Positional arguments of a call, along with its `#:keyword value` pairs.
+pub struct KeywordArgs<'a, 'b> {
+    pub positional: Vec<&'b AnyEval<'a>>,
+    pub keywords: Vec<(&'a str, &'b AnyEval<'a>)>
+}
+
+impl<'a, 'b> KeywordArgs<'a, 'b> {
+    /// Splits the arguments, failing on keywords not in `allowed` or without a value.
+    pub fn parse(
+        args: &'b [AnyEval<'a>],
+        fn_name: &'static str,
+        allowed: &[&str]
+    ) -> Result<Self, InterpreterError> {
+        let mut positional = Vec::with_capacity(args.len());
+        let mut keywords = Vec::new();
+        let mut iter = args.iter();
+
+        while let Some(arg) = iter.next() {
+            match arg {
+                AnyEval::Ident(keyword) if keyword.starts_with("#:") => {
+                    if !allowed.contains(keyword) {
+                        return Err(InterpreterError::Runtime(format!("{fn_name}: unsupported keyword {keyword}")));
+                    }
+
+                    let value = iter.next().ok_or_else(|| InterpreterError::Runtime(
+                        format!("{fn_name}: missing value for keyword {keyword}")
+                    ))?;
+
+                    keywords.push((*keyword, value));
+                },
+                other => positional.push(other)
+            }
+        }
+
+        Ok(Self { positional, keywords })
+    }
+
+    /// Unevaluated value of the keyword, if it was given.
+    pub fn get(&self, keyword: &str) -> Option<&'b AnyEval<'a>> {
+        self.keywords.iter()
+            .rev()
+            .find(|(k, _)| *k == keyword)
+            .map(|(_, value)| *value)
+    }
+}
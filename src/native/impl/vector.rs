@@ -0,0 +1,106 @@
+use std::collections::LinkedList;
+
+use crate::interpreter::any::AnyEval;
+use crate::interpreter::context::Context;
+use crate::interpreter::error::InterpreterError;
+use crate::macros::require_arity;
+use crate::native::error::NativeFnError;
+use crate::native::r#impl::util::non_negative_int;
+use crate::primitives::any::Any;
+use crate::primitives::composed::{Composed, List, Vector};
+use crate::primitives::DataType;
+
+pub fn require_vector<'a>(
+    cx: &mut Context<'_, 'a>,
+    arg: &AnyEval<'a>,
+    fn_name: &'static str,
+    position: u8,
+) -> Result<Vector<'a>, InterpreterError>
+{
+    match cx.level_down().eval(arg)? {
+        Any::Composed(c) if c.is_vector() => {
+            let Composed::Vector(v) = *c else { unreachable!() };
+            Ok(v)
+        },
+        other => Err(NativeFnError::UnexpectedType {
+            function: fn_name,
+            argument_position: position,
+            got: other.variant_name(),
+            expected: "vector"
+        }.into())
+    }
+}
+
+fn vector_value(items: Vec<Any<'_>>) -> Any<'_> {
+    Any::Composed(Box::new(Composed::Vector(Vector(items))))
+}
+
+pub fn vector<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    let items = args.iter()
+        .map(|arg| cx.level_down().eval(arg))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(vector_value(items))
+}
+
+pub fn make_vector<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(at_least 1, args);
+
+    let size = non_negative_int(cx, &args[0], "make-vector", 1)?;
+    let fill = match args.get(1) {
+        Some(fill) => cx.level_down().eval(fill)?,
+        None => Any::Primitive(DataType::Integer(0))
+    };
+
+    Ok(vector_value(vec![fill; size]))
+}
+
+pub fn is_vector<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(exact 1, args);
+
+    let value = cx.level_down().eval(&args[0])?;
+    Ok(Any::Primitive(DataType::Boolean(value.get_composed().map(|c| c.is_vector()).unwrap_or(false))))
+}
+
+pub fn vector_length<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(exact 1, args);
+    Ok(Any::Primitive(DataType::Integer(require_vector(cx, &args[0], "vector-length", 1)?.0.len() as _)))
+}
+
+pub fn vector_ref<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(exact 2, args);
+
+    let mut vector = require_vector(cx, &args[0], "vector-ref", 1)?;
+    let index = non_negative_int(cx, &args[1], "vector-ref", 2)?;
+
+    if index >= vector.0.len() {
+        return Err(InterpreterError::OutOfBounds { length: vector.0.len(), got: index });
+    }
+
+    Ok(vector.0.swap_remove(index))
+}
+
+pub fn vector_to_list<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(exact 1, args);
+
+    let items = require_vector(cx, &args[0], "vector->list", 1)?.0
+        .into_iter()
+        .collect::<LinkedList<_>>();
+
+    Ok(Any::Composed(Box::new(Composed::List(List(items)))))
+}
+
+pub fn list_to_vector<'a>(cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+    require_arity!(exact 1, args);
+
+    let list = cx.level_down().eval(&args[0])?;
+    let items = list.list_items()
+        .ok_or(NativeFnError::UnexpectedType {
+            function: "list->vector",
+            argument_position: 1,
+            got: list.variant_name(),
+            expected: "list"
+        })?;
+
+    Ok(vector_value(items))
+}
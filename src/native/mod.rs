@@ -2,6 +2,11 @@ pub mod error;
 pub mod function;
 mod r#impl;
 use r#impl::*;
+pub use r#impl::parameter;
+pub use r#impl::common::eval_sequence;
+pub use r#impl::exn::error_value;
+pub use r#impl::hash::hash_insert;
+pub use r#impl::pattern::pattern_vars;
 
 use error::NativeFnError;
 
@@ -42,12 +47,83 @@ impl NativeStorage {
                 "foldl" => fos::foldl,
                 "filter" => fos::filter,
                 "string?" => string::is_string,
+                "string" => string::string,
                 "string-append" => string::string_append,
                 "make-string" => string::make_string,
                 "string-length" => string::len,
                 "string-ref" => string::string_ref,
                 "substring" => string::substring,
                 "string->list" => string::string_to_list,
+                "string-upcase" => string::string_upcase,
+                "string-downcase" => string::string_downcase,
+                "string-split" => string::string_split,
+                "string-join" => string::string_join,
+                "string-trim" => string::string_trim,
+                "string-replace" => string::string_replace,
+                "string-contains?" => string::string_contains,
+                "string-prefix?" => string::string_prefix,
+                "string-suffix?" => string::string_suffix,
+                "string=?" => string::string_eq,
+                "string<?" => string::string_lt,
+                "string>?" => string::string_gt,
+                "string<=?" => string::string_le,
+                "string>=?" => string::string_ge,
+                "string-ci=?" => string::string_ci_eq,
+                "string-ci<?" => string::string_ci_lt,
+                "string-ci>?" => string::string_ci_gt,
+                "string-ci<=?" => string::string_ci_le,
+                "string-ci>=?" => string::string_ci_ge,
+                "string->number" => string::string_to_number,
+                "number->string" => string::number_to_string,
+                "string-copy" => string::string_copy,
+                "string-set!" => string::string_set,
+                "string->symbol" => string::string_to_symbol,
+                "symbol->string" => string::symbol_to_string,
+                "symbol?" => string::is_symbol,
+                "~a" => string::tilde_a,
+                "~s" => string::tilde_s,
+                "~v" => string::tilde_v,
+                "~r" => string::tilde_r,
+                "bytes?" => bytes::is_bytes,
+                "make-bytes" => bytes::make_bytes,
+                "bytes" => bytes::bytes,
+                "bytes-length" => bytes::bytes_length,
+                "bytes-ref" => bytes::bytes_ref,
+                "bytes-set!" => bytes::bytes_set,
+                "subbytes" => bytes::subbytes,
+                "bytes-append" => bytes::bytes_append,
+                "bytes->string/utf-8" => bytes::bytes_to_string_utf8,
+                "string->bytes/utf-8" => bytes::string_to_bytes_utf8,
+                "bytes->list" => bytes::bytes_to_list,
+                "list->bytes" => bytes::list_to_bytes,
+                "regexp" => regexp::regexp,
+                "pregexp" => regexp::pregexp,
+                "regexp?" => regexp::is_regexp,
+                "regexp-match" => regexp::regexp_match,
+                "regexp-match*" => regexp::regexp_match_all,
+                "regexp-match?" => regexp::is_regexp_match,
+                "regexp-replace" => regexp::regexp_replace,
+                "regexp-replace*" => regexp::regexp_replace_all,
+                "regexp-split" => regexp::regexp_split,
+                "regexp-quote" => regexp::regexp_quote,
+                "char?" => character::is_char,
+                "char->integer" => character::char_to_integer,
+                "integer->char" => character::integer_to_char,
+                "char-upcase" => character::char_upcase,
+                "char-downcase" => character::char_downcase,
+                "char-alphabetic?" => character::is_char_alphabetic,
+                "char-numeric?" => character::is_char_numeric,
+                "char-whitespace?" => character::is_char_whitespace,
+                "char=?" => character::char_eq,
+                "char<?" => character::char_lt,
+                "char>?" => character::char_gt,
+                "char<=?" => character::char_le,
+                "char>=?" => character::char_ge,
+                "char-ci=?" => character::char_ci_eq,
+                "char-ci<?" => character::char_ci_lt,
+                "char-ci>?" => character::char_ci_gt,
+                "char-ci<=?" => character::char_ci_le,
+                "char-ci>=?" => character::char_ci_ge,
                 "list->string" => list::list_to_string,
                 "length" => list::len,
                 "list-ref" => list::list_ref,
@@ -62,7 +138,166 @@ impl NativeStorage {
                 "nor" => logic::nor,
                 "xor" => logic::xor,
                 "if" => branch::r#if,
-                "cond" => branch::cond
+                "begin" => common::begin,
+                "let" => common::r#let,
+                "letrec" => common::letrec,
+                "lambda" => common::lambda,
+                "void" => common::void,
+                "values" => common::values,
+                "eqv?" => equality::is_eqv,
+                "eq?" => equality::is_eqv,
+                "equal?" => equality::is_equal,
+                "match" => pattern::r#match,
+                "struct" => structs::r#struct,
+                "vector" => vector::vector,
+                "make-vector" => vector::make_vector,
+                "vector?" => vector::is_vector,
+                "vector-length" => vector::vector_length,
+                "vector-ref" => vector::vector_ref,
+                "vector->list" => vector::vector_to_list,
+                "list->vector" => vector::list_to_vector,
+                "box" => boxes::r#box,
+                "unbox" => boxes::unbox,
+                "box?" => boxes::is_box,
+                "hash" => hash::hash,
+                "hash?" => hash::is_hash,
+                "hash-ref" => hash::hash_ref,
+                "hash-set" => hash::hash_set,
+                "hash-count" => hash::hash_count,
+                "hash-keys" => hash::hash_keys,
+                "hash-values" => hash::hash_values,
+                "hash->list" => hash::hash_to_list,
+                "in-range" => sequence::in_range,
+                "in-naturals" => sequence::in_naturals,
+                "in-list" => sequence::in_list,
+                "in-vector" => sequence::in_vector,
+                "in-string" => sequence::in_string,
+                "in-hash" => sequence::in_hash,
+                "in-lines" => sequence::in_lines,
+                "sequence?" => sequence::is_sequence_native,
+                "sequence->list" => sequence::sequence_to_list,
+                "stream->list" => sequence::stream_to_list,
+                "stream" => sequence::stream,
+                "stream?" => sequence::is_stream,
+                "stream-cons" => sequence::stream_cons,
+                "stream-empty?" => sequence::is_stream_empty,
+                "stream-first" => sequence::stream_first,
+                "stream-rest" => sequence::stream_rest,
+                "stream-take" => sequence::stream_take,
+                "stream-map" => sequence::stream_map,
+                "stream-filter" => sequence::stream_filter,
+                "delay" => promise::delay,
+                "delay/force" => promise::delay_force,
+                "lazy" => promise::delay_force,
+                "make-promise" => promise::make_promise,
+                "force" => promise::force,
+                "promise?" => promise::is_promise,
+                "promise-forced?" => promise::is_promise_forced,
+                "raise" => exn::raise,
+                "error" => exn::error,
+                "raise-user-error" => exn::raise_user_error,
+                "raise-argument-error" => exn::raise_argument_error,
+                "with-handlers" => exn::with_handlers,
+                "call/ec" => control::call_ec,
+                "call-with-escape-continuation" => control::call_ec,
+                "call/cc" => control::call_ec,
+                "call-with-current-continuation" => control::call_ec,
+                "let/ec" => control::let_ec,
+                "dynamic-wind" => control::dynamic_wind,
+                "make-parameter" => parameter::make_parameter,
+                "parameter?" => parameter::is_parameter,
+                "parameterize" => parameter::parameterize,
+                "display" => output::display,
+                "displayln" => output::displayln,
+                "write" => output::write,
+                "writeln" => output::writeln,
+                "print" => output::print,
+                "write-bytes" => output::write_bytes,
+                "newline" => output::newline,
+                "format" => output::format,
+                "printf" => output::printf,
+                "fprintf" => output::fprintf,
+                "eprintf" => output::eprintf,
+                "port?" => ports::is_port,
+                "input-port?" => ports::is_input_port,
+                "output-port?" => ports::is_output_port,
+                "open-output-string" => ports::open_output_string,
+                "open-input-string" => ports::open_input_string,
+                "get-output-string" => ports::get_output_string,
+                "open-output-bytes" => ports::open_output_bytes,
+                "open-input-bytes" => ports::open_input_bytes,
+                "get-output-bytes" => ports::get_output_bytes,
+                "with-output-to-string" => ports::with_output_to_string,
+                "call-with-output-string" => ports::call_with_output_string,
+                "with-input-from-string" => ports::with_input_from_string,
+                "read-line" => input::read_line,
+                "read-char" => input::read_char,
+                "peek-char" => input::peek_char,
+                "read-string" => input::read_string,
+                "read-bytes" => input::read_bytes,
+                "char-ready?" => input::is_char_ready,
+                "read" => input::read,
+                "eof-object" => input::eof_object,
+                "eof-object?" => input::is_eof_object,
+                "open-input-file" => files::open_input_file,
+                "open-output-file" => files::open_output_file,
+                "call-with-input-file" => files::call_with_input_file,
+                "call-with-output-file" => files::call_with_output_file,
+                "with-output-to-file" => files::with_output_to_file,
+                "file->string" => files::file_to_string,
+                "file->lines" => files::file_to_lines,
+                "display-to-file" => files::display_to_file,
+                "file-exists?" => files::is_file_exists,
+                "directory-exists?" => files::is_directory_exists,
+                "directory-list" => files::directory_list,
+                "delete-file" => files::delete_file,
+                "make-directory" => files::make_directory,
+                "rename-file-or-directory" => files::rename_file_or_directory,
+                "build-path" => files::build_path,
+                "path->string" => files::path_to_string,
+                "string->path" => files::string_to_path,
+                "path?" => files::is_path,
+                "exn" => exn::exn,
+                "exn?" => exn::is_exn,
+                "exn:fail" => exn::exn_fail,
+                "exn:fail?" => exn::is_exn_fail,
+                "exn:fail:contract" => exn::exn_fail_contract,
+                "exn:fail:contract?" => exn::is_exn_fail_contract,
+                "exn:fail:contract:divide-by-zero" => exn::exn_fail_contract_divide_by_zero,
+                "exn:fail:contract:divide-by-zero?" => exn::is_exn_fail_contract_divide_by_zero,
+                "exn:fail:contract:variable" => exn::exn_fail_contract_variable,
+                "exn:fail:contract:variable?" => exn::is_exn_fail_contract_variable,
+                "exn:fail:user" => exn::exn_fail_user,
+                "exn:fail:user?" => exn::is_exn_fail_user,
+                "exn:fail:filesystem" => exn::exn_fail_filesystem,
+                "exn:fail:filesystem?" => exn::is_exn_fail_filesystem,
+                "exn:fail:filesystem:exists" => exn::exn_fail_filesystem_exists,
+                "exn:fail:filesystem:exists?" => exn::is_exn_fail_filesystem_exists,
+                "exn-message" => exn::exn_message,
+                "exn-continuation-marks" => exn::exn_continuation_marks,
+                "for" => iteration::r#for,
+                "for*" => iteration::for_nested,
+                "for/list" => iteration::for_list,
+                "for*/list" => iteration::for_list_nested,
+                "for/vector" => iteration::for_vector,
+                "for*/vector" => iteration::for_vector_nested,
+                "for/hash" => iteration::for_hash,
+                "for*/hash" => iteration::for_hash_nested,
+                "for/fold" => iteration::for_fold,
+                "for*/fold" => iteration::for_fold_nested,
+                "for/sum" => iteration::for_sum,
+                "for*/sum" => iteration::for_sum_nested,
+                "for/product" => iteration::for_product,
+                "for*/product" => iteration::for_product_nested,
+                "for/and" => iteration::for_and,
+                "for*/and" => iteration::for_and_nested,
+                "for/or" => iteration::for_or,
+                "for*/or" => iteration::for_or_nested,
+                "for/first" => iteration::for_first,
+                "for*/first" => iteration::for_first_nested,
+                "for/last" => iteration::for_last,
+                "for*/last" => iteration::for_last_nested,
+                "d/expand" => debug::expand
             }
         }
     }
@@ -70,4 +305,8 @@ impl NativeStorage {
     pub fn get(&self, item: &str) -> Option<&NativeFunction> {
         self.table.get(item)
     }
+
+    pub fn names(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.table.keys().copied()
+    }
 }
@@ -1,3 +1,4 @@
+use std::collections::LinkedList;
 use std::f32::consts::E;
 use std::fmt::Debug;
 use std::ops::Deref;
@@ -6,7 +7,7 @@ use crate::ast::expr::Expr;
 use crate::display::InterpreterDisplay;
 use crate::interpreter::any::AnyEval;
 use crate::macros::get_enum;
-use crate::primitives::composed::Composed;
+use crate::primitives::composed::{Composed, List, Pair};
 use crate::primitives::DataType;
 
 pub trait AnyDebug: std::any::Any + Debug + InterpreterDisplay {}
@@ -25,6 +26,7 @@ impl<'a> From<Expr<'a>> for Any<'a> {
     fn from(value: Expr<'a>) -> Self {
         match value {
             Expr::Primitive(p) => Self::Primitive(p),
+            Expr::Datum(d) => *d,
             other => Self::Expression(other)
         }       
     }
@@ -64,6 +66,34 @@ impl<'a> Any<'a> {
         }
     }
 
+    /// Items of a list.
+    pub fn list_items(&self) -> Option<Vec<Any<'a>>> {
+        match self {
+            Any::Composed(c) => c.get_list().map(|l| l.0.iter().cloned().collect()),
+            _ => None
+        }
+    }
+
+    /// Name of a symbol like `'foo`.
+    pub fn get_symbol(&self) -> Option<&'a str> {
+        match self {
+            Any::Composed(c) => c.get_symbol().map(|s| s.0),
+            _ => None
+        }
+    }
+
+    /// Pair of `left` and `right`, which is a list when `right` is one.
+    pub fn cons(left: Any<'a>, right: Any<'a>) -> Any<'a> {
+        if let Some(items) = right.list_items() {
+            let mut items = items.into_iter().collect::<LinkedList<_>>();
+            items.push_front(left);
+
+            return Any::Composed(Box::new(Composed::List(List(items))));
+        }
+
+        Any::Composed(Box::new(Composed::Pair(Pair { left, right })))
+    }
+
     pub fn into_expr(self) -> Option<Expr<'a>> {
         match self {
             Any::Expression(e) => Some(e),
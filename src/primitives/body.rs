@@ -4,6 +4,7 @@ use crate::interpreter::any::AnyEval;
 use crate::interpreter::context::Context;
 use crate::interpreter::error::InterpreterError;
 use crate::interpreter::eval_tree::{EvalTree};
+use crate::native::{eval_sequence, pattern_vars};
 use crate::primitives::any::Any;
 use crate::primitives::composed::FunctionBody;
 
@@ -21,14 +22,216 @@ impl<'a> FunctionBody<'a> {
             } else {
                 item
             },
-            Expression(e) => Expression(Box::new(EvalTree {
-                node: e.node.as_ref().map(|n| Self::substitute_needed(n.clone(), vars)),
-                children: e.children.iter().map(|c| Self::substitute_needed(c.clone(), vars)).collect()
-            })),
+            Expression(e) => {
+                if let Some(tree) = Self::substitute_binding_form(e, vars) {
+                    return Expression(Box::new(tree));
+                }
+
+                Expression(Box::new(EvalTree {
+                    node: e.node.as_ref().map(|n| Self::substitute_needed(n.clone(), vars)),
+                    children: e.children.iter().map(|c| Self::substitute_needed(c.clone(), vars)).collect(),
+                    span: e.span.clone()
+                }))
+            },
             other => other.clone(),
         }
     }
 
+    /// Substitutes inside forms that introduce new bindings, so names bound by an inner `lambda`,
+    /// `let`, `letrec`, `let/ec`, `define`, `for` or `match` shadow the arguments of the function
+    /// being called.
+    fn substitute_binding_form(
+        tree: &EvalTree<'a>,
+        vars: &HashMap<String, AnyEval<'a>>
+    ) -> Option<EvalTree<'a>> {
+        use AnyEval::*;
+
+        let head = *tree.node.as_ref()?.get_ident()?;
+        let first = tree.children.first()?;
+
+        if head == "for" || head == "for*" || head.starts_with("for/") || head.starts_with("for*/") {
+            return Self::substitute_for(tree, vars, head.ends_with("/fold"));
+        }
+
+        if head == "match" {
+            return Some(Self::substitute_match(tree, vars));
+        }
+
+        let bound = match head {
+            "lambda" => first.get_expression()?.ident_vec(),
+            "define" => first.get_expression()?.children.clone(),
+            "let/ec" => vec![first.clone()],
+            "let" | "letrec" => first.get_expression()?
+                .as_vec()
+                .iter()
+                .filter_map(|b| b.get_expression().and_then(|b| b.node.clone()))
+                .collect(),
+            _ => return None
+        };
+
+        let mut inner = vars.clone();
+
+        for name in bound.iter().filter_map(AnyEval::get_ident) {
+            inner.remove(*name);
+        }
+
+        let first = match (head, first) {
+            ("let" | "letrec", Expression(bindings)) => {
+                let scope = if head == "let" { vars } else { &inner };
+
+                Expression(Box::new(EvalTree::from_vec(bindings.as_vec()
+                    .iter()
+                    .map(|binding| Self::substitute_binding(binding, scope))
+                    .collect())))
+            },
+            (_, other) => other.clone()
+        };
+
+        let mut children = Vec::with_capacity(tree.children.len());
+        children.push(first);
+        children.extend(tree.children.iter().skip(1).map(|c| Self::substitute_needed(c.clone(), &inner)));
+
+        Some(EvalTree {
+            node: tree.node.clone(),
+            children,
+            span: tree.span.clone()
+        })
+    }
+
+    /// Substitutes inside a `for` form, whose clauses bind names for the clauses after them and
+    /// the body, like the accumulators of `for/fold` do for the clauses, body and `#:result`.
+    fn substitute_for(
+        tree: &EvalTree<'a>,
+        vars: &HashMap<String, AnyEval<'a>>,
+        fold: bool
+    ) -> Option<EvalTree<'a>> {
+        use AnyEval::*;
+
+        let mut inner = vars.clone();
+        let mut children = Vec::with_capacity(tree.children.len());
+        let mut rest = tree.children.iter();
+
+        if fold {
+            let accumulators = rest.next()?.get_expression()?.as_vec();
+
+            // initial values are outside the scope of the accumulators, unlike #:result
+            for binding in &accumulators {
+                for name in Self::binding_names(binding) {
+                    inner.remove(name);
+                }
+            }
+
+            let mut out = Vec::with_capacity(accumulators.len());
+            let mut items = accumulators.iter();
+
+            while let Some(item) = items.next() {
+                out.push(Self::substitute_binding(item, vars));
+
+                if item.get_ident().copied() == Some("#:result") {
+                    out.extend(items.next().map(|result| Self::substitute_needed(result.clone(), &inner)));
+                }
+            }
+
+            children.push(Expression(Box::new(EvalTree::from_vec(out))));
+        }
+
+        let clauses = rest.next()?.get_expression()?.as_vec();
+        let mut out = Vec::with_capacity(clauses.len());
+
+        let mut keyword = false;
+
+        for clause in &clauses {
+            out.push(match clause {
+                // the guard after a keyword like #:when
+                _ if keyword => Self::substitute_needed(clause.clone(), &inner),
+                Ident(_) => clause.clone(),
+                _ => {
+                    let binding = Self::substitute_binding(clause, &inner);
+
+                    for name in Self::binding_names(clause) {
+                        inner.remove(name);
+                    }
+
+                    binding
+                }
+            });
+
+            keyword = !keyword && matches!(clause, Ident(_));
+        }
+
+        children.push(Expression(Box::new(EvalTree::from_vec(out))));
+        children.extend(rest.map(|c| Self::substitute_needed(c.clone(), &inner)));
+
+        Some(EvalTree {
+            node: tree.node.clone(),
+            children,
+            span: tree.span.clone()
+        })
+    }
+
+    /// Substitutes inside a `match` form, leaving the patterns as they are and keeping the names
+    /// they bind out of the clause bodies.
+    fn substitute_match(tree: &EvalTree<'a>, vars: &HashMap<String, AnyEval<'a>>) -> EvalTree<'a> {
+        let mut children = tree.children.iter();
+        let value = children.next().map(|value| Self::substitute_needed(value.clone(), vars));
+
+        let clauses = children.map(|clause| match clause {
+            AnyEval::Expression(c) => {
+                let mut names = Vec::new();
+
+                if let Some(pattern) = &c.node {
+                    pattern_vars(pattern, &mut names);
+                }
+
+                let mut inner = vars.clone();
+
+                for name in names {
+                    inner.remove(name);
+                }
+
+                AnyEval::Expression(Box::new(EvalTree {
+                    node: c.node.clone(),
+                    children: c.children.iter().map(|c| Self::substitute_needed(c.clone(), &inner)).collect(),
+                    span: c.span.clone()
+                }))
+            },
+            other => other.clone()
+        });
+
+        EvalTree {
+            node: tree.node.clone(),
+            children: value.into_iter().chain(clauses).collect(),
+            span: tree.span.clone()
+        }
+    }
+
+    /// Substitutes the values of a binding like `[name value]`, leaving the name as it is.
+    fn substitute_binding(binding: &AnyEval<'a>, vars: &HashMap<String, AnyEval<'a>>) -> AnyEval<'a> {
+        match binding {
+            AnyEval::Expression(b) => AnyEval::Expression(Box::new(EvalTree {
+                node: b.node.clone(),
+                children: b.children.iter()
+                    .map(|c| Self::substitute_needed(c.clone(), vars))
+                    .collect(),
+                span: b.span.clone()
+            })),
+            other => other.clone()
+        }
+    }
+
+    /// Names bound by a binding like `[name value]` or `[(name ...) value]`.
+    fn binding_names(binding: &AnyEval<'a>) -> Vec<&'a str> {
+        let Some(name) = binding.get_expression().and_then(|b| b.node.as_ref()) else {
+            return Vec::new();
+        };
+
+        match name {
+            AnyEval::Ident(name) => vec![*name],
+            AnyEval::Expression(names) => names.as_vec().iter().filter_map(AnyEval::get_ident).copied().collect(),
+            _ => Vec::new()
+        }
+    }
+
     fn prepare(&self, args: &[AnyEval<'a>]) -> Vec<AnyEval<'a>> {
         let map = self.args.iter().zip(args.iter())
             .map(|(k, v)| (k.to_string(), v.clone()))
@@ -44,19 +247,6 @@ impl<'a> FunctionBody<'a> {
     }
 
     pub fn call(&self, cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
-        let body = self.prepare(args);
-
-        let len = body.len();
-        let mut iter = body.into_iter().enumerate();
-
-        while let Some((idx, expr)) = iter.next() {
-            if idx == len - 1 {
-                return cx.level_down().eval(&expr)
-            } else {
-                cx.level_down().eval(&expr)?;
-            }
-        }
-
-        Ok(Any::Void(()))
+        eval_sequence(cx, &self.prepare(args))
     }
 }
@@ -1,8 +1,12 @@
+use std::cell::RefCell;
 use std::collections::{HashMap, LinkedList};
 use std::fmt::{self, Write};
+use std::path::PathBuf;
+use std::rc::Rc;
+use std::sync::Arc;
 use clap::arg;
 use crate::ast::expr::{Expr, Tree};
-use crate::display::InterpreterDisplay;
+use crate::display::{InterpreterDisplay, RawDisplay};
 use crate::ext::StrExt;
 use crate::interpreter::any::AnyEval;
 use crate::interpreter::eval_tree::EvalTree;
@@ -13,10 +17,23 @@ use crate::interpreter::Interpreter;
 use crate::macros::get_enum;
 use crate::native::error::DeclaredFunctionError;
 use crate::primitives::any::Any;
+use crate::primitives::continuation::Continuation;
+use crate::primitives::parameter::Parameter;
+use crate::primitives::port::{Eof, Port};
+use crate::primitives::promise::Promise;
+use crate::primitives::sequence::Sequence;
+use crate::primitives::structs::{Struct, StructProcedure, StructType};
 
 #[derive(Clone, Debug)]
 pub struct List<'a>(pub LinkedList<Any<'a>>);
 
+#[derive(Clone, Debug)]
+pub struct Vector<'a>(pub Vec<Any<'a>>);
+
+/// Immutable hash table, keys are compared with `equal?`.
+#[derive(Clone, Debug)]
+pub struct HashTable<'a>(pub Vec<(Any<'a>, Any<'a>)>);
+
 #[derive(Clone, Debug)]
 pub struct FunctionBody<'a> {
     pub args: Vec<&'a str>,
@@ -33,7 +50,9 @@ pub struct Function<'a> {
 #[derive(Clone, Debug)]
 pub struct LambdaFunction<'a> {
     pub arity: Option<u8>,
-    pub body: FunctionBody<'a>
+    pub body: FunctionBody<'a>,
+    /// Local variables in scope where the lambda was made, which its body sees when called
+    pub captured: Vec<(String, Any<'a>)>
 }
 
 #[derive(Clone, Debug)]
@@ -50,10 +69,30 @@ get_enum! {
     #[derive(Clone, Debug)]
     pub enum Composed<'a> {
         List(List<'a>),
+        Vector(Vector<'a>),
+        Hash(HashTable<'a>),
+        Sequence(Sequence<'a>),
+        Promise(Promise),
+        Continuation(Continuation),
+        Parameter(Parameter),
+        Port(Port),
+        Eof(Eof),
+        Path(PathBuf),
+        /// Strings made by `string`, `make-string` and `string-copy`, the only ones `string-set!`
+        /// changes
+        MutableString(Rc<RefCell<String>>),
+        /// Byte strings made by `bytes`, `make-bytes` and the other byte string procedures, the
+        /// only ones `bytes-set!` changes
+        MutableBytes(Rc<RefCell<Vec<u8>>>),
         Function(Function<'a>),
         Lambda(LambdaFunction<'a>),
         Symbol(Symbol<'a>),
-        Pair(Pair<'a>)
+        /// Boxes read from `#&datum` or made by `box`
+        Boxed(Box<Any<'a>>),
+        Pair(Pair<'a>),
+        Struct(Struct<'a>),
+        StructType(Arc<StructType>),
+        StructProcedure(StructProcedure)
     }
 }
 
@@ -96,9 +135,46 @@ impl InterpreterDisplay for Composed<'_> {
     fn fmt(&self, f: &mut dyn Write, interpreter: &Interpreter<'_>) -> fmt::Result {
         match self {
             Self::List(l) => l.fmt(f, interpreter),
+            Self::Vector(v) => v.fmt(f, interpreter),
+            Self::Hash(h) => h.fmt(f, interpreter),
+            Self::Sequence(Sequence::Stream(_)) => write!(f, "#<stream>"),
+            Self::Sequence(_) => write!(f, "#<sequence>"),
+            Self::Promise(_) => write!(f, "#<promise>"),
+            Self::Continuation(_) => write!(f, "#<continuation>"),
+            Self::Parameter(_) => write!(f, "#<procedure:parameter-procedure>"),
+            Self::Port(p) => p.fmt(f, interpreter),
+            Self::Eof(_) => write!(f, "#<eof>"),
+            Self::Path(p) => write!(f, "#<path:{}>", p.display()),
+            Self::MutableString(s) => s.borrow().as_str().fmt(f, interpreter),
+            Self::MutableBytes(b) => b.borrow().as_slice().fmt(f, interpreter),
+            Self::Symbol(s) => s.fmt(f, interpreter),
+            Self::Boxed(_) => {
+                write!(f, "'")?;
+                self.raw_fmt(f, interpreter)
+            },
             Self::Pair(p) => p.fmt(f, interpreter),
             Self::Function(fun) => fun.fmt(f, interpreter),
-            _ => Ok(())
+            Self::Lambda(_) => write!(f, "#<procedure>"),
+            Self::Struct(s) => s.fmt(f, interpreter),
+            Self::StructType(t) => t.fmt(f, interpreter),
+            Self::StructProcedure(p) => p.fmt(f, interpreter)
+        }
+    }
+}
+
+impl RawDisplay for Composed<'_> {
+    fn raw_fmt(&self, f: &mut dyn Write, interpreter: &Interpreter<'_>) -> fmt::Result {
+        match self {
+            Self::List(l) => l.raw_fmt(f, interpreter),
+            Self::Vector(v) => v.raw_fmt(f, interpreter),
+            Self::Hash(h) => h.raw_fmt(f, interpreter),
+            Self::Pair(p) => p.raw_fmt(f, interpreter),
+            Self::Symbol(s) => s.raw_fmt(f, interpreter),
+            Self::Boxed(b) => {
+                write!(f, "#&")?;
+                b.raw_fmt(f, interpreter)
+            },
+            other => other.fmt(f, interpreter)
         }
     }
 }
@@ -108,10 +184,25 @@ impl Composed<'_> {
         use Composed::*;
         match self {
             List(l) => List(l.make_static()),
+            Vector(v) => Vector(v.make_static()),
+            Hash(h) => Hash(h.make_static()),
+            Sequence(s) => Sequence(s.make_static()),
+            Promise(p) => Promise(p),
+            Continuation(k) => Continuation(k),
+            Parameter(p) => Parameter(p),
+            Port(p) => Port(p),
+            Eof(e) => Eof(e),
+            Path(p) => Path(p),
+            MutableString(s) => MutableString(s),
+            MutableBytes(b) => MutableBytes(b),
             Function(f) => Function(f.make_static()),
             Lambda(l) => Lambda(l.make_static()),
             Symbol(s) => Symbol(s.make_static()),
+            Boxed(b) => Boxed(Box::new(b.make_static())),
             Pair(p) => Pair(p.make_static()),
+            Struct(s) => Struct(s.make_static()),
+            StructType(t) => StructType(t),
+            StructProcedure(p) => StructProcedure(p),
         }
     }
 }
@@ -128,6 +219,18 @@ impl List<'_> {
     }
 }
 
+impl Vector<'_> {
+    pub fn make_static(self) -> Vector<'static> {
+        Vector(self.0.into_iter().map(Any::make_static).collect())
+    }
+}
+
+impl HashTable<'_> {
+    pub fn make_static(self) -> HashTable<'static> {
+        HashTable(self.0.into_iter().map(|(k, v)| (k.make_static(), v.make_static())).collect())
+    }
+}
+
 impl<'a> FunctionBody<'a> {
     pub fn make_static(self) -> FunctionBody<'static> {
         FunctionBody {
@@ -183,20 +286,19 @@ impl<'a> Function<'a> {
             return Err(InterpreterError::InvalidExpression);
         }
 
-        let arity = tree.children.len() - 1;
-
         assert_eq!(*tree.node.as_ref().unwrap().get_ident().unwrap(), "lambda");
 
-         let args = (&tree.children[1..]).iter().map(Clone::clone).collect::<Vec<_>>();
+        let args = (&tree.children[1..]).iter().map(Clone::clone).collect::<Vec<_>>();
+        let body = FunctionBody::parse(
+            &tree.children[0].get_expression()
+                .ok_or(InterpreterError::InvalidExpression)?
+                .ident_vec(),
+            args)?;
 
         Ok(Function {
             name,
-            arity: Some(arity as _),
-            body: FunctionBody::parse(
-                &tree.children[0].get_expression()
-                    .ok_or(InterpreterError::InvalidExpression)?
-                    .ident_vec(),
-                args)?
+            arity: Some(body.args.len() as _),
+            body
         })
     }
 }
@@ -206,6 +308,7 @@ impl LambdaFunction<'_> {
         LambdaFunction {
             arity: self.arity,
             body: self.body.make_static(),
+            captured: self.captured.into_iter().map(|(k, v)| (k, v.make_static())).collect(),
         }
     }
 }
@@ -0,0 +1,53 @@
+use std::cell::Cell;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::interpreter::error::InterpreterError;
+use crate::primitives::any::Any;
+
+static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// Escape continuation, which jumps back to the point where it was captured as long as that
+/// point is still being evaluated. Clones share whether it's still usable.
+#[derive(Clone, Debug)]
+pub struct Continuation {
+    id: usize,
+    active: Rc<Cell<bool>>
+}
+
+impl Continuation {
+    pub fn new() -> Self {
+        Self {
+            id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
+            active: Rc::new(Cell::new(true))
+        }
+    }
+
+    pub fn id(&self) -> usize {
+        self.id
+    }
+
+    /// Marks the continuation as unusable, once the evaluation that captured it returned.
+    pub fn invalidate(&self) {
+        self.active.set(false);
+    }
+
+    /// Error unwinding the evaluation up to the capture point with the given values.
+    pub fn escape(&self, mut values: Vec<Any<'_>>) -> InterpreterError {
+        if !self.active.get() {
+            return InterpreterError::Runtime(
+                "continuation application: attempt to jump into an escape continuation".to_string()
+            );
+        }
+
+        let value = match values.len() {
+            0 => Any::Void(()),
+            1 => values.swap_remove(0),
+            got => return InterpreterError::Runtime(format!(
+                "continuation application: expected a single value, got {got}"
+            ))
+        };
+
+        InterpreterError::Escape { id: self.id, value: value.make_static() }
+    }
+}
@@ -1,8 +1,8 @@
 use std::{borrow::Cow, str::Chars};
 use lazy_static::lazy_static;
-use crate::{lexer::Token, macros::*};
+use crate::{lexer::LexerError, macros::*};
 
-use pcre2::bytes::Regex;
+use pcre2::bytes::{Regex, RegexBuilder};
 
 lazy_static! {
     static ref COMPLEX_REGEX: Regex = Regex::new(r"[+-]?(((\d+\.\d*|\d*\.\d+|\d+)[+-])?((\d+\.\d*|\d*\.\d+|\d+)i|i(\d+\.\d*|\d*\.\d+|\d+)|i)|(\d+\.\d*|\d*\.\d+|\d+)?e\^(\([+-]?|[+-]?\()((\d+\.\d*|\d*\.\d+|\d+)i|i(\d+\.\d*|\d*\.\d+|\d+)|i)\))").unwrap();
@@ -12,9 +12,8 @@ get_enum! {
     #[derive(Debug, Clone)]
     pub enum DataType<'a> {
         String(Cow<'a, str>),
-        Character(Cow<'a, str>),
-        #[allow(unused)]
-        Regex(Regex), // not parsed right now
+        Character(char),
+        Regex(Regexp),
         Integer(i32),
         Rational(Rational),
         Complex(Complex),
@@ -48,6 +47,92 @@ pub enum Repr {
     Binary
 }
 
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum RegexRepr {
+    /// `#rx`
+    Rx,
+    /// `#px`
+    Px
+}
+
+#[derive(Debug, Clone)]
+pub struct Regexp {
+    pub inner: Box<Regex>,
+    pub repr: RegexRepr,
+    source: Box<str>
+}
+
+impl Regexp {
+    /// Compiles `pattern` with PCRE, rewriting `#rx` patterns first so they don't get the `#px`
+    /// extensions.
+    pub fn new(pattern: &str, repr: RegexRepr) -> Result<Self, pcre2::Error> {
+        let inner = match repr {
+            RegexRepr::Px => RegexBuilder::new().utf(true).build(pattern)?,
+            RegexRepr::Rx => RegexBuilder::new().utf(true).build(&rx_to_pcre(pattern))?
+        };
+
+        Ok(Self { inner: Box::new(inner), repr, source: pattern.into() })
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+}
+
+/// Rewrites a `#rx` pattern into PCRE syntax. `#rx` patterns have no classes like `\d`,
+/// backreferences or bounded repetitions, so a backslash matches the character after it, braces
+/// match themselves, and so does a backslash inside brackets.
+fn rx_to_pcre(pattern: &str) -> String {
+    let mut out = String::with_capacity(pattern.len());
+    let mut chars = pattern.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some(c) if c.is_ascii_alphanumeric() => out.push(c),
+                Some(c) => {
+                    out.push('\\');
+                    out.push(c);
+                },
+                None => out.push('\\')
+            },
+            '{' | '}' => {
+                out.push('\\');
+                out.push(c);
+            },
+            '[' => {
+                out.push('[');
+
+                if chars.next_if_eq(&'^').is_some() {
+                    out.push('^');
+                }
+
+                // a `]` right after the opening bracket is part of the range
+                if chars.next_if_eq(&']').is_some() {
+                    out.push_str("\\]");
+                }
+
+                for c in chars.by_ref() {
+                    match c {
+                        ']' => {
+                            out.push(']');
+                            break;
+                        },
+                        '\\' | '[' => {
+                            out.push('\\');
+                            out.push(c);
+                        },
+                        c => out.push(c)
+                    }
+                }
+            },
+            c => out.push(c)
+        }
+    }
+
+    out
+}
+
 #[derive(Debug, Clone)]
 pub struct LiteralNumber<'a> {
     pub inner: Cow<'a, str>,
@@ -56,131 +141,58 @@ pub struct LiteralNumber<'a> {
 } 
 
 impl<'a> DataType<'a> {
-    pub fn len(&'a self) -> usize {
-        use DataType::*;
-
-        match self {
-            String(item) => len_u8buf(&item.as_ref()) + 2, // " count
-            Character(item) => Self::character_length(item) + 2, // #\ count
-            Regex(r) => r.as_str().len(),
-            Integer(i) => len_num(*i),
-            Rational(r) => len_num(r.left) + len_num(r.right) + 1, // +1 for "/" character
-            Complex(c) => len_num(c.real) 
-                + len_num(c.imaginary) + 1 // + 1 for i
-                + if c.includes_prefix { 1 } else { 0 } // +1 if the number is prefixed with "+"
-                + if c.imaginary > 0 { 1 } else { 0 }, // +1 if the imaginary part is positive, because len_num does not detect it
-            Floating(f) => {
-                let l = len_num(*f);
-                //println!("floating with value: {f}, len: {l}");
-                l
-            },
-            Double(d) => len_num(*d),
-            Hex(n) | Octal(n) | Binary(n) => n.inner.len(),
-            Bytes(b) => len_u8buf(&b.as_ref()),
-            Boolean(_) => 2, // #t or #f
-        }
-    }
-
     pub fn parse(item: &'a str) -> Option<Self> {
         let first = item.chars().next()?;
 
         match first {
             '#' => Self::parse_prefixed(item),
-            '"' => Self::parse_str(item).map(DataType::String),
+            '"' => parse_string(&item[1..]).ok().map(|(s, _)| DataType::String(s)),
             _ if item.chars().filter(|c| c.is_numeric()).next().is_some() => Self::parse_number(item),
             _ => None
         }
     }
 
-    fn parse_str(item: &'a str) -> Option<Cow<'a, str>> {
-        let mut characters = item.char_indices();
-        let mut until = 0;
-
-        while let Some((idx, next_char)) = characters.next() {
-            if next_char == '\\' {
-                characters.next().unwrap(); // skip the escaped character
-                continue;
-            }
-
-            until = idx;
-
-            if next_char == '"' && idx > 0 {
-                break;
-            }
+    fn parse_prefixed(item: &'a str) -> Option<DataType<'a>> {
+        match item {
+            "#t" | "#T" | "#true" => return Some(DataType::Boolean(true)),
+            "#f" | "#F" | "#false" => return Some(DataType::Boolean(false)),
+            _ => ()
         }
 
-        Some(Cow::Borrowed(&item[1..until]))
-    }
-
-    fn parse_prefixed(item: &'a str) -> Option<DataType<'a>> {
-        sw!(item, "#t", || Some(DataType::Boolean(true)));
-        sw!(item, "#f", || Some(DataType::Boolean(false)));
-        sw!(item, "#\"", || Some(DataType::Bytes(Cow::Borrowed(&item[1..].as_bytes()))));
-        sw!(item, "#\\", || Some(DataType::Character(Self::parse_character(&item[2..]))));
-        sw!(item, "#x", || Some(DataType::Hex(Self::parse_literal_number(Repr::Hex, item))));
-        sw!(item, "#o", || Some(DataType::Octal(Self::parse_literal_number(Repr::Octal, item))));
-        sw!(item, "#b", || Some(DataType::Binary(Self::parse_literal_number(Repr::Binary, item))));
+        sw!(item, "#\"", || parse_bytes(&item[2..]).ok().map(|(b, _)| DataType::Bytes(b)));
+        sw!(item, "#rx\"", || parse_regex(item).ok().map(|(r, _)| DataType::Regex(r)));
+        sw!(item, "#px\"", || parse_regex(item).ok().map(|(r, _)| DataType::Regex(r)));
+        sw!(item, "#\\", || parse_character(&item[2..]).ok().map(|(c, _)| DataType::Character(c)));
+        sw!(item, "#x", || Self::parse_literal_number(Repr::Hex, item).map(DataType::Hex));
+        sw!(item, "#o", || Self::parse_literal_number(Repr::Octal, item).map(DataType::Octal));
+        sw!(item, "#b", || Self::parse_literal_number(Repr::Binary, item).map(DataType::Binary));
 
         None
     }
 
-    pub fn parse_character(item: &'a str) -> Cow<'a, str> {
-        let space = item.find(" ").unwrap_or(item.len());
-        let item = &item[..space];
-
-        match item {
-            "" | " " => Cow::Owned("space".to_string()),
-            other => Cow::Borrowed(other)
-        }
-    }
-
-    pub fn character_length(item: &'a Cow<'a, str>) -> usize {
-        match item.as_ref() {
-            "space" => 1,
-            other => other.len()
-        }
-    }
+    fn parse_literal_number(repr: Repr, item: &'a str) -> Option<LiteralNumber<'a>> {
+        let radix = match repr {
+            Repr::Hex => 16,
+            Repr::Octal => 8,
+            Repr::Binary => 2
+        };
 
-    pub fn character_to_string(item: &'a Cow<'a, str>) -> Cow<'a, str> {
-        match item.as_ref() {
-            "space" => Cow::Borrowed(" "),
-            other => Cow::Borrowed(&other)
-        }
-    }
+        i64::from_str_radix(&item[2..], radix).ok()?;
 
-    fn parse_literal_number(repr: Repr, item: &'a str) -> LiteralNumber<'a> {
-        let num = Self::take_numbers(&item[2..]);
-        LiteralNumber {
+        Some(LiteralNumber {
             repr,
-            inner: Cow::Borrowed(&item[0.. num.len() + 2])
-        }
+            inner: Cow::Borrowed(item)
+        })
     }
 
-    fn parse_number(mut item: &'a str) -> Option<DataType<'a>> {
-        let space = item.find(" ").unwrap_or(item.len());
-        item = &item[0..space];
-
+    /// Parses `item` if all of it is a number.
+    fn parse_number(item: &'a str) -> Option<DataType<'a>> {
         c!(item, "/", || Self::parse_rational(item));
         c!(item, "i", || Self::parse_complex(item));
         c!(item, "e", || Some(DataType::Double(item.parse::<f64>().ok()?)));
         c!(item, ".", || Some(DataType::Floating(item.parse::<f32>().ok()?)));
 
-        Some(DataType::Integer(Self::take_numbers(item).parse::<i32>().ok()?))
-    }
-
-    fn take_numbers(item: &'a str) -> &'a str {
-        let mut buf = &item[0..1];
-        let mut chars = item.char_indices().skip(1);
-
-        while let Some((idx, curr)) = chars.next() {
-            if curr.is_numeric() {
-                buf = &item[0..=idx];
-            } else {
-                break;
-            }
-        }
-
-        buf
+        Some(DataType::Integer(item.parse::<i32>().ok()?))
     }
 
     fn parse_complex(item: &'a str) -> Option<DataType<'a>> {
@@ -236,7 +248,7 @@ impl<'a> DataType<'a> {
 
         Some(DataType::Rational(Rational {
             left: first.parse().ok()?,
-            right: Self::take_numbers(second).parse().ok()?,
+            right: second.parse().ok()?,
         }))
     }
 
@@ -248,7 +260,6 @@ impl<'a> DataType<'a> {
         use DataType::*;
         let this = match self {
             String(d) => String(Cow::Owned(d.into_owned())),
-            Character(c) => Character(Cow::Owned(c.into_owned())),
             Hex(h) => Hex(LiteralNumber {
                 repr: Repr::Hex, 
                 inner: Cow::Owned(h.inner.into_owned())
@@ -273,15 +284,165 @@ impl<'a> DataType<'a> {
     }
 }
 
-fn len_u8buf<B: AsRef<[u8]>>(item: &B) -> usize {
-    item.as_ref().len()
+/// Characters written by name, the first name of each character is the one used to print it.
+const CHARACTER_NAMES: &[(&str, char)] = &[
+    ("nul", '\0'),
+    ("null", '\0'),
+    ("backspace", '\u{8}'),
+    ("tab", '\t'),
+    ("newline", '\n'),
+    ("linefeed", '\n'),
+    ("vtab", '\u{b}'),
+    ("page", '\u{c}'),
+    ("return", '\r'),
+    ("space", ' '),
+    ("rubout", '\u{7f}'),
+    ("delete", '\u{7f}')
+];
+
+pub fn character_name(c: char) -> Option<&'static str> {
+    CHARACTER_NAMES.iter()
+        .find(|(_, named)| *named == c)
+        .map(|(name, _)| *name)
 }
 
-fn len_num<T>(item: T) -> usize
-where
-    T: ToString
-{
-    item.to_string().len()
+/// Parses the character literal starting `item`, which is the text following `#\`, returning
+/// the character along with the length of its literal. Names like `newline` and code points
+/// like `x41` or `u03BB` are read whole, any other character only by itself, as long as it isn't
+/// a letter followed by another one.
+pub fn parse_character(item: &str) -> Result<(char, usize), LexerError> {
+    let first = item.chars().next().ok_or(LexerError::Eof)?;
+
+    if !first.is_alphabetic() {
+        return Ok((first, first.len_utf8()));
+    }
+
+    let end = item.find(|c: char| !c.is_alphanumeric()).unwrap_or(item.len());
+    let name = &item[..end];
+
+    if let Some((_, c)) = CHARACTER_NAMES.iter().find(|(named, _)| *named == name) {
+        return Ok((*c, end));
+    }
+
+    let code_point = name.strip_prefix(['x', 'u', 'U'])
+        .filter(|hex| !hex.is_empty() && hex.chars().all(|c| c.is_ascii_hexdigit()))
+        .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+        .and_then(char::from_u32);
+
+    if let Some(c) = code_point {
+        return Ok((c, end));
+    }
+
+    match name[first.len_utf8()..].chars().next() {
+        Some(c) if c.is_alphabetic() => Err(LexerError::UnknownCharacterName(name.to_string())),
+        _ => Ok((first, first.len_utf8()))
+    }
+}
+
+/// Parses the string literal starting `item`, which is the text following the opening quote,
+/// returning its contents along with the length of the literal including the closing quote.
+/// Literals without escapes are borrowed, a backslash before a line break joins the lines.
+pub fn parse_string(item: &str) -> Result<(Cow<'_, str>, usize), LexerError> {
+    let Some(first_special) = item.find(['"', '\\']) else {
+        return Err(LexerError::UnterminatedString);
+    };
+
+    if item[first_special..].starts_with('"') {
+        return Ok((Cow::Borrowed(&item[..first_special]), first_special + 1));
+    }
+
+    let mut out = String::from(&item[..first_special]);
+    let mut chars = item[first_special..].char_indices().peekable();
+
+    while let Some((idx, c)) = chars.next() {
+        match c {
+            '"' => return Ok((Cow::Owned(out), first_special + idx + 1)),
+            '\\' => {
+                let (_, escaped) = chars.next().ok_or(LexerError::UnterminatedString)?;
+
+                out.push(match escaped {
+                    'a' => '\u{7}',
+                    'b' => '\u{8}',
+                    't' => '\t',
+                    'n' => '\n',
+                    'v' => '\u{b}',
+                    'f' => '\u{c}',
+                    'r' => '\r',
+                    'e' => '\u{1b}',
+                    '"' | '\'' | '\\' => escaped,
+                    '\n' | '\r' => {
+                        if escaped == '\r' {
+                            chars.next_if(|(_, c)| *c == '\n');
+                        }
+
+                        while chars.next_if(|(_, c)| *c == ' ' || *c == '\t').is_some() {}
+                        continue;
+                    },
+                    '0'..='7' => {
+                        let mut code = escaped.to_digit(8).unwrap_or_default();
+
+                        for _ in 0..2 {
+                            match chars.peek().and_then(|(_, c)| c.to_digit(8)) {
+                                Some(digit) if code * 8 + digit <= 0o377 => {
+                                    code = code * 8 + digit;
+                                    chars.next();
+                                },
+                                _ => break
+                            }
+                        }
+
+                        char::from_u32(code).ok_or(LexerError::InvalidEscape(escaped))?
+                    },
+                    'x' | 'u' | 'U' => {
+                        let max_digits = match escaped { 'x' => 2, 'u' => 4, _ => 8 };
+                        let mut code = 0u32;
+                        let mut digits = 0;
+
+                        while digits < max_digits {
+                            let Some(digit) = chars.peek().and_then(|(_, c)| c.to_digit(16)) else { break; };
+                            code = code * 16 + digit;
+                            digits += 1;
+                            chars.next();
+                        }
+
+                        char::from_u32(code)
+                            .filter(|_| digits > 0)
+                            .ok_or(LexerError::InvalidEscape(escaped))?
+                    },
+                    other => return Err(LexerError::InvalidEscape(other))
+                });
+            },
+            c => out.push(c)
+        }
+    }
+
+    Err(LexerError::UnterminatedString)
+}
+
+/// Parses the byte string literal starting `item`, which is the text following `#"`, returning
+/// its bytes along with the length of the literal including the closing quote. Escapes work like
+/// in strings but every character has to fit in a byte.
+pub fn parse_bytes(item: &str) -> Result<(Cow<'_, [u8]>, usize), LexerError> {
+    let (text, len) = parse_string(item)?;
+
+    let bytes = match text {
+        Cow::Borrowed(text) if text.is_ascii() => Cow::Borrowed(text.as_bytes()),
+        text => Cow::Owned(text.chars()
+            .map(|c| u8::try_from(c).map_err(|_| LexerError::InvalidByte(c)))
+            .collect::<Result<Vec<_>, _>>()?)
+    };
+
+    Ok((bytes, len))
+}
+
+/// Parses the `#rx"..."` or `#px"..."` literal starting `item`, returning the compiled
+/// expression along with the length of the literal.
+pub fn parse_regex(item: &str) -> Result<(Regexp, usize), LexerError> {
+    let repr = if item.starts_with("#px") { RegexRepr::Px } else { RegexRepr::Rx };
+    let (pattern, len) = parse_string(&item[4..])?;
+    let regex = Regexp::new(&pattern, repr).map_err(|e| LexerError::InvalidRegex(e.to_string()))?;
+
+    Ok((regex, len + 4))
 }
 
 #[test]
@@ -1,6 +1,6 @@
 use std::fmt::{self, Write};
 
-use crate::{display::InterpreterDisplay, interpreter::Interpreter, primitives::any::Any};
+use crate::{display::{InterpreterDisplay, RawDisplay}, interpreter::Interpreter, primitives::any::Any};
 
 impl InterpreterDisplay for Any<'_> {
     fn fmt(&self, f: &mut dyn Write, interpreter: &Interpreter<'_>) -> fmt::Result {
@@ -11,4 +11,17 @@ impl InterpreterDisplay for Any<'_> {
             _ => Ok(())
         }
     }
-}
\ No newline at end of file
+}
+
+/// Written form of the value, like the printed one but without quoting it, as used by `write`
+/// and for the items of printed lists.
+impl RawDisplay for Any<'_> {
+    fn raw_fmt(&self, f: &mut dyn Write, interpreter: &Interpreter<'_>) -> fmt::Result {
+        match self {
+            Any::Primitive(p) => p.raw_fmt(f, interpreter),
+            Any::Composed(c) => c.raw_fmt(f, interpreter),
+            Any::Expression(e) => e.raw_fmt(f, interpreter),
+            Any::Void(()) => write!(f, "#<void>")
+        }
+    }
+}
@@ -1,10 +1,27 @@
 use std::fmt::{self, Write};
 
-use crate::{display::InterpreterDisplay, interpreter::Interpreter, primitives::composed::{Function, List, Pair}};
+use crate::primitives::any::Any;
+
+use crate::{display::{InterpreterDisplay, RawDisplay}, interpreter::Interpreter, primitives::composed::{Function, HashTable, List, Pair, Symbol, Vector}};
 
 impl InterpreterDisplay for List<'_> {
     fn fmt(&self, f: &mut dyn Write, interpreter: &Interpreter<'_>) -> fmt::Result {
-        write!(f, "'(")?;
+        write!(f, "'")?;
+        self.raw_fmt(f, interpreter)
+    }
+}
+
+/// Lists like `(quote datum)` are shown as `'datum`, the way they're read.
+impl RawDisplay for List<'_> {
+    fn raw_fmt(&self, f: &mut dyn Write, interpreter: &Interpreter<'_>) -> fmt::Result {
+        if let [quote, datum] = self.0.iter().collect::<Vec<_>>()[..] {
+            if quote.get_symbol() == Some("quote") {
+                write!(f, "'")?;
+                return datum.raw_fmt(f, interpreter);
+            }
+        }
+
+        write!(f, "(")?;
         let mut first = true;
 
         for i in &self.0 {
@@ -14,7 +31,7 @@ impl InterpreterDisplay for List<'_> {
                 first = false;
             }
 
-            i.fmt(f, interpreter)?;
+            i.raw_fmt(f, interpreter)?;
         }
 
         write!(f, ")")?;
@@ -23,12 +40,75 @@ impl InterpreterDisplay for List<'_> {
     }
 }
 
+impl InterpreterDisplay for Vector<'_> {
+    fn fmt(&self, f: &mut dyn Write, interpreter: &Interpreter<'_>) -> fmt::Result {
+        write!(f, "'")?;
+        self.raw_fmt(f, interpreter)
+    }
+}
+
+impl RawDisplay for Vector<'_> {
+    fn raw_fmt(&self, f: &mut dyn Write, interpreter: &Interpreter<'_>) -> fmt::Result {
+        write!(f, "#(")?;
+
+        for (idx, i) in self.0.iter().enumerate() {
+            if idx > 0 {
+                write!(f, " ")?;
+            }
+
+            i.raw_fmt(f, interpreter)?;
+        }
+
+        write!(f, ")")
+    }
+}
+
+impl InterpreterDisplay for HashTable<'_> {
+    fn fmt(&self, f: &mut dyn Write, interpreter: &Interpreter<'_>) -> fmt::Result {
+        write!(f, "'")?;
+        self.raw_fmt(f, interpreter)
+    }
+}
+
+impl RawDisplay for HashTable<'_> {
+    fn raw_fmt(&self, f: &mut dyn Write, interpreter: &Interpreter<'_>) -> fmt::Result {
+        write!(f, "#hash(")?;
+
+        for (idx, (key, value)) in self.0.iter().enumerate() {
+            if idx > 0 {
+                write!(f, " ")?;
+            }
+
+            Any::cons(key.clone(), value.clone()).raw_fmt(f, interpreter)?;
+        }
+
+        write!(f, ")")
+    }
+}
+
 impl InterpreterDisplay for Pair<'_> {
     fn fmt(&self, f: &mut dyn Write, interpreter: &Interpreter<'_>) -> fmt::Result {
+        write!(f, "'")?;
+        self.raw_fmt(f, interpreter)
+    }
+}
+
+/// Pairs ending other pairs are shown as a single form, like `(a b . c)`.
+impl RawDisplay for Pair<'_> {
+    fn raw_fmt(&self, f: &mut dyn Write, interpreter: &Interpreter<'_>) -> fmt::Result {
         write!(f, "(")?;
-        self.left.fmt(f, interpreter)?;
+        self.left.raw_fmt(f, interpreter)?;
+
+        let mut rest = &self.right;
+
+        while let Some(pair) = rest.get_composed().and_then(|c| c.get_pair()) {
+            write!(f, " ")?;
+            pair.left.raw_fmt(f, interpreter)?;
+            rest = &pair.right;
+        }
+
         write!(f, " . ")?;
-        self.right.fmt(f, interpreter)?;
+        rest.raw_fmt(f, interpreter)?;
         write!(f, ")")
     }
 }
@@ -38,3 +118,16 @@ impl InterpreterDisplay for Function<'_> {
         write!(f, "#<procedure:{}>", self.name)
     }
 }
+
+impl InterpreterDisplay for Symbol<'_> {
+    fn fmt(&self, f: &mut dyn Write, interpreter: &Interpreter<'_>) -> fmt::Result {
+        write!(f, "'")?;
+        self.raw_fmt(f, interpreter)
+    }
+}
+
+impl RawDisplay for Symbol<'_> {
+    fn raw_fmt(&self, f: &mut dyn Write, _: &Interpreter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
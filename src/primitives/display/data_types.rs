@@ -1,13 +1,11 @@
-use pcre2::bytes::Regex;
-
-use crate::{display::{InterpreterDisplay, RawDisplay}, interpreter::Interpreter, primitives::{Complex, DataType, LiteralNumber, Rational, Repr}};
+use crate::{display::{InterpreterDisplay, RawDisplay}, interpreter::Interpreter, primitives::{character_name, Complex, DataType, LiteralNumber, Rational, RegexRepr, Regexp, Repr}};
 use std::{borrow::Cow, fmt::{self, Write}, ops::Deref};
 
 impl InterpreterDisplay for DataType<'_> {
     fn fmt(&self, f: &mut dyn Write, interpreter: &Interpreter<'_>) -> fmt::Result {
         match self {
             Self::String(s) => s.fmt(f, interpreter),
-            Self::Character(c) => CharacterDisplay(c).fmt(f, interpreter),
+            Self::Character(c) => CharacterDisplay(*c).fmt(f, interpreter),
             Self::Regex(r) => r.fmt(f, interpreter),
             Self::Integer(i) => i.fmt(f, interpreter),
             Self::Rational(r) => r.fmt(f, interpreter),
@@ -21,23 +19,53 @@ impl InterpreterDisplay for DataType<'_> {
     }
 }
 
+/// Written form of a string, escaping what the reader wouldn't read back as itself.
 impl InterpreterDisplay for &str {
     fn fmt(&self, f: &mut dyn Write, _: &Interpreter<'_>) -> fmt::Result {
-        write!(f, "\"{self}\"")
+        f.write_char('"')?;
+
+        for c in self.chars() {
+            match c {
+                '"' => f.write_str("\\\"")?,
+                '\\' => f.write_str("\\\\")?,
+                '\n' => f.write_str("\\n")?,
+                '\t' => f.write_str("\\t")?,
+                '\r' => f.write_str("\\r")?,
+                '\u{7}' => f.write_str("\\a")?,
+                '\u{8}' => f.write_str("\\b")?,
+                '\u{b}' => f.write_str("\\v")?,
+                '\u{c}' => f.write_str("\\f")?,
+                '\u{1b}' => f.write_str("\\e")?,
+                c if c.is_control() => write!(f, "\\u{:04X}", c as u32)?,
+                c => f.write_char(c)?
+            }
+        }
+
+        f.write_char('"')
     }
 }
 
-pub struct CharacterDisplay<'a>(pub &'a Cow<'a, str>);
+/// Written form of a character, by name if it has one or by code point if it isn't printable.
+pub struct CharacterDisplay(pub char);
 
-impl InterpreterDisplay for CharacterDisplay<'_> {
+impl InterpreterDisplay for CharacterDisplay {
     fn fmt(&self, f: &mut dyn Write, _: &Interpreter<'_>) -> fmt::Result {
-        write!(f, "#\\{}", self.0)
+        match character_name(self.0) {
+            Some(name) => write!(f, "#\\{name}"),
+            None if self.0.is_control() => write!(f, "#\\u{:04X}", self.0 as u32),
+            None => write!(f, "#\\{}", self.0)
+        }
     }
 }
 
-impl InterpreterDisplay for Regex {
-    fn fmt(&self, f: &mut dyn Write, _: &Interpreter<'_>) -> fmt::Result {
-        write!(f, "#rx\"{}\"", self.as_str())
+impl InterpreterDisplay for Regexp {
+    fn fmt(&self, f: &mut dyn Write, interpreter: &Interpreter<'_>) -> fmt::Result {
+        f.write_str(match self.repr {
+            RegexRepr::Rx => "#rx",
+            RegexRepr::Px => "#px"
+        })?;
+
+        self.source().fmt(f, interpreter)
     }
 }
 
@@ -87,11 +115,31 @@ impl InterpreterDisplay for LiteralNumber<'_> {
 }
 
 impl InterpreterDisplay for Cow<'_, [u8]> {
+    fn fmt(&self, f: &mut dyn Write, interpreter: &Interpreter<'_>) -> fmt::Result {
+        self.as_ref().fmt(f, interpreter)
+    }
+}
+
+/// Written form of a byte string, bytes that aren't printable ASCII are escaped in octal.
+impl InterpreterDisplay for &[u8] {
     fn fmt(&self, f: &mut dyn Write, _: &Interpreter<'_>) -> fmt::Result {
-        let s = std::str::from_utf8(self)
-            .expect("Must be utf8 to be printed");
+        f.write_str("#\"")?;
+
+        for (idx, byte) in self.iter().enumerate() {
+            match byte {
+                b'"' => f.write_str("\\\"")?,
+                b'\\' => f.write_str("\\\\")?,
+                b'\n' => f.write_str("\\n")?,
+                b'\t' => f.write_str("\\t")?,
+                b'\r' => f.write_str("\\r")?,
+                byte if byte.is_ascii_graphic() || *byte == b' ' => f.write_char(*byte as char)?,
+                // a shorter escape would take the following digit with it
+                byte if matches!(self.get(idx + 1), Some(b'0'..=b'7')) => write!(f, "\\{byte:03o}")?,
+                byte => write!(f, "\\{byte:o}")?
+            }
+        }
 
-        write!(f, "#\"{s}\"")
+        f.write_char('"')
     }
 }
 
@@ -3,6 +3,12 @@ pub mod reserved;
 pub mod syntax;
 pub mod composed;
 pub mod any;
+pub mod structs;
+pub mod sequence;
+pub mod promise;
+pub mod continuation;
+pub mod parameter;
+pub mod port;
 
 pub use data_types::*;
 pub use syntax::*;
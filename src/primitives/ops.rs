@@ -81,6 +81,7 @@ impl Div for ComparisonOperator {
 
     fn div(self, rhs: Self) -> Self::Output {
         Ok(match (self, rhs) {
+            (Self::Simple(_), Self::Simple(r)) if r.0 == 0.0 => return Err(NativeFnError::DivisionByZero),
             (Self::Simple(s), Self::Simple(r)) => Self::Simple(s/r),
             (Self::Complex(_), Self::Complex(_)) 
                 => return Err(NativeFnError::NotYetImplemented("Complex/rational division")),
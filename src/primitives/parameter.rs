@@ -0,0 +1,57 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::interpreter::any::AnyEval;
+use crate::interpreter::context::Context;
+use crate::interpreter::error::InterpreterError;
+use crate::native::error::NativeFnError;
+use crate::primitives::any::Any;
+
+/// Procedure holding a value that's dynamically rebound by `parameterize`. Clones share the same
+/// value, which is stored as static like global variables are.
+#[derive(Clone, Debug)]
+pub struct Parameter {
+    value: Rc<RefCell<Any<'static>>>,
+    /// Procedure applied to every value before storing it
+    guard: Option<Rc<Any<'static>>>
+}
+
+impl Parameter {
+    pub fn new(value: Any<'_>, guard: Option<Any<'_>>) -> Self {
+        Self {
+            value: Rc::new(RefCell::new(value.make_static())),
+            guard: guard.map(|g| Rc::new(g.make_static()))
+        }
+    }
+
+    pub fn get<'a>(&self) -> Any<'a> {
+        self.value.borrow().clone()
+    }
+
+    /// Stores the value, which must have gone through the guard already, returning the previous
+    /// one.
+    pub fn replace(&self, value: Any<'_>) -> Any<'static> {
+        self.value.replace(value.make_static())
+    }
+
+    pub fn guarded<'a>(&self, cx: &mut Context<'_, 'a>, value: Any<'a>) -> Result<Any<'a>, InterpreterError> {
+        match &self.guard {
+            Some(guard) => cx.apply(&(**guard).clone(), &[AnyEval::from_any(value)]),
+            None => Ok(value)
+        }
+    }
+
+    /// Reads the value when called without arguments and sets it when called with one.
+    pub fn apply<'a>(&self, cx: &mut Context<'_, 'a>, args: &[AnyEval<'a>]) -> Result<Any<'a>, InterpreterError> {
+        match args {
+            [] => Ok(self.get()),
+            [value] => {
+                let value = Any::from(value);
+                let value = self.guarded(cx, value)?;
+                self.replace(value);
+                Ok(Any::Void(()))
+            },
+            _ => Err(NativeFnError::ArityMismatch { expected: 1, got: args.len() as _ }.into())
+        }
+    }
+}
@@ -0,0 +1,236 @@
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::fmt::{self, Write};
+use std::fs::File;
+use std::io::{self, Write as _};
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+use crate::display::InterpreterDisplay;
+use crate::interpreter::Interpreter;
+
+thread_local! {
+    /// Lines read from the standard input but not consumed yet, shared by every stdin port.
+    static STDIN: Rc<RefCell<InputBuffer>> = Rc::default();
+}
+
+/// Text read by an input port, along with how much of it was consumed already.
+#[derive(Debug, Default)]
+pub struct InputBuffer {
+    pub text: String,
+    pub position: usize
+}
+
+impl InputBuffer {
+    pub fn remaining(&self) -> &str {
+        &self.text[self.position..]
+    }
+
+    pub fn peek_char(&self) -> Option<char> {
+        self.remaining().chars().next()
+    }
+
+    pub fn read_char(&mut self) -> Option<char> {
+        let c = self.peek_char()?;
+        self.position += c.len_utf8();
+        Some(c)
+    }
+
+    /// Reads up to the next line feed, which is consumed but not returned.
+    pub fn read_line(&mut self) -> Option<String> {
+        if self.remaining().is_empty() {
+            return None;
+        }
+
+        let rest = self.remaining();
+        let (line, consumed) = match rest.find('\n') {
+            Some(end) => (rest[..end].to_string(), end + 1),
+            None => (rest.to_string(), rest.len())
+        };
+
+        self.position += consumed;
+        Some(line)
+    }
+
+    /// Reads up to `count` characters.
+    pub fn read_string(&mut self, count: usize) -> Option<String> {
+        if self.remaining().is_empty() {
+            return None;
+        }
+
+        let out = self.remaining().chars().take(count).collect::<String>();
+        self.position += out.len();
+        Some(out)
+    }
+
+    /// Reads up to `count` bytes, stopping before them if they'd split a character.
+    pub fn read_bytes(&mut self, count: usize) -> Option<Vec<u8>> {
+        if self.remaining().is_empty() {
+            return None;
+        }
+
+        let mut end = (self.position + count).min(self.text.len());
+
+        while !self.text.is_char_boundary(end) {
+            end -= 1;
+        }
+
+        let out = self.text.as_bytes()[self.position..end].to_vec();
+        self.position = end;
+        Some(out)
+    }
+
+    /// Marks the given amount of bytes as consumed.
+    pub fn advance(&mut self, bytes: usize) {
+        self.position = (self.position + bytes).min(self.text.len());
+    }
+
+    fn push(&mut self, text: &str) {
+        self.text.drain(..self.position);
+        self.position = 0;
+        self.text.push_str(text);
+    }
+}
+
+/// Value returned by the reading procedures once an input port has nothing left.
+#[derive(Clone, Copy, Debug)]
+pub struct Eof;
+
+/// Port read or written by the I/O procedures. Clones of string ports share their contents.
+#[derive(Clone, Debug)]
+pub enum Port {
+    Stdin,
+    Stdout,
+    Stderr,
+    /// Bytes written to a string port, which is also a byte port like in Racket
+    OutputString(Rc<RefCell<Vec<u8>>>),
+    InputString(Rc<RefCell<InputBuffer>>),
+    /// File read whole when opened
+    InputFile {
+        path: Rc<PathBuf>,
+        buffer: Rc<RefCell<InputBuffer>>
+    },
+    OutputFile {
+        path: Rc<PathBuf>,
+        file: Rc<RefCell<File>>
+    }
+}
+
+impl Port {
+    pub fn output_string() -> Self {
+        Self::OutputString(Rc::new(RefCell::new(Vec::new())))
+    }
+
+    pub fn input_string(text: impl Into<String>) -> Self {
+        Self::InputString(Rc::new(RefCell::new(InputBuffer { text: text.into(), position: 0 })))
+    }
+
+    pub fn input_file(path: &Path) -> io::Result<Self> {
+        Ok(Self::InputFile {
+            path: Rc::new(path.to_path_buf()),
+            buffer: Rc::new(RefCell::new(InputBuffer { text: std::fs::read_to_string(path)?, position: 0 }))
+        })
+    }
+
+    pub fn output_file(path: &Path, file: File) -> Self {
+        Self::OutputFile { path: Rc::new(path.to_path_buf()), file: Rc::new(RefCell::new(file)) }
+    }
+
+    pub fn name(&self) -> Cow<'_, str> {
+        match self {
+            Self::Stdin => Cow::Borrowed("stdin"),
+            Self::Stdout => Cow::Borrowed("stdout"),
+            Self::Stderr => Cow::Borrowed("stderr"),
+            Self::OutputString(_) | Self::InputString(_) => Cow::Borrowed("string"),
+            Self::InputFile { path, .. } | Self::OutputFile { path, .. } => path.to_string_lossy()
+        }
+    }
+
+    pub fn is_input(&self) -> bool {
+        matches!(self, Self::Stdin | Self::InputString(_) | Self::InputFile { .. })
+    }
+
+    pub fn is_output(&self) -> bool {
+        !self.is_input()
+    }
+
+    /// Everything written so far to an output string port, decoded as UTF-8.
+    pub fn contents(&self) -> Option<String> {
+        self.contents_bytes().map(|bytes| String::from_utf8_lossy(&bytes).into_owned())
+    }
+
+    /// Everything written so far to an output string port.
+    pub fn contents_bytes(&self) -> Option<Vec<u8>> {
+        match self {
+            Self::OutputString(out) => Some(out.borrow().clone()),
+            _ => None
+        }
+    }
+
+    /// Reads from an input port with `read`, pulling lines from the standard input first until
+    /// `ready` accepts the pending text or the input ends.
+    pub fn read_with<T>(
+        &self,
+        ready: impl Fn(&str) -> bool,
+        read: impl FnOnce(&mut InputBuffer) -> T
+    ) -> io::Result<T> {
+        let buffer = match self {
+            Self::Stdin => STDIN.with(Rc::clone),
+            Self::InputString(buffer) | Self::InputFile { buffer, .. } => Rc::clone(buffer),
+            _ => return Err(io::Error::new(io::ErrorKind::Unsupported, "can't read from an output port"))
+        };
+
+        let mut buffer = buffer.borrow_mut();
+
+        if matches!(self, Self::Stdin) {
+            while !ready(buffer.remaining()) {
+                let mut line = String::new();
+
+                if io::stdin().read_line(&mut line)? == 0 {
+                    break;
+                }
+
+                buffer.push(&line);
+            }
+        }
+
+        Ok(read(&mut buffer))
+    }
+
+    /// Reads a line from an input port, without its line terminator.
+    pub fn read_line(&self) -> io::Result<Option<String>> {
+        self.read_with(|s| s.contains('\n'), |buffer| buffer.read_line()
+            .map(|line| line.strip_suffix('\r').map(String::from).unwrap_or(line)))
+    }
+
+    pub fn write_str(&self, s: &str) -> io::Result<()> {
+        self.write_bytes(s.as_bytes())
+    }
+
+    pub fn write_bytes(&self, bytes: &[u8]) -> io::Result<()> {
+        match self {
+            Self::Stdout => {
+                let mut stdout = io::stdout();
+                stdout.write_all(bytes)?;
+                stdout.flush()
+            },
+            Self::Stderr => io::stderr().write_all(bytes),
+            Self::OutputString(out) => {
+                out.borrow_mut().extend_from_slice(bytes);
+                Ok(())
+            },
+            Self::OutputFile { file, .. } => file.borrow_mut().write_all(bytes),
+            Self::Stdin | Self::InputString(_) | Self::InputFile { .. } => Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "can't write to an input port"
+            ))
+        }
+    }
+}
+
+impl InterpreterDisplay for Port {
+    fn fmt(&self, f: &mut dyn Write, _: &Interpreter<'_>) -> fmt::Result {
+        let direction = if self.is_input() { "input" } else { "output" };
+        write!(f, "#<{direction}-port:{}>", self.name())
+    }
+}
@@ -0,0 +1,143 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::cell::Cell;
+use crate::interpreter::any::AnyEval;
+use crate::interpreter::context::Context;
+use crate::interpreter::error::InterpreterError;
+use crate::interpreter::vars::VarsStorage;
+use crate::native::error_value;
+use crate::primitives::any::Any;
+
+/// State of a promise, stored as static since it's shared by every copy of the promise, which
+/// may outlive the source it was created from, like global variables do.
+#[derive(Debug)]
+pub enum PromiseState {
+    /// Expression waiting to be evaluated, along with the local variables visible where it was
+    /// delayed
+    Delayed {
+        expr: AnyEval<'static>,
+        locals: VarsStorage<'static>,
+        /// Whether the expression produces another promise that must be forced too, like the
+        /// ones created by `delay/force` and `lazy`
+        chained: bool
+    },
+    Running,
+    Forced(Any<'static>),
+    /// Exception raised by the expression, raised again every time the promise is forced
+    Raised {
+        value: Any<'static>,
+        message: String
+    },
+    /// State of the chained promise that took over this one, when it was produced by its body
+    Shared(Promise)
+}
+
+/// Delayed evaluation of an expression, which is evaluated at most once, the first time the
+/// promise is forced. Clones share the same state.
+#[derive(Clone, Debug)]
+pub struct Promise(Rc<RefCell<PromiseState>>);
+
+impl Promise {
+    pub fn delay<'a>(cx: &Context<'_, 'a>, expr: AnyEval<'a>) -> Self {
+        Self::delayed(cx, expr, false)
+    }
+
+    pub fn delay_force<'a>(cx: &Context<'_, 'a>, expr: AnyEval<'a>) -> Self {
+        Self::delayed(cx, expr, true)
+    }
+
+    fn delayed<'a>(cx: &Context<'_, 'a>, expr: AnyEval<'a>, chained: bool) -> Self {
+        Self(Rc::new(RefCell::new(PromiseState::Delayed {
+            expr: expr.make_static(),
+            locals: cx.local_vars().clone().make_static(),
+            chained
+        })))
+    }
+
+    pub fn forced(value: Any<'_>) -> Self {
+        Self(Rc::new(RefCell::new(PromiseState::Forced(value.make_static()))))
+    }
+
+    pub fn is_forced(&self) -> bool {
+        matches!(*self.resolved().0.borrow(), PromiseState::Forced(_) | PromiseState::Raised { .. })
+    }
+
+    /// The promise holding the state of this one, following the promises it was taken over by.
+    fn resolved(&self) -> Promise {
+        let mut promise = self.clone();
+
+        loop {
+            let next = match &*promise.0.borrow() {
+                PromiseState::Shared(next) => next.clone(),
+                _ => return promise.clone()
+            };
+
+            promise = next;
+        }
+    }
+
+    /// Forces the promise, along with the promises its body produces if it's chained. Rather than
+    /// forcing those recursively, the promise takes over their state and is forced again, so
+    /// chains of `delay/force` run in constant space.
+    pub fn force<'a>(&self, cx: &Context<'_, 'a>) -> Result<Any<'a>, InterpreterError> {
+        let promise = self.resolved();
+
+        loop {
+            let state = std::mem::replace(&mut *promise.0.borrow_mut(), PromiseState::Running);
+
+            let (expr, locals, chained) = match state {
+                PromiseState::Delayed { expr, locals, chained } => (expr, locals, chained),
+                PromiseState::Forced(value) => {
+                    *promise.0.borrow_mut() = PromiseState::Forced(value.clone());
+                    return Ok(value);
+                },
+                PromiseState::Raised { value, message } => {
+                    *promise.0.borrow_mut() = PromiseState::Raised { value: value.clone(), message: message.clone() };
+                    return Err(InterpreterError::Raised { value, message });
+                },
+                PromiseState::Running | PromiseState::Shared(_) => return Err(reentrant())
+            };
+
+            let scope = VarsStorage {
+                table: locals.table.iter()
+                    .map(|(k, v)| (k.clone(), Cell::new((**v).clone())))
+                    .collect()
+            };
+
+            let value = match cx.with_locals(scope).eval(&expr) {
+                Ok(value) => value,
+                Err(error) => {
+                    // continuation jumps and syntax errors aren't exceptions, so they aren't kept
+                    *promise.0.borrow_mut() = match error_value(&error) {
+                        Some(value) => PromiseState::Raised { value, message: error.message() },
+                        None => PromiseState::Delayed { expr, locals, chained }
+                    };
+
+                    return Err(error);
+                }
+            };
+
+            let inner = match value.get_composed().and_then(|c| c.get_promise()) {
+                Some(inner) if chained => inner.resolved(),
+                _ => {
+                    *promise.0.borrow_mut() = PromiseState::Forced(value.clone().make_static());
+                    return Ok(value);
+                }
+            };
+
+            // the inner promise is being forced already, which includes being this one
+            if matches!(*inner.0.borrow(), PromiseState::Running) {
+                *promise.0.borrow_mut() = PromiseState::Delayed { expr, locals, chained };
+                return Err(reentrant());
+            }
+
+            let state = std::mem::replace(&mut *inner.0.borrow_mut(), PromiseState::Shared(promise.clone()));
+            *promise.0.borrow_mut() = state;
+        }
+    }
+}
+
+fn reentrant() -> InterpreterError {
+    InterpreterError::Runtime("force: reentrant promise".to_string())
+}
@@ -4,12 +4,16 @@ enum_from_str!{
     pub enum ReservedWords {
         If = "if",
         Cond = "cond",
+        Case = "case",
+        Match = "match",
         Else = "else",
+        Do = "do",
         For = "for",
         ForAsterisk = "for*",
         ForList = "for/list",
         Let = "let",
         LetAsterisk = "let*",
+        Letrec = "letrec",
         Define = "define",
         Lambda = "lambda",
         When = "when",
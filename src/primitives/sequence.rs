@@ -0,0 +1,78 @@
+use crate::primitives::any::Any;
+use crate::primitives::port::Port;
+use crate::primitives::promise::Promise;
+
+/// Sequences that are produced on demand instead of being stored, like the ones returned by
+/// `in-range` and `in-naturals`, or lazy streams.
+#[derive(Clone, Debug)]
+pub enum Sequence<'a> {
+    Range {
+        start: i32,
+        /// `None` for sequences without end, like `in-naturals`
+        end: Option<i32>,
+        step: i32
+    },
+    /// Ranges with a bound or step that isn't an integer, like `(in-range 0 1 0.25)`
+    RealRange {
+        start: f64,
+        end: f64,
+        step: f64
+    },
+    /// Lines read from an input port
+    Lines(Port),
+    Stream(Stream<'a>)
+}
+
+/// Lazy streams, the sources of the adapters can be any sequence.
+#[derive(Clone, Debug)]
+pub enum Stream<'a> {
+    Empty,
+    Cons {
+        first: Promise,
+        rest: Promise
+    },
+    Map {
+        procedure: Any<'a>,
+        source: Any<'a>
+    },
+    Filter {
+        procedure: Any<'a>,
+        source: Any<'a>
+    },
+    Take {
+        count: usize,
+        source: Any<'a>
+    }
+}
+
+impl Sequence<'_> {
+    pub fn make_static(self) -> Sequence<'static> {
+        match self {
+            Self::Range { start, end, step } => Sequence::Range { start, end, step },
+            Self::RealRange { start, end, step } => Sequence::RealRange { start, end, step },
+            Self::Lines(port) => Sequence::Lines(port),
+            Self::Stream(s) => Sequence::Stream(s.make_static())
+        }
+    }
+}
+
+impl Stream<'_> {
+    pub fn make_static(self) -> Stream<'static> {
+        match self {
+            Self::Empty => Stream::Empty,
+            Self::Cons { first, rest } => Stream::Cons { first, rest },
+            Self::Map { procedure, source } => Stream::Map {
+                procedure: procedure.make_static(),
+                source: source.make_static()
+            },
+            Self::Filter { procedure, source } => Stream::Filter {
+                procedure: procedure.make_static(),
+                source: source.make_static()
+            },
+            Self::Take { count, source } => Stream::Take {
+                count,
+                source: source.make_static()
+            }
+        }
+    }
+}
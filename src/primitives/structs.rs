@@ -0,0 +1,167 @@
+use std::fmt::{self, Write};
+use std::sync::Arc;
+
+use crate::display::InterpreterDisplay;
+use crate::interpreter::error::InterpreterError;
+use crate::interpreter::Interpreter;
+use crate::native::error::{DeclaredFunctionError, NativeFnError};
+use crate::primitives::any::Any;
+use crate::primitives::composed::Composed;
+use crate::primitives::DataType;
+
+/// Type created by a `struct` definition, shared by all of its instances and procedures.
+#[derive(Debug)]
+pub struct StructType {
+    pub name: String,
+    /// Fields declared by this type, without the ones inherited from the parent
+    pub fields: Vec<String>,
+    pub parent: Option<Arc<StructType>>,
+    pub transparent: bool
+}
+
+/// Instance of a struct type, with the inherited fields first.
+#[derive(Clone, Debug)]
+pub struct Struct<'a> {
+    pub kind: Arc<StructType>,
+    pub fields: Vec<Any<'a>>
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum StructOp {
+    Constructor,
+    Predicate,
+    Accessor(usize)
+}
+
+/// Procedures generated by a `struct` definition.
+#[derive(Clone, Debug)]
+pub struct StructProcedure {
+    pub name: String,
+    pub kind: Arc<StructType>,
+    pub op: StructOp
+}
+
+impl StructType {
+    pub fn field_count(&self) -> usize {
+        self.parent.as_ref().map(|p| p.field_count()).unwrap_or(0) + self.fields.len()
+    }
+
+    /// Whether this type is the given one or one of its subtypes.
+    pub fn is_a(&self, other: &StructType) -> bool {
+        if std::ptr::eq(self, other) {
+            return true;
+        }
+
+        self.parent.as_ref().map(|p| p.is_a(other)).unwrap_or(false)
+    }
+
+    pub fn procedures(self: &Arc<Self>) -> Vec<StructProcedure> {
+        let mut out = vec![
+            StructProcedure {
+                name: self.name.clone(),
+                kind: Arc::clone(self),
+                op: StructOp::Constructor
+            },
+            StructProcedure {
+                name: format!("{}?", self.name),
+                kind: Arc::clone(self),
+                op: StructOp::Predicate
+            }
+        ];
+
+        let offset = self.field_count() - self.fields.len();
+
+        for (idx, field) in self.fields.iter().enumerate() {
+            out.push(StructProcedure {
+                name: format!("{}-{}", self.name, field),
+                kind: Arc::clone(self),
+                op: StructOp::Accessor(offset + idx)
+            });
+        }
+
+        out
+    }
+}
+
+impl<'a> Struct<'a> {
+    pub fn make_static(self) -> Struct<'static> {
+        Struct {
+            kind: self.kind,
+            fields: self.fields.into_iter().map(Any::make_static).collect()
+        }
+    }
+}
+
+impl StructProcedure {
+    pub fn arity(&self) -> u8 {
+        match self.op {
+            StructOp::Constructor => self.kind.field_count() as _,
+            _ => 1
+        }
+    }
+
+    pub fn call<'a>(&self, mut args: Vec<Any<'a>>) -> Result<Any<'a>, InterpreterError> {
+        if args.len() != self.arity() as usize {
+            return Err(DeclaredFunctionError::ArityMismatch {
+                expected: self.arity(),
+                got: args.len() as _
+            }.into());
+        }
+
+        let instance = args.first()
+            .and_then(|a| a.get_composed())
+            .and_then(|c| c.get_struct())
+            .filter(|s| s.kind.is_a(&self.kind));
+
+        Ok(match self.op {
+            StructOp::Constructor => Any::Composed(Box::new(Composed::Struct(Struct {
+                kind: Arc::clone(&self.kind),
+                fields: args
+            }))),
+            StructOp::Predicate => Any::Primitive(DataType::Boolean(instance.is_some())),
+            StructOp::Accessor(idx) => {
+                if instance.is_none() {
+                    return Err(NativeFnError::UnexpectedType {
+                        function: "struct accessor",
+                        argument_position: 1,
+                        got: args[0].variant_name(),
+                        expected: "instance of the struct type"
+                    }.into());
+                }
+
+                let Any::Composed(c) = args.swap_remove(0) else { unreachable!() };
+                let Composed::Struct(mut s) = *c else { unreachable!() };
+                s.fields.swap_remove(idx)
+            }
+        })
+    }
+}
+
+impl InterpreterDisplay for Struct<'_> {
+    fn fmt(&self, f: &mut dyn Write, interpreter: &Interpreter<'_>) -> fmt::Result {
+        if !self.kind.transparent {
+            return write!(f, "#<{}>", self.kind.name);
+        }
+
+        write!(f, "({}", self.kind.name)?;
+
+        for field in &self.fields {
+            write!(f, " ")?;
+            field.fmt(f, interpreter)?;
+        }
+
+        write!(f, ")")
+    }
+}
+
+impl InterpreterDisplay for StructProcedure {
+    fn fmt(&self, f: &mut dyn Write, _: &Interpreter<'_>) -> fmt::Result {
+        write!(f, "#<procedure:{}>", self.name)
+    }
+}
+
+impl InterpreterDisplay for Arc<StructType> {
+    fn fmt(&self, f: &mut dyn Write, _: &Interpreter<'_>) -> fmt::Result {
+        write!(f, "#<struct-type:{}>", self.name)
+    }
+}
@@ -0,0 +1,368 @@
+use std::iter::{Enumerate, Peekable};
+use std::vec::IntoIter;
+
+use crate::ast::expr::{Expr, Shape};
+use crate::ast::{AstError, AstErrors};
+use crate::lexer::{LocatedToken, Token};
+use crate::native::hash_insert;
+use crate::primitives::any::Any;
+use crate::primitives::composed::{Composed, HashTable, List, Symbol, Vector};
+use crate::span::{Located, Span};
+
+pub(crate) fn closing(token: &Token<'_>) -> Option<char> {
+    match token {
+        Token::CloseParen => Some(')'),
+        Token::CloseBracket => Some(']'),
+        Token::CloseBraces => Some('}'),
+        _ => None
+    }
+}
+
+/// Indices of the tokens where reading resumes after forms that are never closed, which are
+/// the first forms opened at the first column inside them, since top level forms usually start
+/// there.
+fn resync_points(tokens: &[LocatedToken<'_>]) -> Vec<usize> {
+    let mut points = Vec::new();
+    let mut start = 0;
+
+    loop {
+        let mut depth = 0usize;
+        let mut candidate = None;
+
+        for (idx, located) in tokens.iter().enumerate().skip(start) {
+            if Shape::opened_by(&located.token).is_some() {
+                if depth > 0 && located.span.column == 1 && candidate.is_none() {
+                    candidate = Some(idx);
+                }
+
+                depth += 1;
+            } else if closing(&located.token).is_some() {
+                depth = depth.saturating_sub(1);
+
+                if depth == 0 {
+                    candidate = None;
+                }
+            }
+        }
+
+        match candidate {
+            Some(idx) if depth > 0 => {
+                points.push(idx);
+                start = idx;
+            },
+            _ => return points
+        }
+    }
+}
+
+/// The form opened at the span wasn't closed before the end of the source or the next resync
+/// point.
+pub(crate) struct Unclosed(pub(crate) Span);
+
+/// Turns tokens into data, the way `read` and quoted forms see them, keeping going after syntax
+/// errors and skipping to the next top level form when a form is never closed.
+///
+/// The code parser reads its forms from the same tokens, handing quoted forms and literals over
+/// to the reader. Code isn't read into data before the expander gets it, since data has no
+/// spans for diagnostics and backtraces to point at.
+pub struct Reader<'a> {
+    tokens: Peekable<Enumerate<IntoIter<LocatedToken<'a>>>>,
+    resync: Vec<usize>,
+    errors: Vec<Located<AstError>>
+}
+
+impl<'a> Reader<'a> {
+    pub fn new(tokens: Vec<LocatedToken<'a>>) -> Self {
+        Self {
+            resync: resync_points(&tokens),
+            tokens: tokens.into_iter().enumerate().peekable(),
+            errors: Vec::new()
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.tokens.len()
+    }
+
+    pub(crate) fn error(&mut self, span: &Span, error: AstError) {
+        self.errors.push(Located { span: span.clone(), error });
+    }
+
+    /// Next token that isn't a comment, without consuming it.
+    fn peek(&mut self) -> Option<&LocatedToken<'a>> {
+        while self.tokens.next_if(|(_, t)| matches!(t.token, Token::Comment)).is_some() {}
+        self.tokens.peek().map(|(_, t)| t)
+    }
+
+    fn next(&mut self) -> Option<LocatedToken<'a>> {
+        self.peek()?;
+        self.tokens.next().map(|(_, t)| t)
+    }
+
+    /// Next token of the form opened at `open`, which ends at the end of the source or the next
+    /// resync point.
+    pub(crate) fn next_in_form(&mut self, open: &Span) -> Result<LocatedToken<'a>, Unclosed> {
+        self.peek();
+
+        let Some((idx, _)) = self.tokens.peek() else {
+            return Err(Unclosed(open.clone()));
+        };
+
+        if self.resync.binary_search(idx).is_ok() {
+            return Err(Unclosed(open.clone()));
+        }
+
+        Ok(self.next().unwrap())
+    }
+
+    /// Whether `token` closes the form opened by `open`, reporting it if it's a closing
+    /// delimiter that doesn't match.
+    pub(crate) fn closes(&mut self, open: &LocatedToken<'a>, token: &LocatedToken<'a>) -> bool {
+        let Some(found) = closing(&token.token) else { return false; };
+        let expected = Shape::opened_by(&open.token).unwrap().close();
+
+        if found != expected {
+            self.error(&token.span, AstError::MismatchedDelimiter {
+                expected,
+                found,
+                opened: open.span.clone()
+            });
+        }
+
+        true
+    }
+
+    /// Reads every top level item with `item`, reporting the closing delimiters and dots found
+    /// outside of any form and the forms that are never closed.
+    pub(crate) fn top_level<T>(
+        &mut self,
+        mut item: impl FnMut(&mut Self, LocatedToken<'a>) -> Result<Option<(T, Span)>, Unclosed>
+    ) -> Vec<(T, Span)> {
+        // preallocate a fourth of the amount of tokens, this is an arbitrary measure
+        let mut items = Vec::with_capacity(self.len() / 4);
+
+        while let Some(token) = self.next() {
+            let span = token.span.clone();
+
+            if let Some(found) = closing(&token.token) {
+                self.error(&span, AstError::UnexpectedClosingDelimiter(found));
+                continue;
+            }
+
+            if matches!(token.token, Token::Dot) {
+                self.error(&span, AstError::InvalidExpression);
+                continue;
+            }
+
+            match item(self, token) {
+                Ok(Some(item)) => items.push(item),
+                Ok(None) => (),
+                Err(Unclosed(open)) => self.error(&open, AstError::MissingClosingDelimiter)
+            }
+        }
+
+        items
+    }
+
+    /// Every error found so far, if any, in the order they appear in the source. Forms that are
+    /// never closed are only found to be after the errors inside them.
+    pub(crate) fn finish(mut self) -> Result<(), AstErrors> {
+        self.errors.sort_by_key(|error| error.span.range.start);
+
+        match self.errors.is_empty() {
+            true => Ok(()),
+            false => Err(AstErrors(self.errors))
+        }
+    }
+
+    /// Reads every datum of the tokens.
+    pub fn read_all(mut self) -> Result<Vec<Any<'a>>, AstErrors> {
+        let data = self.top_level(Self::read_item);
+        self.finish()?;
+
+        Ok(data.into_iter().map(|(datum, _)| datum).collect())
+    }
+
+    /// Reads the datum after the prefix at `prefix`, like a quote, reporting `dangling` if
+    /// there's none.
+    pub(crate) fn read_prefixed(
+        &mut self,
+        prefix: &Span,
+        dangling: AstError
+    ) -> Result<Option<(Any<'a>, Span)>, Unclosed> {
+        let ends_form = self.peek().map(|t| closing(&t.token).is_some()).unwrap_or(true);
+
+        if ends_form {
+            self.error(prefix, dangling);
+            return Ok(None);
+        }
+
+        let token = self.next_in_form(prefix)?;
+
+        if matches!(token.token, Token::Dot) {
+            self.error(&token.span, AstError::InvalidExpression);
+            return Ok(None);
+        }
+
+        Ok(self.read_item(token)?.map(|(datum, end)| (datum, prefix.to(&end))))
+    }
+
+    /// Reads the datum starting at `token`, which isn't a closing delimiter.
+    pub(crate) fn read_item(&mut self, token: LocatedToken<'a>) -> Result<Option<(Any<'a>, Span)>, Unclosed> {
+        Ok(match &token.token {
+            Token::OpenParen | Token::OpenBracket | Token::OpenBraces => {
+                let (items, tail, span) = self.read_items(token)?;
+                let list = items.into_iter()
+                    .rev()
+                    .fold(tail.unwrap_or_else(|| list([])), |tail, (item, _)| Any::cons(item, tail));
+
+                Some((list, span))
+            },
+            Token::OpenVector => {
+                let (items, span) = self.read_sequence(token)?;
+                let items = items.into_iter().map(|(item, _)| item).collect();
+
+                Some((composed(Composed::Vector(Vector(items))), span))
+            },
+            Token::OpenHash => {
+                let (entries, span) = self.read_sequence(token)?;
+                let mut table = HashTable(Vec::with_capacity(entries.len()));
+
+                for (entry, entry_span) in entries {
+                    match hash_entry(entry) {
+                        Some((key, value)) => hash_insert(&mut table, key, value),
+                        None => self.error(&entry_span, AstError::InvalidHashEntry)
+                    }
+                }
+
+                Some((composed(Composed::Hash(table)), span))
+            },
+            Token::SingleQuote => self.read_prefixed(&token.span, AstError::DanglingQuote)?
+                .map(|(datum, span)| (list([composed(Composed::Symbol(Symbol("quote"))), datum]), span)),
+            Token::BoxPrefix => self.read_prefixed(&token.span, AstError::DanglingBox)?
+                .map(|(datum, span)| (composed(Composed::Boxed(Box::new(datum))), span)),
+            _ => match token.token {
+                Token::Primitive(p) => Some((Any::Primitive(p), token.span)),
+                Token::Ident(ident) => Some((composed(Composed::Symbol(Symbol(ident))), token.span)),
+                _ => None
+            }
+        })
+    }
+
+    /// Reads the items of a vector or hash table literal, which can't have a dot.
+    fn read_sequence(&mut self, open: LocatedToken<'a>) -> Result<(Vec<(Any<'a>, Span)>, Span), Unclosed> {
+        let (items, tail, span) = self.read_items(open)?;
+
+        if tail.is_some() {
+            self.error(&span, AstError::InvalidExpression);
+        }
+
+        Ok((items, span))
+    }
+
+    /// Reads the items of the form opened by `open` up to its closing delimiter, along with the
+    /// datum after a dot, like `c` in `(a b . c)`.
+    #[allow(clippy::type_complexity)]
+    fn read_items(
+        &mut self,
+        open: LocatedToken<'a>
+    ) -> Result<(Vec<(Any<'a>, Span)>, Option<Any<'a>>, Span), Unclosed> {
+        let mut items = Vec::new();
+        let mut tail = None;
+
+        loop {
+            let token = self.next_in_form(&open.span)?;
+
+            if self.closes(&open, &token) {
+                return Ok((items, tail, open.span.to(&token.span)));
+            }
+
+            // a dot has to be between the items and a single datum at the end
+            if matches!(token.token, Token::Dot) {
+                if items.is_empty() || tail.is_some() {
+                    self.error(&token.span, AstError::InvalidExpression);
+                    continue;
+                }
+
+                tail = self.read_prefixed(&token.span, AstError::InvalidExpression)?
+                    .map(|(datum, _)| datum);
+                continue;
+            }
+
+            let span = token.span.clone();
+
+            if let Some(item) = self.read_item(token)? {
+                match tail {
+                    Some(_) => self.error(&span, AstError::InvalidExpression),
+                    None => items.push(item)
+                }
+            }
+        }
+    }
+}
+
+fn composed(value: Composed<'_>) -> Any<'_> {
+    Any::Composed(Box::new(value))
+}
+
+fn list<'a>(items: impl IntoIterator<Item = Any<'a>>) -> Any<'a> {
+    composed(Composed::List(List(items.into_iter().collect())))
+}
+
+/// Key and value of an entry of a hash table literal, which is a pair like `(key . value)`.
+fn hash_entry(entry: Any<'_>) -> Option<(Any<'_>, Any<'_>)> {
+    let Any::Composed(entry) = entry else { return None; };
+
+    match *entry {
+        Composed::Pair(pair) => Some((pair.left, pair.right)),
+        Composed::List(List(mut items)) => {
+            let key = items.pop_front()?;
+            Some((key, list(items)))
+        },
+        _ => None
+    }
+}
+
+/// Data quoting `expr` means, for quoted forms the code parser already read, like the ones of
+/// `(quote datum)`.
+pub fn datum(expr: Expr<'_>) -> Any<'_> {
+    match expr {
+        Expr::Primitive(p) => Any::Primitive(p),
+        Expr::Ident(ident) => composed(Composed::Symbol(Symbol(ident))),
+        Expr::Parenthesized(tree) => list(tree.into_vec().into_iter().map(datum)),
+        Expr::Datum(datum) => *datum
+    }
+}
+
+#[test]
+fn test_reader() {
+    use crate::interpreter::eval_source;
+    use crate::lexer::Lexer;
+
+    let read = |source| Reader::new(Lexer::new(source).parse().unwrap()).read_all();
+
+    assert_eq!(read("(1 2 3) a \"s\" #\\x #t ; comment\n #(1) #&b").unwrap().len(), 7);
+    assert_eq!(
+        eval_source("(list '(1 (2 \"three\") . (4)) 'sym '#(a #(b)) '(a . b) ''q '() #hash((a . 1) (b 2)) #&(x))").unwrap(),
+        "'((1 (2 \"three\") 4) sym #(a #(b)) (a . b) 'q () #hash((a . 1) (b 2)) #&(x))"
+    );
+    assert_eq!(eval_source("(list (eq? 'a (string->symbol \"a\")) (equal? '(1 #(2)) (list 1 (vector 2))) (unbox #&5))").unwrap(), "'(#t #t 5)");
+    assert_eq!(eval_source("(read (open-input-string \"#hash((k . (v))) rest\"))").unwrap(), "'#hash((k v))");
+
+    assert_eq!(read("(a . b c) (. a) #hash(1) '#&)").unwrap_err().to_string(), "\
+1:8: Invalid expression
+1:12: Invalid expression
+1:23: Expected a key and a value in a hash table entry
+1:27: Expected a datum after `#&`
+1:29: Unexpected `)`");
+
+    // unclosed forms are reported where they're opened, and reading goes on after them
+    let mut reader = Reader::new(Lexer::new("(define x 1)\n'\n(+ 1 [2 3)\n{a b}").parse().unwrap());
+    let spans = reader.top_level(Reader::read_item).into_iter().map(|(_, span)| span.to_string()).collect::<Vec<_>>();
+    assert_eq!(spans, vec!["1:1", "4:1"]);
+    assert_eq!(reader.finish().unwrap_err().to_string(), "\
+3:1: Missing closing delimiter
+3:10: mismatched delimiter: expected ] but found ) (opened at 3:6)");
+    assert_eq!(read("'(a (b c)").unwrap_err().to_string(), "1:2: Missing closing delimiter");
+}
+
@@ -0,0 +1,45 @@
+use std::fmt;
+use std::ops::Range;
+use std::sync::Arc;
+
+use thiserror::Error;
+
+/// Where a token or form was read from, lines and columns start at 1 and columns count
+/// characters.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Span {
+    /// File the source was read from, `None` for sources like the REPL
+    pub file: Option<Arc<str>>,
+    pub line: u32,
+    pub column: u32,
+    /// Bytes of the source covered
+    pub range: Range<usize>
+}
+
+impl Span {
+    /// Span starting at `self` and ending where `end` ends.
+    pub fn to(&self, end: &Span) -> Span {
+        Span {
+            range: self.range.start..end.range.end,
+            ..self.clone()
+        }
+    }
+}
+
+impl fmt::Display for Span {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(file) = &self.file {
+            write!(f, "{file}:")?;
+        }
+
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
+/// Error along with the span of the form that caused it.
+#[derive(Debug, Error)]
+#[error("{span}: {error}")]
+pub struct Located<E: std::error::Error> {
+    pub span: Span,
+    pub error: E
+}